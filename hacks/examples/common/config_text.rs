@@ -0,0 +1,107 @@
+// The tiny TOML subset read by the config examples: comments, an optional
+// [server] table, and `key = value` with quoted strings or integers.
+// m5_builder_config and m5_builder_reload both read their files through
+// parse_server_table, via `#[path = "common/config_text.rs"] mod config_text;`.
+// This directory has no main.rs, so Cargo does not treat it as an example.
+
+use std::collections::HashMap;
+use std::fmt;
+
+// Drop everything after a `#` that is not inside a quoted string
+pub(crate) fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+// A TOML value is either "a quoted string" or a bare integer.
+// Unbalanced or embedded quotes are rejected.
+pub(crate) fn parse_toml_value(value: &str) -> Option<String> {
+    if let Some(inner) = value.strip_prefix('"') {
+        let inner = inner.strip_suffix('"')?;
+        if inner.contains('"') {
+            return None;
+        }
+        return Some(inner.to_string());
+    }
+    let digits = value.replace('_', ""); // TOML allows 1_000
+    if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+        Some(digits)
+    } else {
+        None
+    }
+}
+
+// One `key = value` line from the [server] table; line numbers are 1-based
+#[derive(Debug, PartialEq)]
+pub(crate) struct Setting {
+    pub(crate) line: usize,
+    pub(crate) key: String,
+    pub(crate) value: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum TomlError {
+    Syntax { line: usize, message: String },
+    DuplicateKey { line: usize, key: String, first_line: usize },
+}
+
+impl fmt::Display for TomlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TomlError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+            TomlError::DuplicateKey { line, key, first_line } => {
+                write!(f, "line {}: '{}' was already set on line {}", line, key, first_line)
+            }
+        }
+    }
+}
+
+// The settings of the [server] table, in file order. Lines before any
+// table header belong to it too; other tables are skipped. TOML forbids
+// setting a key twice, so a repeated key is an error.
+pub(crate) fn parse_server_table(text: &str) -> Result<Vec<Setting>, TomlError> {
+    let mut settings = Vec::new();
+    let mut in_server_table = true;
+    // Where each key was first set
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for (index, raw) in text.lines().enumerate() {
+        let line = index + 1;
+        let syntax = |message: String| TomlError::Syntax { line, message };
+        let trimmed = strip_comment(raw).trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if trimmed.starts_with('[') {
+            if !trimmed.ends_with(']') {
+                return Err(syntax(String::from("unterminated table header")));
+            }
+            in_server_table = trimmed[1..trimmed.len() - 1].trim() == "server";
+            continue;
+        }
+        if !in_server_table {
+            continue;
+        }
+
+        let (key, value) = trimmed
+            .split_once('=')
+            .ok_or_else(|| syntax(String::from("expected `key = value`")))?;
+        let value = parse_toml_value(value.trim())
+            .ok_or_else(|| syntax(format!("cannot parse value `{}`", value.trim())))?;
+        let key = key.trim().to_string();
+        if let Some(&first_line) = seen.get(&key) {
+            return Err(TomlError::DuplicateKey { line, key, first_line });
+        }
+        seen.insert(key.clone(), line);
+        settings.push(Setting { line, key, value });
+    }
+    Ok(settings)
+}
@@ -0,0 +1,403 @@
+// PBKDF2-HMAC-SHA256 password credentials, stored as PHC strings.
+// Explained in m5_structs_password. The User in common/users.rs carries
+// an `Option<Credential>`, so users.rs includes this file as its
+// `credential` submodule.
+
+#![allow(dead_code)] // each example uses a different subset
+
+use std::fmt;
+use std::io::{self, Read};
+
+// ---------------------------------------------------------------------
+// SHA-256 (FIPS 180-4)
+// ---------------------------------------------------------------------
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const INITIAL_STATE: [u32; 8] =
+    [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+
+// Cloneable mid-way, which HMAC uses to skip re-hashing its key pads
+#[derive(Clone)]
+pub(crate) struct Sha256 {
+    state: [u32; 8],
+    block: [u8; 64],
+    filled: usize, // bytes waiting in `block`
+    length: u64,   // total bytes hashed
+}
+
+impl Sha256 {
+    pub(crate) fn new() -> Self {
+        Sha256 { state: INITIAL_STATE, block: [0; 64], filled: 0, length: 0 }
+    }
+
+    pub(crate) fn digest(data: &[u8]) -> [u8; 32] {
+        let mut sha = Sha256::new();
+        sha.update(data);
+        sha.finish()
+    }
+
+    pub(crate) fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u64;
+        while !data.is_empty() {
+            let take = (64 - self.filled).min(data.len());
+            self.block[self.filled..self.filled + take].copy_from_slice(&data[..take]);
+            self.filled += take;
+            data = &data[take..];
+            if self.filled == 64 {
+                self.compress();
+                self.filled = 0;
+            }
+        }
+    }
+
+    // Pad with 0x80, zeros, then the length in bits
+    pub(crate) fn finish(mut self) -> [u8; 32] {
+        let bits = self.length * 8;
+        let mut padding = [0u8; 64];
+        padding[0] = 0x80;
+        // Enough to leave exactly 8 bytes free in the last block
+        let len = if self.filled < 56 { 56 - self.filled } else { 120 - self.filled };
+        self.update(&padding[..len]);
+        self.update(&bits.to_be_bytes());
+
+        let mut out = [0; 32];
+        for (chunk, word) in out.chunks_exact_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        out
+    }
+
+    fn compress(&mut self) {
+        let mut w = [0u32; 64];
+        for (i, chunk) in self.block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (word, add) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(add);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------
+// HMAC (RFC 2104) and PBKDF2 (RFC 8018)
+// ---------------------------------------------------------------------
+
+// The inner and outer hashes with the key pads already absorbed. PBKDF2
+// runs HMAC with one key many thousand times, so this halves the work.
+#[derive(Clone)]
+pub(crate) struct HmacSha256 {
+    inner: Sha256,
+    outer: Sha256,
+}
+
+impl HmacSha256 {
+    pub(crate) fn new(key: &[u8]) -> Self {
+        // Keys longer than a block are hashed first
+        let mut block = [0u8; 64];
+        if key.len() > 64 {
+            block[..32].copy_from_slice(&Sha256::digest(key));
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+        let mut inner = Sha256::new();
+        inner.update(&block.map(|byte| byte ^ 0x36));
+        let mut outer = Sha256::new();
+        outer.update(&block.map(|byte| byte ^ 0x5c));
+        HmacSha256 { inner, outer }
+    }
+
+    pub(crate) fn mac(&self, message: &[u8]) -> [u8; 32] {
+        let mut inner = self.inner.clone();
+        inner.update(message);
+        let mut outer = self.outer.clone();
+        outer.update(&inner.finish());
+        outer.finish()
+    }
+}
+
+pub(crate) fn pbkdf2_sha256(password: &[u8], salt: &[u8], iterations: u32, out: &mut [u8]) {
+    let hmac = HmacSha256::new(password);
+    // Each 32-byte block i is U1 ^ U2 ^ ... ^ Uc, where
+    // U1 = HMAC(salt || i) and U(n+1) = HMAC(Un)
+    for (index, chunk) in out.chunks_mut(32).enumerate() {
+        let mut message = salt.to_vec();
+        message.extend_from_slice(&(index as u32 + 1).to_be_bytes());
+        let mut u = hmac.mac(&message);
+        let mut block = u;
+        for _ in 1..iterations {
+            u = hmac.mac(&u);
+            for (b, x) in block.iter_mut().zip(u) {
+                *b ^= x;
+            }
+        }
+        chunk.copy_from_slice(&block[..chunk.len()]);
+    }
+}
+
+// Looks at every byte whatever it finds, so the time taken does not tell
+// an attacker how many leading bytes of a guess were right
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false; // lengths come from the stored string, not a secret
+    }
+    let difference = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    // Keeps the optimizer from turning the fold back into an early exit
+    std::hint::black_box(difference) == 0
+}
+
+// ---------------------------------------------------------------------
+// Base64 (standard alphabet, no padding, as PHC strings use)
+// ---------------------------------------------------------------------
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub(crate) fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        // 1 byte -> 2 chars, 2 -> 3, 3 -> 4
+        for i in 0..=chunk.len() {
+            out.push(BASE64[(n >> (18 - 6 * i) & 63) as usize] as char);
+        }
+    }
+    out
+}
+
+pub(crate) fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 4 == 1 {
+        return None; // one char holds only 6 bits, not a whole byte
+    }
+    let mut out = Vec::new();
+    for chunk in text.as_bytes().chunks(4) {
+        let mut n = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            let value = BASE64.iter().position(|&b| b == c)? as u32;
+            n |= value << (18 - 6 * i);
+        }
+        for i in 0..chunk.len() - 1 {
+            out.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    // Leftover bits must be zero, or two strings would decode the same
+    (base64_encode(&out) == text).then_some(out)
+}
+
+// ---------------------------------------------------------------------
+// Credential
+// ---------------------------------------------------------------------
+
+pub(crate) const ALGORITHM: &str = "pbkdf2-sha256";
+
+// How new hashes are made. Raising a number makes older hashes due for
+// a rehash at their next login. The fields are private so every policy
+// goes through `new`, which keeps them inside what parse_phc reads back.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct HashPolicy {
+    iterations: u32,
+    salt_len: usize,
+    hash_len: usize,
+}
+
+impl HashPolicy {
+    // OWASP's 2023 advice for PBKDF2-HMAC-SHA256. Slow in a debug build,
+    // so the demo uses smaller numbers.
+    pub(crate) const RECOMMENDED: HashPolicy = HashPolicy { iterations: 600_000, salt_len: 16, hash_len: 32 };
+
+    pub(crate) fn new(iterations: u32, salt_len: usize, hash_len: usize) -> Result<Self, PhcError> {
+        Credential::check_parameters(iterations, salt_len, hash_len)?;
+        Ok(HashPolicy { iterations, salt_len, hash_len })
+    }
+
+    pub(crate) fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    pub(crate) fn salt_len(&self) -> usize {
+        self.salt_len
+    }
+
+    pub(crate) fn hash_len(&self) -> usize {
+        self.hash_len
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PhcError {
+    Format,
+    UnknownAlgorithm(String),
+    BadParameter(String),
+    BadBase64(&'static str), // which part
+    TooLong { part: &'static str, max: usize },
+    EmptyHash,
+}
+
+impl fmt::Display for PhcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PhcError::Format => write!(f, "expected ${}$i=<iterations>$<salt>$<hash>", ALGORITHM),
+            PhcError::UnknownAlgorithm(id) => write!(f, "unknown algorithm {:?}", id),
+            PhcError::BadParameter(param) => write!(f, "bad parameter {:?}", param),
+            PhcError::BadBase64(part) => write!(f, "{} is not valid base64", part),
+            PhcError::TooLong { part, max } => write!(f, "{} is longer than {} bytes", part, max),
+            PhcError::EmptyHash => write!(f, "hash is empty"),
+        }
+    }
+}
+
+impl std::error::Error for PhcError {}
+
+// With `--features serde` it is written as its PHC string
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+pub(crate) struct Credential {
+    pub(crate) iterations: u32,
+    pub(crate) salt: Vec<u8>,
+    pub(crate) hash: Vec<u8>,
+}
+
+impl Credential {
+    // Limits on what parse_phc accepts. A stored string is checked on
+    // every login, so one with i=4000000000 would cost hours per attempt,
+    // and a huge hash would be allocated before it is compared.
+    pub(crate) const MAX_ITERATIONS: u32 = 10_000_000;
+    pub(crate) const MAX_SALT_LEN: usize = 64;
+    pub(crate) const MAX_HASH_LEN: usize = 64;
+
+    // The same limits for what is made as for what is read back, so a
+    // credential that was saved can always be loaded again
+    fn check_parameters(iterations: u32, salt_len: usize, hash_len: usize) -> Result<(), PhcError> {
+        if iterations == 0 || iterations > Self::MAX_ITERATIONS {
+            return Err(PhcError::BadParameter(format!("i={}", iterations)));
+        }
+        if salt_len > Self::MAX_SALT_LEN {
+            return Err(PhcError::TooLong { part: "salt", max: Self::MAX_SALT_LEN });
+        }
+        if hash_len > Self::MAX_HASH_LEN {
+            return Err(PhcError::TooLong { part: "hash", max: Self::MAX_HASH_LEN });
+        }
+        if hash_len == 0 {
+            return Err(PhcError::EmptyHash);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn derive(password: &str, salt: Vec<u8>, iterations: u32, hash_len: usize) -> Result<Self, PhcError> {
+        Self::check_parameters(iterations, salt.len(), hash_len)?;
+        let mut hash = vec![0; hash_len];
+        pbkdf2_sha256(password.as_bytes(), &salt, iterations, &mut hash);
+        Ok(Credential { iterations, salt, hash })
+    }
+
+    pub(crate) fn verify(&self, password: &str) -> bool {
+        // Two empty hashes compare equal, so an empty one would accept
+        // any password. derive and parse_phc never make one.
+        if self.hash.is_empty() {
+            return false;
+        }
+        let mut candidate = vec![0; self.hash.len()];
+        pbkdf2_sha256(password.as_bytes(), &self.salt, self.iterations, &mut candidate);
+        constant_time_eq(&candidate, &self.hash)
+    }
+
+    pub(crate) fn needs_rehash(&self, policy: &HashPolicy) -> bool {
+        self.iterations < policy.iterations || self.salt.len() < policy.salt_len || self.hash.len() < policy.hash_len
+    }
+
+    pub(crate) fn to_phc(&self) -> String {
+        format!("${}$i={}${}${}", ALGORITHM, self.iterations, base64_encode(&self.salt), base64_encode(&self.hash))
+    }
+
+    pub(crate) fn parse_phc(text: &str) -> Result<Self, PhcError> {
+        let parts: Vec<&str> = text.split('$').collect();
+        // The algorithm first: other algorithms have other fields
+        match parts[..] {
+            ["", id, ..] if id != ALGORITHM => return Err(PhcError::UnknownAlgorithm(id.to_string())),
+            _ => {}
+        }
+        let ["", _, params, salt, hash] = parts[..] else {
+            return Err(PhcError::Format);
+        };
+        let iterations = params
+            .strip_prefix("i=")
+            .and_then(|n| n.parse().ok())
+            .filter(|&n| n > 0 && n <= Self::MAX_ITERATIONS)
+            .ok_or_else(|| PhcError::BadParameter(params.to_string()))?;
+        // Lengths are checked on the base64 text, before decoding it
+        let decode = |text: &str, part, max: usize| {
+            if text.len() > (max * 4).div_ceil(3) {
+                return Err(PhcError::TooLong { part, max });
+            }
+            base64_decode(text).ok_or(PhcError::BadBase64(part))
+        };
+        let salt = decode(salt, "salt", Self::MAX_SALT_LEN)?;
+        let hash = decode(hash, "hash", Self::MAX_HASH_LEN)?;
+        if hash.is_empty() {
+            return Err(PhcError::EmptyHash);
+        }
+        Ok(Credential { iterations, salt, hash })
+    }
+}
+
+// What serde goes through
+impl TryFrom<String> for Credential {
+    type Error = PhcError;
+    fn try_from(text: String) -> Result<Self, PhcError> {
+        Credential::parse_phc(&text)
+    }
+}
+
+impl From<Credential> for String {
+    fn from(credential: Credential) -> String {
+        credential.to_phc()
+    }
+}
+
+// Never print the hash, even in debug output
+impl fmt::Debug for Credential {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Credential({}, i={}, ..)", ALGORITHM, self.iterations)
+    }
+}
+
+// Bytes from the operating system's secure random source. Unix only:
+// elsewhere there is no /dev/urandom, so this returns an error and
+// set_password fails. Real code would use the getrandom crate.
+pub(crate) fn random_salt(len: usize) -> io::Result<Vec<u8>> {
+    let mut salt = vec![0; len];
+    std::fs::File::open("/dev/urandom")?.read_exact(&mut salt)?;
+    Ok(salt)
+}
@@ -0,0 +1,147 @@
+// A counting limiter (a semaphore) handing out RAII permits.
+// Explained in m5_connection_limit; m5_builder_serve uses the same one via
+// `#[path = "common/limiter.rs"] mod limiter;`.
+
+#![allow(dead_code)] // each example uses a different subset
+
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+// A limiter with no slots could never grant anything - acquire() would
+// block forever - so it cannot be created
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ZeroCapacity;
+
+impl fmt::Display for ZeroCapacity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a connection limiter needs at least one slot")
+    }
+}
+
+impl std::error::Error for ZeroCapacity {}
+
+// A snapshot of the limiter's counters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Metrics {
+    pub(crate) capacity: usize,
+    pub(crate) in_use: usize,
+    pub(crate) peak: usize,
+    pub(crate) rejected: usize,
+}
+
+struct State {
+    in_use: usize,
+    peak: usize,
+    rejected: usize,
+}
+
+struct Inner {
+    capacity: usize,
+    state: Mutex<State>,
+    released: Condvar, // signalled whenever a permit is dropped
+    idle: Condvar,     // signalled when the last permit is dropped
+}
+
+// Cheap to clone: every clone shares the same slots
+#[derive(Clone)]
+pub(crate) struct ConnectionLimiter {
+    inner: Arc<Inner>,
+}
+
+// Proof of holding one slot; dropping it gives the slot back
+pub(crate) struct Permit {
+    inner: Arc<Inner>,
+}
+
+impl ConnectionLimiter {
+    pub(crate) fn new(capacity: usize) -> Result<Self, ZeroCapacity> {
+        if capacity == 0 {
+            return Err(ZeroCapacity);
+        }
+        Ok(ConnectionLimiter {
+            inner: Arc::new(Inner {
+                capacity,
+                state: Mutex::new(State { in_use: 0, peak: 0, rejected: 0 }),
+                released: Condvar::new(),
+                idle: Condvar::new(),
+            }),
+        })
+    }
+
+    // Take a slot; the caller already holds the lock and checked there is room
+    fn grant(&self, state: &mut State) -> Permit {
+        state.in_use += 1;
+        state.peak = state.peak.max(state.in_use);
+        Permit { inner: Arc::clone(&self.inner) }
+    }
+
+    // Wait (without spinning) until a slot is free
+    pub(crate) fn acquire(&self) -> Permit {
+        let mut state = self.inner.state.lock().unwrap();
+        while state.in_use >= self.inner.capacity {
+            state = self.inner.released.wait(state).unwrap();
+        }
+        self.grant(&mut state)
+    }
+
+    // Never waits; a failure counts as a rejection
+    pub(crate) fn try_acquire(&self) -> Option<Permit> {
+        let mut state = self.inner.state.lock().unwrap();
+        if state.in_use < self.inner.capacity {
+            Some(self.grant(&mut state))
+        } else {
+            state.rejected += 1;
+            None
+        }
+    }
+
+    // Wait at most `timeout`; giving up counts as a rejection. A timeout
+    // too long to be a point in time (Duration::MAX) waits like acquire().
+    pub(crate) fn acquire_timeout(&self, timeout: Duration) -> Option<Permit> {
+        let Some(deadline) = Instant::now().checked_add(timeout) else {
+            return Some(self.acquire());
+        };
+        let mut state = self.inner.state.lock().unwrap();
+        while state.in_use >= self.inner.capacity {
+            // Condvars can wake up spuriously, so re-check the remaining time
+            let now = Instant::now();
+            if now >= deadline {
+                state.rejected += 1;
+                return None;
+            }
+            state = self.inner.released.wait_timeout(state, deadline - now).unwrap().0;
+        }
+        Some(self.grant(&mut state))
+    }
+
+    // Block until every permit has been dropped (graceful shutdown)
+    pub(crate) fn wait_idle(&self) {
+        let mut state = self.inner.state.lock().unwrap();
+        while state.in_use > 0 {
+            state = self.inner.idle.wait(state).unwrap();
+        }
+    }
+
+    pub(crate) fn metrics(&self) -> Metrics {
+        let state = self.inner.state.lock().unwrap();
+        Metrics {
+            capacity: self.inner.capacity,
+            in_use: state.in_use,
+            peak: state.peak,
+            rejected: state.rejected,
+        }
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut state = self.inner.state.lock().unwrap();
+        state.in_use -= 1;
+        // One slot freed - one waiter can proceed
+        self.inner.released.notify_one();
+        if state.in_use == 0 {
+            self.inner.idle.notify_all();
+        }
+    }
+}
@@ -0,0 +1,253 @@
+// The user repository: ids, the UserStore trait a backend implements,
+// and UserRepository, which enforces the rules on top of any store.
+// Explained in m5_structs_repository; m5_structs_jsonl uses the same one.
+// Include it next to the users module:
+//
+//     #[path = "common/users.rs"]
+//     mod users;
+//     #[path = "common/repository.rs"]
+//     mod repository;
+
+#![allow(dead_code)] // each example uses a different subset
+
+use std::fmt;
+use std::io;
+
+use super::users::{EmailAddress, User, Username};
+
+// ---------------------------------------------------------------------
+// Ids and storage
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) struct UserId(pub(crate) u64);
+
+impl fmt::Display for UserId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+// A backend could be a file or a database, so every call can fail
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum StorageError {
+    Io(String),
+    // A stored file that cannot be read back. `line` counts from 1, as
+    // editors do.
+    Corrupt { line: usize, reason: String },
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StorageError::Io(e) => write!(f, "storage failed: {}", e),
+            StorageError::Corrupt { line, reason } => write!(f, "user file is corrupt at line {}: {}", line, reason),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<io::Error> for StorageError {
+    fn from(e: io::Error) -> Self {
+        StorageError::Io(e.to_string())
+    }
+}
+
+// Just storage: the store keeps what it is given, and the rules live in
+// UserRepository. Lookups take the folded (lowercase) keys.
+pub(crate) trait UserStore {
+    fn get(&self, id: UserId) -> Result<Option<User>, StorageError>;
+    fn id_for_username(&self, folded: &str) -> Result<Option<UserId>, StorageError>;
+    fn id_for_email(&self, folded: &str) -> Result<Option<UserId>, StorageError>;
+    // Insert or replace
+    fn put(&mut self, id: UserId, user: User) -> Result<(), StorageError>;
+    fn remove(&mut self, id: UserId) -> Result<Option<User>, StorageError>;
+    fn largest_id(&self) -> Result<Option<UserId>, StorageError>;
+}
+
+// ---------------------------------------------------------------------
+// Repository
+// ---------------------------------------------------------------------
+
+// What a failed lookup was looking for
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Lookup {
+    Id(UserId),
+    Username(Username),
+    Email(EmailAddress),
+}
+
+impl fmt::Display for Lookup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Lookup::Id(id) => write!(f, "id {}", id),
+            Lookup::Username(username) => write!(f, "username {:?}", username.as_ref()),
+            Lookup::Email(email) => write!(f, "email {:?}", email.as_ref()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RepositoryError {
+    NotFound(Lookup),
+    DuplicateUsername { username: Username, taken_by: UserId },
+    DuplicateEmail { email: EmailAddress, taken_by: UserId },
+    AlreadyActive(UserId),
+    AlreadyInactive(UserId),
+    Inactive(UserId),    // updated while inactive
+    StillActive(UserId), // deleted without deactivating first
+    // Every id after this one is taken; ids are never reused
+    IdsExhausted(UserId),
+    Storage(StorageError),
+}
+
+impl fmt::Display for RepositoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RepositoryError::NotFound(lookup) => write!(f, "no user with {}", lookup),
+            RepositoryError::DuplicateUsername { username, taken_by } => {
+                write!(f, "username {:?} is taken by user {}", username.as_ref(), taken_by)
+            }
+            RepositoryError::DuplicateEmail { email, taken_by } => {
+                write!(f, "email {:?} is taken by user {}", email.as_ref(), taken_by)
+            }
+            RepositoryError::AlreadyActive(id) => write!(f, "user {} is already active", id),
+            RepositoryError::AlreadyInactive(id) => write!(f, "user {} is already inactive", id),
+            RepositoryError::Inactive(id) => write!(f, "user {} is inactive and cannot be changed", id),
+            RepositoryError::StillActive(id) => write!(f, "user {} must be deactivated before deleting", id),
+            RepositoryError::IdsExhausted(id) => write!(f, "no user ids are left after {}", id),
+            RepositoryError::Storage(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for RepositoryError {}
+
+impl From<StorageError> for RepositoryError {
+    fn from(e: StorageError) -> Self {
+        RepositoryError::Storage(e)
+    }
+}
+
+// Fields to change; None leaves a field as it is
+#[derive(Debug, Default)]
+pub(crate) struct UserUpdate {
+    pub(crate) username: Option<Username>,
+    pub(crate) email: Option<EmailAddress>,
+}
+
+// Private fields: changing the store or the numbering behind the
+// repository's back would break its rules
+pub(crate) struct UserRepository<S: UserStore> {
+    store: S,
+    next_id: u64,
+}
+
+impl<S: UserStore> UserRepository<S> {
+    // Carries on numbering after whatever the store already holds
+    pub(crate) fn new(store: S) -> Result<Self, RepositoryError> {
+        let next_id = match store.largest_id()? {
+            None => 1,
+            Some(largest) => largest.0.checked_add(1).ok_or(RepositoryError::IdsExhausted(largest))?,
+        };
+        Ok(UserRepository { store, next_id })
+    }
+
+    // Read-only access, e.g. to look at what a backend wrote
+    pub(crate) fn store(&self) -> &S {
+        &self.store
+    }
+
+    // Give the store back, e.g. to compact it or to reopen it later
+    pub(crate) fn into_store(self) -> S {
+        self.store
+    }
+
+    // The id the next create() will use
+    pub(crate) fn next_id(&self) -> UserId {
+        UserId(self.next_id)
+    }
+
+    pub(crate) fn create(&mut self, username: Username, email: EmailAddress) -> Result<UserId, RepositoryError> {
+        self.check_unique(None, &username, &email)?;
+        let id = UserId(self.next_id);
+        // Checked before writing, so the last id is never handed out twice
+        let next_id = self.next_id.checked_add(1).ok_or(RepositoryError::IdsExhausted(id))?;
+        self.store.put(id, User { username, email, active: true, credential: None })?;
+        self.next_id = next_id;
+        Ok(id)
+    }
+
+    pub(crate) fn get(&self, id: UserId) -> Result<User, RepositoryError> {
+        self.store.get(id)?.ok_or(RepositoryError::NotFound(Lookup::Id(id)))
+    }
+
+    pub(crate) fn get_by_username(&self, username: &Username) -> Result<(UserId, User), RepositoryError> {
+        let not_found = || RepositoryError::NotFound(Lookup::Username(username.clone()));
+        let id = self.store.id_for_username(username.folded())?.ok_or_else(not_found)?;
+        Ok((id, self.get(id)?))
+    }
+
+    pub(crate) fn get_by_email(&self, email: &EmailAddress) -> Result<(UserId, User), RepositoryError> {
+        let not_found = || RepositoryError::NotFound(Lookup::Email(email.clone()));
+        let id = self.store.id_for_email(email.folded())?.ok_or_else(not_found)?;
+        Ok((id, self.get(id)?))
+    }
+
+    pub(crate) fn update(&mut self, id: UserId, update: UserUpdate) -> Result<User, RepositoryError> {
+        let mut user = self.get(id)?;
+        if !user.active {
+            return Err(RepositoryError::Inactive(id));
+        }
+        user.username = update.username.unwrap_or(user.username);
+        user.email = update.email.unwrap_or(user.email);
+        self.check_unique(Some(id), &user.username, &user.email)?;
+        self.store.put(id, user.clone())?;
+        Ok(user)
+    }
+
+    pub(crate) fn deactivate(&mut self, id: UserId) -> Result<(), RepositoryError> {
+        self.set_active(id, false)
+    }
+
+    pub(crate) fn reactivate(&mut self, id: UserId) -> Result<(), RepositoryError> {
+        self.set_active(id, true)
+    }
+
+    pub(crate) fn delete(&mut self, id: UserId) -> Result<User, RepositoryError> {
+        if self.get(id)?.active {
+            return Err(RepositoryError::StillActive(id));
+        }
+        self.store.remove(id)?.ok_or(RepositoryError::NotFound(Lookup::Id(id)))
+    }
+
+    // The only place `active` changes, so every transition is checked
+    fn set_active(&mut self, id: UserId, active: bool) -> Result<(), RepositoryError> {
+        let mut user = self.get(id)?;
+        match (user.active, active) {
+            (true, true) => Err(RepositoryError::AlreadyActive(id)),
+            (false, false) => Err(RepositoryError::AlreadyInactive(id)),
+            _ => {
+                user.active = active;
+                Ok(self.store.put(id, user)?)
+            }
+        }
+    }
+
+    // `own` is the user being updated: keeping your own name, or changing
+    // only its case, is not a clash
+    fn check_unique(&self, own: Option<UserId>, username: &Username, email: &EmailAddress) -> Result<(), RepositoryError> {
+        if let Some(taken_by) = self.store.id_for_username(username.folded())? {
+            if Some(taken_by) != own {
+                return Err(RepositoryError::DuplicateUsername { username: username.clone(), taken_by });
+            }
+        }
+        if let Some(taken_by) = self.store.id_for_email(email.folded())? {
+            if Some(taken_by) != own {
+                return Err(RepositoryError::DuplicateEmail { email: email.clone(), taken_by });
+            }
+        }
+        Ok(())
+    }
+}
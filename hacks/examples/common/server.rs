@@ -0,0 +1,41 @@
+// The Server configuration and its derived ServerBuilder.
+// Explained in m5_builder; m5_builder_config, m5_builder_serve,
+// m5_builder_reload, m5_connection_limit and m5_serde_csv use the same
+// one via `#[path = "common/server.rs"] mod server;`.
+
+#![allow(dead_code)] // each example uses a different subset
+
+use hacks_derive::Builder;
+
+// validate = ... runs after all fields are set; it can reject the config.
+// With `--features serde` it is also Serialize/Deserialize (see m5_serde_csv);
+// deserializing skips the builder, so call validate() yourself.
+#[derive(Builder, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[builder(validate = Server::validate)]
+pub(crate) struct Server {
+    #[builder(default = String::from("localhost"))]
+    pub(crate) host: String,
+    #[builder(default = 8080)]
+    pub(crate) port: u16,
+    #[builder(default = 100)]
+    pub(crate) max_connections: u32,
+    // setter = ... keeps the short name the hand-written builder used
+    #[builder(default = 30, setter = timeout)]
+    pub(crate) timeout_seconds: u32,
+}
+
+impl Server {
+    // Validation hook: called by build() with the finished value.
+    // Both limits must be usable: no connections at all, or a timeout of
+    // zero (which the socket API would read as "wait forever"), is refused.
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.max_connections == 0 {
+            return Err(String::from("max_connections must be at least 1"));
+        }
+        if self.timeout_seconds == 0 {
+            return Err(String::from("timeout_seconds must be at least 1"));
+        }
+        Ok(())
+    }
+}
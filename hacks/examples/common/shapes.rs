@@ -0,0 +1,33 @@
+// Point and SimplePoint (explained in m5_derive) and the positioned
+// Rectangle (explained in m5_methods, which adds its methods). Those two
+// examples, m5_generics_geometry and m5_serde_csv use them via
+// `#[path = "common/shapes.rs"] mod shapes;`.
+//
+// cfg_attr adds Serialize/Deserialize only with `--features serde`.
+
+#![allow(dead_code)] // each example uses a different subset
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Point {
+    pub(crate) x: i32,
+    pub(crate) y: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct SimplePoint {
+    pub(crate) x: i32,
+    pub(crate) y: i32,
+}
+
+// Not Clone or Copy: m5_methods shows `into_square` consuming one
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Rectangle {
+    // Top-left corner. x grows to the right, y grows downwards.
+    pub(crate) x: i32,
+    pub(crate) y: i32,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
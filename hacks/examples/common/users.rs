@@ -0,0 +1,363 @@
+// Validated Username and EmailAddress newtypes, and the User built from
+// them. Explained in m5_structs_validated; m5_structs, the repository
+// examples and m5_structs_password use the same ones via
+// `#[path = "common/users.rs"] mod users;`.
+//
+// With `--features serde`, both newtypes (and the credential) serialize
+// as plain strings and are parsed again when read back, so invalid input
+// is rejected.
+
+#![allow(dead_code)] // each example uses a different subset
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+// common/credential.rs, the password hash a User may carry
+#[path = "credential.rs"]
+pub(crate) mod credential;
+
+use credential::Credential;
+
+// ---------------------------------------------------------------------
+// Username
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum UsernameError {
+    TooShort { len: usize, min: usize },
+    TooLong { len: usize, max: usize },
+    InvalidChar { ch: char, index: usize },
+    MustStartWithLetter,
+    MisplacedSeparator { index: usize }, // at the end, or two in a row
+}
+
+impl fmt::Display for UsernameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UsernameError::TooShort { len, min } => {
+                write!(f, "username has {} characters, needs at least {}", len, min)
+            }
+            UsernameError::TooLong { len, max } => {
+                write!(f, "username has {} characters, at most {} allowed", len, max)
+            }
+            UsernameError::InvalidChar { ch, index } => {
+                write!(f, "username has {:?} at position {}; use letters, digits, '_', '-' or '.'", ch, index)
+            }
+            UsernameError::MustStartWithLetter => write!(f, "username must start with a letter"),
+            UsernameError::MisplacedSeparator { index } => {
+                write!(f, "username has a misplaced '_', '-' or '.' at position {}", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for UsernameError {}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+pub(crate) struct Username {
+    text: String, // as typed, for display
+    key: String,  // lowercase, for comparison
+}
+
+impl Username {
+    const MIN_LEN: usize = 3;
+    const MAX_LEN: usize = 32;
+
+    pub(crate) fn parse(text: &str) -> Result<Self, UsernameError> {
+        let len = text.chars().count();
+        if len < Self::MIN_LEN {
+            return Err(UsernameError::TooShort { len, min: Self::MIN_LEN });
+        }
+        if len > Self::MAX_LEN {
+            return Err(UsernameError::TooLong { len, max: Self::MAX_LEN });
+        }
+
+        let is_separator = |ch: char| matches!(ch, '_' | '-' | '.');
+        let mut previous_separator = false;
+        for (index, ch) in text.chars().enumerate() {
+            if !(ch.is_ascii_alphanumeric() || is_separator(ch)) {
+                return Err(UsernameError::InvalidChar { ch, index });
+            }
+            if index == 0 && !ch.is_ascii_alphabetic() {
+                return Err(UsernameError::MustStartWithLetter);
+            }
+            let separator = is_separator(ch);
+            if separator && (previous_separator || index == len - 1) {
+                return Err(UsernameError::MisplacedSeparator { index });
+            }
+            previous_separator = separator;
+        }
+
+        Ok(Username { text: text.to_string(), key: text.to_ascii_lowercase() })
+    }
+
+    // The form used for comparison and lookups
+    pub(crate) fn folded(&self) -> &str {
+        &self.key
+    }
+}
+
+// ---------------------------------------------------------------------
+// EmailAddress
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum EmailError {
+    Empty,
+    TooLong { len: usize },
+    MissingAt,
+    EmptyLocalPart,
+    LocalPartTooLong { len: usize },
+    InvalidLocalChar { ch: char, index: usize },
+    MisplacedDot { index: usize }, // first, last or doubled in the local part
+    EmptyDomain,
+    InvalidDomain { label: String, reason: &'static str },
+}
+
+impl fmt::Display for EmailError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EmailError::Empty => write!(f, "email address is empty"),
+            EmailError::TooLong { len } => {
+                write!(f, "email address is {} bytes, at most {} allowed", len, EmailAddress::MAX_LEN)
+            }
+            EmailError::MissingAt => write!(f, "email address needs an '@'"),
+            EmailError::EmptyLocalPart => write!(f, "email address has nothing before the '@'"),
+            EmailError::LocalPartTooLong { len } => {
+                write!(f, "part before the '@' is {} bytes, at most {} allowed", len, EmailAddress::MAX_LOCAL_LEN)
+            }
+            EmailError::InvalidLocalChar { ch, index } => {
+                write!(f, "email address has {:?} at position {} before the '@'", ch, index)
+            }
+            EmailError::MisplacedDot { index } => write!(f, "email address has a misplaced '.' at position {}", index),
+            EmailError::EmptyDomain => write!(f, "email address has nothing after the '@'"),
+            EmailError::InvalidDomain { label, reason } => write!(f, "domain label {:?} {}", label, reason),
+        }
+    }
+}
+
+impl std::error::Error for EmailError {}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+pub(crate) struct EmailAddress {
+    text: String,
+    key: String,
+}
+
+impl EmailAddress {
+    const MAX_LEN: usize = 254; // RFC 5321 path limit, minus the <>
+    const MAX_LOCAL_LEN: usize = 64;
+    const MAX_LABEL_LEN: usize = 63;
+
+    // RFC 5322 "atext": what a dot-atom may contain besides dots.
+    // Quoted local parts ("john doe"@example.com) are not accepted.
+    fn is_atext(ch: char) -> bool {
+        ch.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(ch)
+    }
+
+    pub(crate) fn parse(text: &str) -> Result<Self, EmailError> {
+        if text.is_empty() {
+            return Err(EmailError::Empty);
+        }
+        if text.len() > Self::MAX_LEN {
+            return Err(EmailError::TooLong { len: text.len() });
+        }
+        // The last '@' splits; an earlier one is reported as a bad character
+        let (local, domain) = text.rsplit_once('@').ok_or(EmailError::MissingAt)?;
+
+        if local.is_empty() {
+            return Err(EmailError::EmptyLocalPart);
+        }
+        if local.len() > Self::MAX_LOCAL_LEN {
+            return Err(EmailError::LocalPartTooLong { len: local.len() });
+        }
+        let mut previous_dot = false;
+        for (index, ch) in local.char_indices() {
+            if ch == '.' {
+                if index == 0 || previous_dot || index == local.len() - 1 {
+                    return Err(EmailError::MisplacedDot { index });
+                }
+            } else if !Self::is_atext(ch) {
+                return Err(EmailError::InvalidLocalChar { ch, index });
+            }
+            previous_dot = ch == '.';
+        }
+
+        Self::check_domain(domain)?;
+        Ok(EmailAddress { text: text.to_string(), key: text.to_ascii_lowercase() })
+    }
+
+    // Hostname rules (RFC 1035): dot-separated labels of letters, digits
+    // and inner hyphens. A single label like "localhost" is not accepted,
+    // and the last label (the TLD) cannot be all digits.
+    fn check_domain(domain: &str) -> Result<(), EmailError> {
+        if domain.is_empty() {
+            return Err(EmailError::EmptyDomain);
+        }
+        let invalid = |label: &str, reason| Err(EmailError::InvalidDomain { label: label.to_string(), reason });
+        let labels: Vec<&str> = domain.split('.').collect();
+        for label in &labels {
+            if label.is_empty() {
+                return invalid(label, "is empty");
+            }
+            if label.len() > Self::MAX_LABEL_LEN {
+                return invalid(label, "is longer than 63 bytes");
+            }
+            if !label.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '-') {
+                return invalid(label, "may only contain letters, digits and '-'");
+            }
+            if label.starts_with('-') || label.ends_with('-') {
+                return invalid(label, "cannot start or end with '-'");
+            }
+        }
+        let tld = labels[labels.len() - 1];
+        if labels.len() < 2 {
+            return invalid(tld, "needs a top-level domain after it");
+        }
+        if tld.chars().all(|ch| ch.is_ascii_digit()) {
+            return invalid(tld, "is all digits");
+        }
+        Ok(())
+    }
+
+    // Strictly only the domain is case-insensitive, but in practice no
+    // mail provider treats "Bob@" and "bob@" as different people
+    pub(crate) fn folded(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn domain(&self) -> &str {
+        self.text.rsplit_once('@').map(|(_, domain)| domain).unwrap_or_default()
+    }
+}
+
+// ---------------------------------------------------------------------
+// Shared trait impls
+// ---------------------------------------------------------------------
+
+// Both types: equality and hashing use the folded key, AsRef and Display
+// give the text as typed, TryFrom<&str> is the fallible constructor.
+// TryFrom<String> and Into<String> are what serde goes through.
+macro_rules! validated_text {
+    ($Type:ident, $Error:ident) => {
+        impl PartialEq for $Type {
+            fn eq(&self, other: &Self) -> bool {
+                self.key == other.key
+            }
+        }
+
+        impl Eq for $Type {}
+
+        // Must agree with PartialEq, so it hashes the key too
+        impl Hash for $Type {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.key.hash(state);
+            }
+        }
+
+        impl AsRef<str> for $Type {
+            fn as_ref(&self) -> &str {
+                &self.text
+            }
+        }
+
+        impl fmt::Display for $Type {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(&self.text)
+            }
+        }
+
+        impl TryFrom<&str> for $Type {
+            type Error = $Error;
+            fn try_from(text: &str) -> Result<Self, $Error> {
+                $Type::parse(text)
+            }
+        }
+
+        impl TryFrom<String> for $Type {
+            type Error = $Error;
+            fn try_from(text: String) -> Result<Self, $Error> {
+                $Type::parse(&text)
+            }
+        }
+
+        // Lets text formats such as m5_serde_csv's parse a cell with `parse()`
+        impl std::str::FromStr for $Type {
+            type Err = $Error;
+            fn from_str(text: &str) -> Result<Self, $Error> {
+                $Type::parse(text)
+            }
+        }
+
+        impl From<$Type> for String {
+            fn from(value: $Type) -> String {
+                value.text
+            }
+        }
+    };
+}
+
+validated_text!(Username, UsernameError);
+validated_text!(EmailAddress, EmailError);
+
+// ---------------------------------------------------------------------
+// User
+// ---------------------------------------------------------------------
+
+#[derive(Debug)]
+pub(crate) enum UserError {
+    Username(UsernameError),
+    Email(EmailError),
+}
+
+impl fmt::Display for UserError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UserError::Username(e) => write!(f, "invalid username: {}", e),
+            UserError::Email(e) => write!(f, "invalid email: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for UserError {}
+
+// Lets `?` turn either field's error into a UserError
+impl From<UsernameError> for UserError {
+    fn from(e: UsernameError) -> Self {
+        UserError::Username(e)
+    }
+}
+
+impl From<EmailError> for UserError {
+    fn from(e: EmailError) -> Self {
+        UserError::Email(e)
+    }
+}
+
+// The User from m5_structs, with checked fields. A user made by an admin
+// tool may not have a password yet, hence the Option.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct User {
+    pub(crate) username: Username,
+    pub(crate) email: EmailAddress,
+    pub(crate) active: bool,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub(crate) credential: Option<Credential>,
+}
+
+impl User {
+    pub(crate) fn new(username: &str, email: &str) -> Result<User, UserError> {
+        Ok(User {
+            username: Username::try_from(username)?,
+            email: EmailAddress::try_from(email)?,
+            active: true,
+            credential: None,
+        })
+    }
+}
+
@@ -2,70 +2,70 @@
 //
 // Demonstrates the Builder Pattern - a common Rust idiom for constructing
 // complex objects step by step with optional configuration.
+//
+// Writing a builder by hand means mirroring every field of the target
+// struct (see the Module 5 slides and Lab 8). Here #[derive(Builder)]
+// from the workspace crate `hacks_derive` generates that code for us:
+//
+//   struct ServerBuilder { host: String, port: u16, ... }
+//   impl ServerBuilder {
+//       fn new() -> Self                      // defaults from #[builder(default = ...)]
+//       fn host(mut self, host: impl Into<String>) -> Self
+//       fn port(mut self, port: u16) -> Self
+//       ...
+//       fn build(self) -> Result<Server, ServerBuilderError>
+//   }
+//
+// build() returns a Result only when it can fail (a required field or a
+// validator); an all-default struct like Retry below gets `fn build(self) -> Retry`.
 
-// The final object we want to build
-struct Server {
-    host: String,
-    port: u16,
-    max_connections: u32,
-    timeout_seconds: u32,
-}
-
-// Builder struct - holds configuration before building
-struct ServerBuilder {
-    host: String,
-    port: u16,
-    max_connections: u32,
-    timeout_seconds: u32,
-}
-
-impl ServerBuilder {
-    // Start with sensible defaults
-    fn new() -> Self {
-        ServerBuilder {
-            host: String::from("localhost"),
-            port: 8080,
-            max_connections: 100,
-            timeout_seconds: 30,
-        }
-    }
-
-    // Each setter takes self by value and returns Self
-    // This allows method chaining: builder.host(...).port(...)
-    fn host(mut self, host: &str) -> Self {
-        self.host = host.to_string();
-        self
-    }
-
-    fn port(mut self, port: u16) -> Self {
-        self.port = port;
-        self
-    }
+use hacks_derive::Builder;
 
-    fn max_connections(mut self, max: u32) -> Self {
-        self.max_connections = max;
-        self
-    }
+// The final object we want to build. It lives in common/server.rs, shared
+// with m5_builder_config, m5_builder_serve, m5_builder_reload and
+// m5_serde_csv, and is defined there like this:
+//
+//     #[derive(Builder)]
+//     #[builder(validate = Server::validate)]
+//     struct Server {
+//         #[builder(default = String::from("localhost"))]
+//         host: String,
+//         #[builder(default = 8080)]
+//         port: u16,
+//         #[builder(default = 100)]
+//         max_connections: u32,
+//         // setter = ... keeps the short name the hand-written builder used
+//         #[builder(default = 30, setter = timeout)]
+//         timeout_seconds: u32,
+//     }
+//
+// validate = ... runs after all fields are set; Server::validate refuses
+// a max_connections or timeout_seconds of 0.
+#[path = "common/server.rs"]
+mod server;
+use server::{Server, ServerBuilder};
 
-    fn timeout(mut self, seconds: u32) -> Self {
-        self.timeout_seconds = seconds;
-        self
-    }
+// A field WITHOUT a default is required - build() fails if it is missing
+#[derive(Builder)]
+struct Route {
+    path: String,
+    #[builder(default = String::from("GET"))]
+    method: String,
+}
 
-    // Consume the builder and create the final object
-    fn build(self) -> Server {
-        Server {
-            host: self.host,
-            port: self.port,
-            max_connections: self.max_connections,
-            timeout_seconds: self.timeout_seconds,
-        }
-    }
+// Every field has a default and there is no validator: build() cannot fail,
+// so it returns the value itself instead of a Result
+#[derive(Builder)]
+struct Retry {
+    #[builder(default = 3)]
+    attempts: u32,
+    #[builder(default = 100)]
+    backoff_ms: u64,
 }
 
-fn main() {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Use all defaults
-    let server1 = ServerBuilder::new().build();
+    let server1 = ServerBuilder::new().build()?;
     println!("Server 1: {}:{}", server1.host, server1.port);
 
     // Customize everything with method chaining
@@ -74,16 +74,42 @@ fn main() {
         .port(3000)
         .max_connections(1000)
         .timeout(60)
-        .build();
+        .build()?;
     println!("Server 2: {}:{} (max: {}, timeout: {}s)",
         server2.host, server2.port,
         server2.max_connections, server2.timeout_seconds);
 
     // Partially customize - order doesn't matter
-    let server3 = ServerBuilder::new()
+    // Server::builder() is a shortcut for ServerBuilder::new()
+    let server3 = Server::builder()
         .timeout(120)
         .port(9000)
-        .build();
+        .build()?;
     println!("Server 3: {}:{} (timeout: {}s)",
         server3.host, server3.port, server3.timeout_seconds);
+
+    // String setters accept anything Into<String>: &str, String, ...
+    let host = String::from("127.0.0.1");
+    let server4 = Server::builder().host(host).build()?;
+    println!("Server 4: {}:{}", server4.host, server4.port);
+
+    // The validation hook rejects bad configurations
+    match Server::builder().max_connections(0).build() {
+        Ok(_) => println!("\nUnexpected success"),
+        Err(e) => println!("\nRejected: {}", e),
+    }
+
+    // Required fields must be set before build()
+    match Route::builder().method("POST").build() {
+        Ok(_) => println!("Unexpected success"),
+        Err(e) => println!("Rejected: {}", e),
+    }
+    let route = Route::builder().path("/health").build()?;
+    println!("Route: {} {}", route.method, route.path);
+
+    // No `?` needed - nothing can go wrong
+    let retry = Retry::builder().attempts(5).build();
+    println!("Retry: {} attempts, {}ms backoff", retry.attempts, retry.backoff_ms);
+
+    Ok(())
 }
@@ -0,0 +1,438 @@
+// cargo run --example m5_builder_config
+// cargo run --example m5_builder_config -- --port 9000 --host=0.0.0.0
+//
+// Demonstrates LAYERED CONFIGURATION on top of the Builder Pattern.
+// Real servers are rarely configured by hard-coded method chains. Values
+// come from several places, and later sources override earlier ones:
+//
+//   defaults  <  TOML file  <  HACKS_SERVER_* env vars  <  command-line flags
+//
+// The ConfigBuilder remembers WHICH source set each value, so an operator
+// can answer "why is the port 9000?" without guessing. Its build() hands
+// the values to the ServerBuilder from m5_builder, so a layered config
+// passes the same Server::validate as a chained one.
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// The TOML subset reader, shared with m5_builder_reload
+#[path = "common/config_text.rs"]
+mod config_text;
+use config_text::{parse_server_table, TomlError};
+
+// The final object we want to build, shared with m5_builder
+#[path = "common/server.rs"]
+mod server;
+use server::{Server, ServerBuilderError};
+
+// Where a configuration value came from
+// The first four are ordered from lowest to highest precedence.
+// Code is a setter called in the program: it wins or loses by call order.
+#[derive(Debug, Clone, PartialEq)]
+enum Source {
+    Default,
+    File { path: PathBuf, line: usize },
+    Env(String),
+    Cli(String),
+    Code(String),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File { path, line } => write!(f, "file {}:{}", path.display(), line),
+            Source::Env(var) => write!(f, "env {}", var),
+            Source::Cli(flag) => write!(f, "cli {}", flag),
+            Source::Code(setter) => write!(f, "code {}", setter),
+        }
+    }
+}
+
+// Everything that can go wrong while loading a layer
+#[derive(Debug)]
+enum ConfigError {
+    Io { path: PathBuf, message: String },
+    Syntax { source: Source, message: String },
+    UnknownKey { source: Source, key: String },
+    DuplicateKey { source: Source, key: String, first_line: usize },
+    InvalidValue { source: Source, key: String, value: String },
+    MissingValue { flag: String },
+    Invalid(ServerBuilderError),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, message } => {
+                write!(f, "cannot read {}: {}", path.display(), message)
+            }
+            ConfigError::Syntax { source, message } => write!(f, "{}: {}", source, message),
+            ConfigError::UnknownKey { source, key } => {
+                write!(f, "{}: unknown key '{}'", source, key)
+            }
+            ConfigError::DuplicateKey { source, key, first_line } => {
+                write!(f, "{}: '{}' was already set on line {}", source, key, first_line)
+            }
+            ConfigError::InvalidValue { source, key, value } => {
+                write!(f, "{}: invalid value '{}' for '{}'", source, value, key)
+            }
+            ConfigError::MissingValue { flag } => write!(f, "flag {} needs a value", flag),
+            ConfigError::Invalid(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+// The keys every layer understands
+const KEYS: [&str; 4] = ["host", "port", "max_connections", "timeout_seconds"];
+
+// Environment variables that belong to us, e.g. HACKS_SERVER_PORT
+const ENV_PREFIX: &str = "HACKS_SERVER_";
+
+// Builder struct - holds configuration AND where each value came from
+struct ConfigBuilder {
+    host: String,
+    port: u16,
+    max_connections: u32,
+    timeout_seconds: u32,
+    origins: HashMap<&'static str, Source>,
+}
+
+impl ConfigBuilder {
+    // Start with sensible defaults - the lowest layer
+    fn new() -> Self {
+        let mut origins = HashMap::new();
+        for key in KEYS {
+            origins.insert(key, Source::Default);
+        }
+        ConfigBuilder {
+            host: String::from("localhost"),
+            port: 8080,
+            max_connections: 100,
+            timeout_seconds: 30,
+            origins,
+        }
+    }
+
+    // The chainable setters from m5_builder still work; the value is
+    // recorded as set in code
+    fn host(mut self, host: &str) -> Self {
+        self.host = host.to_string();
+        self.origins.insert("host", Source::Code(String::from("host()")));
+        self
+    }
+
+    fn port(mut self, port: u16) -> Self {
+        self.port = port;
+        self.origins.insert("port", Source::Code(String::from("port()")));
+        self
+    }
+
+    fn max_connections(mut self, max: u32) -> Self {
+        self.max_connections = max;
+        self.origins.insert("max_connections", Source::Code(String::from("max_connections()")));
+        self
+    }
+
+    fn timeout(mut self, seconds: u32) -> Self {
+        self.timeout_seconds = seconds;
+        self.origins.insert("timeout_seconds", Source::Code(String::from("timeout()")));
+        self
+    }
+
+    // Apply a single key/value pair, recording its source
+    // Every layer funnels through here, so parsing rules are identical
+    fn set(&mut self, key: &str, value: &str, source: Source) -> Result<(), ConfigError> {
+        let invalid = |source: Source| ConfigError::InvalidValue {
+            source,
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+
+        let key: &'static str = match key {
+            "host" => {
+                if value.is_empty() {
+                    return Err(invalid(source));
+                }
+                self.host = value.to_string();
+                "host"
+            }
+            "port" => {
+                self.port = value.parse().map_err(|_| invalid(source.clone()))?;
+                "port"
+            }
+            "max_connections" => {
+                self.max_connections = value.parse().map_err(|_| invalid(source.clone()))?;
+                "max_connections"
+            }
+            "timeout_seconds" => {
+                self.timeout_seconds = value.parse().map_err(|_| invalid(source.clone()))?;
+                "timeout_seconds"
+            }
+            other => {
+                return Err(ConfigError::UnknownKey { source, key: other.to_string() });
+            }
+        };
+        self.origins.insert(key, source);
+        Ok(())
+    }
+
+    // LAYER 2: a TOML file
+    // Supports the subset a server config needs: comments, an optional
+    // [server] table, and `key = value` with quoted strings or integers
+    fn with_toml_str(mut self, path: &Path, text: &str) -> Result<Self, ConfigError> {
+        let source = |line| Source::File { path: path.to_path_buf(), line };
+        let settings = parse_server_table(text).map_err(|e| match e {
+            TomlError::Syntax { line, message } => ConfigError::Syntax { source: source(line), message },
+            TomlError::DuplicateKey { line, key, first_line } => {
+                ConfigError::DuplicateKey { source: source(line), key, first_line }
+            }
+        })?;
+        for setting in settings {
+            self.set(&setting.key, &setting.value, source(setting.line))?;
+        }
+        Ok(self)
+    }
+
+    fn with_toml_file(self, path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|e| ConfigError::Io {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        self.with_toml_str(path, &text)
+    }
+
+    // LAYER 3: environment variables, e.g. HACKS_SERVER_PORT=9000
+    // Takes the variables as an iterator so callers can pass a fake environment
+    fn with_env_vars<I>(mut self, vars: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        // Sort so that errors are reported deterministically
+        let mut ours: Vec<(String, String)> = vars
+            .into_iter()
+            .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+            .collect();
+        ours.sort();
+
+        for (name, value) in ours {
+            let key = name[ENV_PREFIX.len()..].to_lowercase();
+            self.set(&key, &value, Source::Env(name.clone()))?;
+        }
+        Ok(self)
+    }
+
+    // The real environment may hold text that is not valid Unicode, where
+    // std::env::vars() would panic. A name like that is not one of ours and
+    // is skipped; one of ours with such a value is reported.
+    fn with_env_os<I>(self, vars: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = (OsString, OsString)>,
+    {
+        let mut text = Vec::new();
+        for (name, value) in vars {
+            let Ok(name) = name.into_string() else {
+                continue;
+            };
+            match value.into_string() {
+                Ok(value) => text.push((name, value)),
+                Err(value) if name.starts_with(ENV_PREFIX) => {
+                    return Err(ConfigError::InvalidValue {
+                        key: name[ENV_PREFIX.len()..].to_lowercase(),
+                        source: Source::Env(name),
+                        value: value.to_string_lossy().into_owned(),
+                    });
+                }
+                Err(_) => {}
+            }
+        }
+        self.with_env_vars(text)
+    }
+
+    fn with_env(self) -> Result<Self, ConfigError> {
+        self.with_env_os(std::env::vars_os())
+    }
+
+    // LAYER 4: command-line flags, `--port 9000` or `--port=9000`
+    // Underscores and dashes are interchangeable: --max-connections
+    fn with_args<I>(mut self, args: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                continue; // positional arguments belong to someone else
+            };
+            let (name, value) = match flag.split_once('=') {
+                Some((name, value)) => (name.to_string(), value.to_string()),
+                None => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| ConfigError::MissingValue { flag: arg.clone() })?;
+                    (flag.to_string(), value)
+                }
+            };
+            let key = name.replace('-', "_");
+            self.set(&key, &value, Source::Cli(format!("--{}", name)))?;
+        }
+        Ok(self)
+    }
+
+    // Where did the final value of `key` come from?
+    fn source_of(&self, key: &str) -> Option<&Source> {
+        self.origins.get(key)
+    }
+
+    // Human-readable report: one line per key, value and origin
+    fn explain(&self) -> String {
+        let mut report = String::new();
+        for key in KEYS {
+            let value = match key {
+                "host" => self.host.clone(),
+                "port" => self.port.to_string(),
+                "max_connections" => self.max_connections.to_string(),
+                _ => self.timeout_seconds.to_string(),
+            };
+            report.push_str(&format!("  {:<16} = {:<10} ({})\n", key, value, self.origins[key]));
+        }
+        report
+    }
+
+    // Consume the builder and create the final object; Server::validate
+    // still gets the last word
+    fn build(self) -> Result<Server, ConfigError> {
+        Server::builder()
+            .host(self.host)
+            .port(self.port)
+            .max_connections(self.max_connections)
+            .timeout(self.timeout_seconds)
+            .build()
+            .map_err(ConfigError::Invalid)
+    }
+}
+
+fn main() {
+    println!("=== Layered Server Configuration ===\n");
+
+    // Write a config file so the example is self-contained
+    let path = std::env::temp_dir().join("hacks_server.toml");
+    let toml = "\
+# Production overrides
+[server]
+host = \"10.0.0.5\"
+port = 8443
+max_connections = 5_000
+
+[logging]
+level = \"debug\"   # not ours, ignored
+";
+    fs::write(&path, toml).expect("could not write example config");
+
+    // A fake environment and command line keep the output predictable
+    let env = vec![
+        (String::from("HACKS_SERVER_PORT"), String::from("9000")),
+        (String::from("HACKS_SERVER_TIMEOUT_SECONDS"), String::from("45")),
+        (String::from("PATH"), String::from("/usr/bin")),
+    ];
+    let args = vec![String::from("--max-connections"), String::from("250")];
+
+    // Layers are applied lowest-first; each one can fail with ? semantics
+    let builder = ConfigBuilder::new()
+        .with_toml_file(&path)
+        .and_then(|b| b.with_env_vars(env))
+        .and_then(|b| b.with_args(args))
+        .expect("example config is valid");
+
+    println!("Where each value came from:");
+    print!("{}", builder.explain());
+    println!("\nWhy is the port {}? Because of {}", builder.port, builder.source_of("port").unwrap());
+
+    // Each key came from the highest layer that set it
+    assert_eq!(builder.source_of("host"), Some(&Source::File { path: path.clone(), line: 3 }));
+    assert_eq!(builder.source_of("port"), Some(&Source::Env(String::from("HACKS_SERVER_PORT"))));
+    assert_eq!(builder.source_of("max_connections"), Some(&Source::Cli(String::from("--max-connections"))));
+    assert_eq!(builder.source_of("timeout_seconds"), Some(&Source::Env(String::from("HACKS_SERVER_TIMEOUT_SECONDS"))));
+
+    let server = builder.build().expect("example config is valid");
+    println!("\nBuilt: {}:{} (max: {}, timeout: {}s)",
+        server.host, server.port,
+        server.max_connections, server.timeout_seconds);
+    assert_eq!((server.host.as_str(), server.port), ("10.0.0.5", 9000));
+    assert_eq!((server.max_connections, server.timeout_seconds), (250, 45));
+
+    // The real process environment and arguments work the same way:
+    //   HACKS_SERVER_HOST=0.0.0.0 cargo run --example m5_builder_config -- --port 3000
+    println!("\n=== From the real environment and command line ===");
+    match ConfigBuilder::new()
+        .with_env()
+        .and_then(|b| b.with_args(std::env::args().skip(1)))
+    {
+        Ok(builder) => print!("{}", builder.explain()),
+        Err(e) => println!("  Error: {}", e),
+    }
+
+    // Explicit setters still chain, exactly like m5_builder
+    let chained = ConfigBuilder::new().host("0.0.0.0").port(3000).max_connections(1000).timeout(60);
+    println!("\nChained setters are recorded too:");
+    print!("{}", chained.explain());
+    assert_eq!(chained.source_of("port"), Some(&Source::Code(String::from("port()"))));
+    assert_eq!(chained.source_of("timeout_seconds"), Some(&Source::Code(String::from("timeout()"))));
+    assert!(chained.build().is_ok());
+
+    // A later layer still overrides a setter called before it
+    let overridden = ConfigBuilder::new()
+        .port(3000)
+        .with_args(vec![String::from("--port=4000")])
+        .expect("valid flag");
+    assert_eq!(overridden.port, 4000);
+    assert_eq!(overridden.source_of("port"), Some(&Source::Cli(String::from("--port"))));
+
+    // Errors point at the exact source that was wrong
+    println!("\n=== Error reporting ===");
+    let bad_file = ConfigBuilder::new().with_toml_str(Path::new("bad.toml"), "port = 99999");
+    let bad_env = ConfigBuilder::new()
+        .with_env_vars(vec![(String::from("HACKS_SERVER_COLOR"), String::from("blue"))]);
+    let bad_cli = ConfigBuilder::new().with_args(vec![String::from("--timeout-seconds")]);
+    let duplicate = ConfigBuilder::new().with_toml_str(Path::new("dup.toml"), "port = 1\n\nport = 2");
+    // Every layer parsed, but Server::validate refuses the result
+    let no_connections = ConfigBuilder::new().with_args(vec![String::from("--max-connections=0")]).and_then(|b| b.build());
+    let errors: Vec<ConfigError> = [bad_file, bad_env, bad_cli, duplicate]
+        .into_iter()
+        .map(|result| result.err().expect("every example here is invalid"))
+        .chain(no_connections.err())
+        .collect();
+    for e in &errors {
+        println!("  Error: {}", e);
+    }
+    assert!(matches!(&errors[0], ConfigError::InvalidValue { key, .. } if key == "port"));
+    assert!(matches!(&errors[1], ConfigError::UnknownKey { key, .. } if key == "color"));
+    assert!(matches!(&errors[2], ConfigError::MissingValue { flag } if flag == "--timeout-seconds"));
+    assert!(matches!(
+        &errors[3],
+        ConfigError::DuplicateKey { source: Source::File { line: 3, .. }, key, first_line: 1 } if key == "port"
+    ));
+    assert!(matches!(&errors[4], ConfigError::Invalid(ServerBuilderError::Invalid(_))));
+
+    // Text that is not Unicode never panics: someone else's variable is
+    // skipped, and a bad value in one of ours is an error
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        let garbage = || OsString::from_vec(vec![b'a', 0xff]);
+        let skipped = ConfigBuilder::new()
+            .with_env_os(vec![(garbage(), OsString::from("x")), (OsString::from("LANG"), garbage())])
+            .expect("not our variables");
+        assert_eq!(skipped.source_of("port"), Some(&Source::Default));
+        let ours = ConfigBuilder::new().with_env_os(vec![(OsString::from("HACKS_SERVER_HOST"), garbage())]);
+        let e = ours.err().expect("not valid Unicode");
+        println!("  Error: {}", e);
+        assert!(matches!(e, ConfigError::InvalidValue { key, .. } if key == "host"));
+    }
+
+    let _ = fs::remove_file(&path);
+}
@@ -0,0 +1,483 @@
+// cargo run --example m5_builder_reload
+//
+// Demonstrates HOT-RELOADING a Server configuration.
+// A ConfigWatcher polls a config file, rebuilds the Server through the
+// builder (so validation still applies), and tells subscribers exactly
+// what changed:
+//
+//   timeout_seconds: 30 -> 60
+//
+// A broken or invalid file is rejected and the last good config is kept.
+// Time comes from a Clock trait, so the demo can move time forward by hand
+// instead of sleeping.
+//
+// cargo test --example m5_builder_reload runs the tests at the bottom.
+
+use std::cell::Cell;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+// The same TOML subset reader as m5_builder_config
+#[path = "common/config_text.rs"]
+mod config_text;
+use config_text::parse_server_table;
+
+// The Server and ServerBuilder from m5_builder
+#[path = "common/server.rs"]
+mod server;
+use server::{Server, ServerBuilder};
+
+// ---------------------------------------------------------------------
+// Typed diff
+// ---------------------------------------------------------------------
+
+// One changed field, keeping the real types of the old and new values
+#[derive(Debug, Clone, PartialEq)]
+enum Change {
+    Host { old: String, new: String },
+    Port { old: u16, new: u16 },
+    MaxConnections { old: u32, new: u32 },
+    TimeoutSeconds { old: u32, new: u32 },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Change::Host { old, new } => write!(f, "host: {} -> {}", old, new),
+            Change::Port { old, new } => write!(f, "port: {} -> {}", old, new),
+            Change::MaxConnections { old, new } => {
+                write!(f, "max_connections: {} -> {}", old, new)
+            }
+            Change::TimeoutSeconds { old, new } => {
+                write!(f, "timeout_seconds: {} -> {}", old, new)
+            }
+        }
+    }
+}
+
+// Compare two configs field by field
+fn diff(old: &Server, new: &Server) -> Vec<Change> {
+    let mut changes = Vec::new();
+    if old.host != new.host {
+        changes.push(Change::Host { old: old.host.clone(), new: new.host.clone() });
+    }
+    if old.port != new.port {
+        changes.push(Change::Port { old: old.port, new: new.port });
+    }
+    if old.max_connections != new.max_connections {
+        changes.push(Change::MaxConnections { old: old.max_connections, new: new.max_connections });
+    }
+    if old.timeout_seconds != new.timeout_seconds {
+        changes.push(Change::TimeoutSeconds { old: old.timeout_seconds, new: new.timeout_seconds });
+    }
+    changes
+}
+
+// ---------------------------------------------------------------------
+// Parsing the config file through the builder
+// ---------------------------------------------------------------------
+
+// The [server] table of a TOML file (or bare `key = value` lines);
+// # starts a comment, strings are quoted, numbers are bare, and a key
+// may only be set once
+fn parse_config(text: &str) -> Result<Server, String> {
+    let mut builder = ServerBuilder::new();
+
+    for setting in parse_server_table(text).map_err(|e| e.to_string())? {
+        let (line_no, key, value) = (setting.line, setting.key.as_str(), setting.value.as_str());
+        let bad = |_| format!("line {}: invalid value '{}' for '{}'", line_no, value, key);
+
+        builder = match key {
+            "host" => builder.host(value),
+            "port" => builder.port(value.parse().map_err(bad)?),
+            "max_connections" => builder.max_connections(value.parse().map_err(bad)?),
+            "timeout_seconds" => builder.timeout(value.parse().map_err(bad)?),
+            other => return Err(format!("line {}: unknown key '{}'", line_no, other)),
+        };
+    }
+
+    // build() runs Server::validate, so reloads obey the same rules
+    builder.build().map_err(|e| e.to_string())
+}
+
+// ---------------------------------------------------------------------
+// Clocks
+// ---------------------------------------------------------------------
+
+// "How long since the watcher started?" - abstracted so it can be faked
+trait Clock {
+    fn now(&self) -> Duration;
+}
+
+struct SystemClock(Instant);
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+// A clock that only moves when told to; clones share the same time
+#[derive(Clone, Default)]
+struct ManualClock(Rc<Cell<Duration>>);
+
+impl ManualClock {
+    fn advance(&self, by: Duration) {
+        self.0.set(self.0.get().saturating_add(by));
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.0.get()
+    }
+}
+
+// ---------------------------------------------------------------------
+// The watcher
+// ---------------------------------------------------------------------
+
+// What subscribers hear about
+#[derive(Debug)]
+enum ReloadEvent<'a> {
+    Applied { changes: &'a [Change], config: &'a Server },
+    Rejected { reason: &'a str, kept: &'a Server },
+}
+
+type Subscriber = Box<dyn FnMut(&ReloadEvent)>;
+
+struct ConfigWatcher<C: Clock> {
+    path: PathBuf,
+    clock: C,
+    interval: Duration,
+    next_poll: Duration,
+    last_text: Option<String>,
+    current: Server,
+    subscribers: Vec<Subscriber>,
+}
+
+impl<C: Clock> ConfigWatcher<C> {
+    // The initial load must succeed - there is no "last good" config yet
+    fn new(path: impl Into<PathBuf>, clock: C, interval: Duration) -> Result<Self, String> {
+        let path = path.into();
+        let text = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        let current = parse_config(&text)?;
+        // A huge interval means "never again", not an overflow panic
+        let next_poll = clock.now().saturating_add(interval);
+        Ok(ConfigWatcher {
+            path,
+            clock,
+            interval,
+            next_poll,
+            last_text: Some(text),
+            current,
+            subscribers: Vec::new(),
+        })
+    }
+
+    fn subscribe(&mut self, callback: impl FnMut(&ReloadEvent) + 'static) {
+        self.subscribers.push(Box::new(callback));
+    }
+
+    fn current(&self) -> &Server {
+        &self.current
+    }
+
+    // Call as often as you like; the file is only read once per interval.
+    // Returns the changes that were applied (empty if nothing happened).
+    fn poll(&mut self) -> Vec<Change> {
+        let now = self.clock.now();
+        if now < self.next_poll {
+            return Vec::new();
+        }
+        self.next_poll = now.saturating_add(self.interval);
+
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) => {
+                self.reject(&format!("cannot read {}: {}", self.path.display(), e));
+                return Vec::new();
+            }
+        };
+        // Unchanged file: nothing to do (and no repeated rejections)
+        if self.last_text.as_deref() == Some(text.as_str()) {
+            return Vec::new();
+        }
+        self.last_text = Some(text.clone());
+
+        match parse_config(&text) {
+            Ok(candidate) => {
+                let changes = diff(&self.current, &candidate);
+                if changes.is_empty() {
+                    return changes; // e.g. only comments changed
+                }
+                self.current = candidate;
+                let event = ReloadEvent::Applied { changes: &changes, config: &self.current };
+                for subscriber in &mut self.subscribers {
+                    subscriber(&event);
+                }
+                changes
+            }
+            Err(reason) => {
+                self.reject(&reason);
+                Vec::new()
+            }
+        }
+    }
+
+    fn reject(&mut self, reason: &str) {
+        let event = ReloadEvent::Rejected { reason, kept: &self.current };
+        for subscriber in &mut self.subscribers {
+            subscriber(&event);
+        }
+    }
+}
+
+fn main() -> Result<(), String> {
+    println!("=== Hot-Reloading Server Configuration ===\n");
+
+    let path = std::env::temp_dir().join("hacks_reload.conf");
+    let write = |text: &str| fs::write(&path, text).expect("could not write config");
+    write("host = \"0.0.0.0\"\nport = 8080\n");
+
+    // A manual clock and a 5 second poll interval
+    let clock = ManualClock::default();
+    let mut watcher = ConfigWatcher::new(&path, clock.clone(), Duration::from_secs(5))?;
+    println!("Initial: {:?}\n", watcher.current());
+
+    // Subscribers are closures; this one just logs every event
+    watcher.subscribe(|event| match event {
+        ReloadEvent::Applied { changes, .. } => {
+            for change in changes.iter() {
+                println!("  [log] changed {}", change);
+            }
+        }
+        ReloadEvent::Rejected { reason, kept } => {
+            println!("  [log] rejected ({}), keeping port {}", reason, kept.port);
+        }
+    });
+
+    // A second subscriber reacts to the new config and counts reloads
+    let reloads = Rc::new(Cell::new(0));
+    let counter = Rc::clone(&reloads);
+    watcher.subscribe(move |event| {
+        if let ReloadEvent::Applied { config, .. } = event {
+            counter.set(counter.get() + 1);
+            println!("  [app] now {}:{} (timeout {}s)", config.host, config.port, config.timeout_seconds);
+        }
+    });
+
+    println!("Edit: timeout_seconds = 60");
+    write("host = \"0.0.0.0\"\nport = 8080\ntimeout_seconds = 60\n");
+    println!("  poll before the interval -> {:?}", watcher.poll());
+    clock.advance(Duration::from_secs(5));
+    let changes = watcher.poll();
+    assert_eq!(changes, vec![Change::TimeoutSeconds { old: 30, new: 60 }]);
+
+    println!("\nEdit: several fields at once");
+    write("host = \"10.0.0.1\"\nport = 9000\ntimeout_seconds = 60\nmax_connections = 500\n");
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(watcher.poll().len(), 3);
+
+    println!("\nEdit: invalid value (max_connections = 0)");
+    write("host = \"10.0.0.1\"\nport = 9000\nmax_connections = 0\n");
+    clock.advance(Duration::from_secs(5));
+    assert!(watcher.poll().is_empty());
+    assert_eq!(watcher.current().max_connections, 500);
+
+    println!("\nEdit: syntax error");
+    write("port = ninety\n");
+    clock.advance(Duration::from_secs(5));
+    let before = watcher.current().clone();
+    assert!(watcher.poll().is_empty());
+    assert_eq!(watcher.current(), &before);
+    println!("  still serving on port {}", watcher.current().port);
+
+    println!("\nEdit: only a comment changes");
+    write("# tuned for production\nhost = \"10.0.0.1\"\nport = 9000\ntimeout_seconds = 60\nmax_connections = 500\n");
+    clock.advance(Duration::from_secs(5));
+    let changes = watcher.poll();
+    println!("  changes: {:?}", changes);
+    assert!(changes.is_empty());
+
+    println!("\nFinal:   {:?}", watcher.current());
+    println!("Applied reloads: {}", reloads.get());
+    assert_eq!(reloads.get(), 2);
+
+    // In a real program the system clock drives the same code
+    let mut live = ConfigWatcher::new(&path, SystemClock(Instant::now()), Duration::from_millis(1))?;
+    std::thread::sleep(Duration::from_millis(2));
+    println!("\nWith SystemClock, unchanged file -> {:?}", live.poll());
+
+    let _ = fs::remove_file(&path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    // A config file in the temp dir, unique per test, removed on drop
+    struct TempConfig(PathBuf);
+
+    impl TempConfig {
+        fn new(name: &str, text: &str) -> Self {
+            let file = format!("hacks_reload_{}_{}.conf", std::process::id(), name);
+            let config = TempConfig(std::env::temp_dir().join(file));
+            config.write(text);
+            config
+        }
+
+        fn write(&self, text: &str) {
+            fs::write(&self.0, text).expect("could not write config");
+        }
+    }
+
+    impl Drop for TempConfig {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    const INTERVAL: Duration = Duration::from_secs(5);
+
+    // A watcher on a manual clock plus a log of every event it sends
+    fn watch(config: &TempConfig) -> (ConfigWatcher<ManualClock>, ManualClock, Rc<RefCell<Vec<String>>>) {
+        let clock = ManualClock::default();
+        let mut watcher = ConfigWatcher::new(&config.0, clock.clone(), INTERVAL).unwrap();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let log = Rc::clone(&events);
+        watcher.subscribe(move |event| {
+            log.borrow_mut().push(match event {
+                ReloadEvent::Applied { changes, .. } => format!("applied {}", changes.len()),
+                ReloadEvent::Rejected { reason, .. } => format!("rejected {}", reason),
+            })
+        });
+        (watcher, clock, events)
+    }
+
+    #[test]
+    fn hash_inside_quotes_is_not_a_comment() {
+        let server = parse_config("host = \"a#b\"  # trailing comment\n").unwrap();
+        assert_eq!(server.host, "a#b");
+    }
+
+    #[test]
+    fn unbalanced_quotes_are_rejected() {
+        for text in ["host = \"abc\n", "host = abc\"\n", "host = \"a\"b\"\n", "host = \"\n"] {
+            let err = parse_config(text).unwrap_err();
+            assert!(err.starts_with("line 1: cannot parse value"), "{:?} -> {}", text, err);
+        }
+    }
+
+    #[test]
+    fn bad_lines_report_their_number() {
+        assert_eq!(parse_config("\n\nport\n").unwrap_err(), "line 3: expected `key = value`");
+        assert_eq!(parse_config("colour = 1\n").unwrap_err(), "line 1: unknown key 'colour'");
+        assert_eq!(parse_config("port = 70000\n").unwrap_err(), "line 1: invalid value '70000' for 'port'");
+    }
+
+    #[test]
+    fn server_table_and_duplicates_follow_the_config_rules() {
+        let text = "[server]\nport = 9000\n\n[logging]\nlevel = \"debug\"\n";
+        assert_eq!(parse_config(text).unwrap().port, 9000);
+        assert_eq!(parse_config("port = 1\n\nport = 2\n").unwrap_err(), "line 3: 'port' was already set on line 1");
+        assert_eq!(parse_config("[server\n").unwrap_err(), "line 1: unterminated table header");
+    }
+
+    #[test]
+    fn a_huge_interval_does_not_overflow() {
+        let config = TempConfig::new("huge", "port = 8080\n");
+        let clock = ManualClock::default();
+        clock.advance(Duration::from_secs(1));
+        let mut watcher = ConfigWatcher::new(&config.0, clock.clone(), Duration::MAX).unwrap();
+        config.write("port = 9000\n");
+        clock.advance(Duration::MAX);
+        assert_eq!(watcher.poll(), vec![Change::Port { old: 8080, new: 9000 }]);
+        // The next poll saturates at Duration::MAX instead of panicking
+        assert!(watcher.poll().is_empty());
+    }
+
+    #[test]
+    fn validation_applies_to_parsed_files() {
+        assert!(parse_config("timeout_seconds = 0\n").is_err());
+        assert!(parse_config("max_connections = 0\n").is_err());
+    }
+
+    #[test]
+    fn file_is_only_read_once_per_interval() {
+        let config = TempConfig::new("interval", "port = 8080\n");
+        let (mut watcher, clock, events) = watch(&config);
+
+        config.write("port = 9000\n");
+        clock.advance(INTERVAL - Duration::from_millis(1));
+        assert!(watcher.poll().is_empty());
+        assert_eq!(watcher.current().port, 8080);
+
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(watcher.poll(), vec![Change::Port { old: 8080, new: 9000 }]);
+        assert_eq!(*events.borrow(), ["applied 1"]);
+
+        // The next poll is a full interval later again
+        config.write("port = 9001\n");
+        clock.advance(INTERVAL - Duration::from_millis(1));
+        assert!(watcher.poll().is_empty());
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(watcher.poll(), vec![Change::Port { old: 9000, new: 9001 }]);
+    }
+
+    #[test]
+    fn broken_files_keep_the_last_good_config() {
+        let config = TempConfig::new("broken", "port = 8080\n");
+        let (mut watcher, clock, events) = watch(&config);
+
+        config.write("port = ninety\n");
+        clock.advance(INTERVAL);
+        assert!(watcher.poll().is_empty());
+        assert_eq!(watcher.current().port, 8080);
+        assert_eq!(*events.borrow(), ["rejected line 1: cannot parse value `ninety`"]);
+
+        // The same broken text is not rejected again on every poll
+        clock.advance(INTERVAL);
+        assert!(watcher.poll().is_empty());
+        assert_eq!(events.borrow().len(), 1);
+
+        // Fixing the file applies it
+        config.write("port = 9000\n");
+        clock.advance(INTERVAL);
+        assert_eq!(watcher.poll(), vec![Change::Port { old: 8080, new: 9000 }]);
+    }
+
+    #[test]
+    fn comment_only_edits_change_nothing() {
+        let config = TempConfig::new("comment", "port = 8080\n");
+        let (mut watcher, clock, events) = watch(&config);
+
+        config.write("# moved to the new rack\nport = 8080 # same port\n");
+        clock.advance(INTERVAL);
+        assert!(watcher.poll().is_empty());
+        assert!(events.borrow().is_empty());
+    }
+
+    #[test]
+    fn a_missing_file_is_rejected() {
+        let config = TempConfig::new("missing", "port = 8080\n");
+        let (mut watcher, clock, events) = watch(&config);
+
+        fs::remove_file(&config.0).unwrap();
+        clock.advance(INTERVAL);
+        assert!(watcher.poll().is_empty());
+        assert_eq!(watcher.current().port, 8080);
+        assert!(events.borrow()[0].starts_with("rejected cannot read"));
+    }
+
+    #[test]
+    fn the_initial_load_must_succeed() {
+        let config = TempConfig::new("initial", "port = -1\n");
+        assert!(ConfigWatcher::new(&config.0, ManualClock::default(), INTERVAL).is_err());
+    }
+}
@@ -0,0 +1,630 @@
+// cargo run --example m5_builder_serve
+//
+// Makes the Server from m5_builder actually SERVE: a minimal HTTP/1.1
+// listener built only on the standard library.
+//
+// Every field of the config now means something:
+//   host:port        - where the TcpListener binds
+//   max_connections  - connections beyond this get "503 Service Unavailable"
+//   timeout_seconds  - read/write timeout on each connection
+//
+// The response comes from a pluggable handler closure, and the server
+// shuts down gracefully (finishing in-flight requests) when told to.
+//
+// The loopback tests at the bottom run with:
+//   cargo test --example m5_builder_serve
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+// The permit-based limiter explained in m5_connection_limit
+#[path = "common/limiter.rs"]
+mod limiter;
+use limiter::ConnectionLimiter;
+
+// The Server and ServerBuilder from m5_builder
+#[path = "common/server.rs"]
+mod server;
+use server::{Server, ServerBuilder};
+
+// ---------------------------------------------------------------------
+// HTTP messages
+// ---------------------------------------------------------------------
+
+// A parsed request - just enough HTTP/1.1 for small services
+#[derive(Debug)]
+struct Request {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>, // names are lower-cased
+    body: Vec<u8>,
+}
+
+#[derive(Debug)]
+struct Response {
+    status: u16,
+    reason: &'static str,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn new(status: u16, reason: &'static str, body: impl Into<Vec<u8>>) -> Self {
+        Response { status, reason, content_type: "text/plain; charset=utf-8", body: body.into() }
+    }
+
+    fn ok(body: impl Into<Vec<u8>>) -> Self {
+        Response::new(200, "OK", body)
+    }
+
+    fn not_found() -> Self {
+        Response::new(404, "Not Found", "not found\n")
+    }
+
+    // Serialize to the wire format; every connection is closed afterwards
+    fn write_to(&self, stream: &mut impl Write) -> io::Result<()> {
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.status,
+            self.reason,
+            self.content_type,
+            self.body.len()
+        )?;
+        stream.write_all(&self.body)?;
+        stream.flush()
+    }
+}
+
+// Why a request could not be read
+enum ReadError {
+    TimedOut,
+    HeadTooLarge,
+    Malformed(&'static str),
+    Io,
+}
+
+impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => ReadError::TimedOut,
+            _ => ReadError::Io,
+        }
+    }
+}
+
+// Largest request head and body we are willing to buffer
+const MAX_HEAD_BYTES: usize = 8 * 1024;
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+fn read_request(stream: &TcpStream) -> Result<Request, ReadError> {
+    let mut reader = BufReader::new(stream);
+
+    // The whole head is read through one `take`, so a client that never
+    // sends a newline cannot make us buffer more than MAX_HEAD_BYTES + 1
+    let mut head = reader.by_ref().take(MAX_HEAD_BYTES as u64 + 1);
+
+    // Request line: METHOD SP PATH SP VERSION
+    let line = read_head_line(&mut head)?.ok_or(ReadError::Malformed("empty request"))?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(ReadError::Malformed("bad request line"));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(ReadError::Malformed("unsupported HTTP version"));
+    }
+    let (method, path) = (method.to_string(), path.to_string());
+
+    // Headers until the blank line
+    let mut headers = HashMap::new();
+    loop {
+        let line = read_head_line(&mut head)?.ok_or(ReadError::Malformed("incomplete headers"))?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let (name, value) = header
+            .split_once(':')
+            .ok_or(ReadError::Malformed("bad header line"))?;
+        headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+    }
+
+    // Body, if the client announced one
+    let length = match headers.get("content-length") {
+        Some(value) => value
+            .parse::<usize>()
+            .map_err(|_| ReadError::Malformed("bad Content-Length"))?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(ReadError::Malformed("body too large"));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    Ok(Request { method, path, headers, body })
+}
+
+// One line of the head, newline included. None if the client closed the
+// connection before sending anything.
+fn read_head_line(head: &mut io::Take<&mut BufReader<&TcpStream>>) -> Result<Option<String>, ReadError> {
+    let mut line = Vec::new();
+    let n = head.read_until(b'\n', &mut line)?;
+    if !line.ends_with(b"\n") {
+        return match (head.limit(), n) {
+            (0, _) => Err(ReadError::HeadTooLarge),
+            (_, 0) => Ok(None),
+            _ => Err(ReadError::Malformed("incomplete request head")),
+        };
+    }
+    String::from_utf8(line).map(Some).map_err(|_| ReadError::Malformed("request head is not valid UTF-8"))
+}
+
+// ---------------------------------------------------------------------
+// Shutdown
+// ---------------------------------------------------------------------
+
+// Cloneable "please stop" flag shared with the accept loop
+#[derive(Clone, Default)]
+struct ShutdownHandle(Arc<AtomicBool>);
+
+impl ShutdownHandle {
+    fn trigger(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    fn is_triggered(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    // Shut down when a message (the "signal") arrives on the channel.
+    // Real OS signals such as Ctrl+C need a crate like `ctrlc`, whose
+    // callback can simply send on this channel.
+    fn trigger_on(&self, signal: Receiver<()>) {
+        let handle = self.clone();
+        thread::spawn(move || {
+            // A closed channel counts as a signal too
+            let _ = signal.recv();
+            handle.trigger();
+        });
+    }
+}
+
+// A server running on a background thread
+struct RunningServer {
+    addr: SocketAddr,
+    shutdown: ShutdownHandle,
+    thread: JoinHandle<io::Result<()>>,
+}
+
+impl RunningServer {
+    // Stop accepting, let in-flight requests finish, then return
+    fn shutdown(self) -> io::Result<()> {
+        self.shutdown.trigger();
+        self.thread.join().expect("server thread panicked")
+    }
+}
+
+// How often the accept loop checks the shutdown flag
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+impl Server {
+    // Never zero: validate() refuses that
+    fn io_timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_seconds as u64)
+    }
+
+    fn bind(&self) -> io::Result<TcpListener> {
+        let listener = TcpListener::bind((self.host.as_str(), self.port))?;
+        // Non-blocking accept lets the loop notice a shutdown request
+        listener.set_nonblocking(true)?;
+        Ok(listener)
+    }
+
+    // Bind host:port and serve until `shutdown` is triggered (blocking)
+    fn run<F>(&self, handler: F, shutdown: ShutdownHandle) -> io::Result<()>
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        let listener = self.bind()?;
+        self.serve(listener, Arc::new(handler), shutdown)
+    }
+
+    // Bind now, serve on a background thread; handy for tests and demos
+    fn spawn<F>(self, handler: F) -> io::Result<RunningServer>
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        let listener = self.bind()?;
+        let addr = listener.local_addr()?;
+        let shutdown = ShutdownHandle::default();
+        let flag = shutdown.clone();
+        let thread = thread::spawn(move || self.serve(listener, Arc::new(handler), flag));
+        Ok(RunningServer { addr, shutdown, thread })
+    }
+
+    fn serve<F>(
+        &self,
+        listener: TcpListener,
+        handler: Arc<F>,
+        shutdown: ShutdownHandle,
+    ) -> io::Result<()>
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        let limiter = ConnectionLimiter::new(self.max_connections as usize)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        while !shutdown.is_triggered() {
+            let (stream, _peer) = match listener.accept() {
+                Ok(conn) => conn,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    thread::sleep(POLL_INTERVAL);
+                    continue;
+                }
+                // One failed accept (the client gave up, or we are out of
+                // file descriptors) must not stop the server. Pause so a
+                // persistent error does not spin.
+                Err(e) => {
+                    eprintln!("accept failed: {}", e);
+                    thread::sleep(POLL_INTERVAL);
+                    continue;
+                }
+            };
+
+            // Enforce max_connections before spending a thread on it
+            let Some(permit) = limiter.try_acquire() else {
+                reject(stream);
+                continue;
+            };
+
+            // Accepted sockets may inherit non-blocking mode; undo that
+            let timeout = Some(self.io_timeout());
+            let setup = stream
+                .set_nonblocking(false)
+                .and_then(|_| stream.set_read_timeout(timeout))
+                .and_then(|_| stream.set_write_timeout(timeout));
+            if let Err(e) = setup {
+                eprintln!("dropping connection: {}", e);
+                continue;
+            }
+
+            let handler = Arc::clone(&handler);
+            thread::spawn(move || {
+                let _permit = permit; // released when this thread ends
+                handle_connection(stream, handler.as_ref());
+            });
+        }
+
+        // Graceful: stop accepting, but let in-flight requests finish
+        drop(listener);
+        limiter.wait_idle();
+        Ok(())
+    }
+}
+
+fn handle_connection<F>(mut stream: TcpStream, handler: &F)
+where
+    F: Fn(&Request) -> Response,
+{
+    let response = match read_request(&stream) {
+        Ok(request) => handler(&request),
+        Err(ReadError::TimedOut) => Response::new(408, "Request Timeout", "request timeout\n"),
+        Err(ReadError::HeadTooLarge) => {
+            Response::new(431, "Request Header Fields Too Large", "request head too large\n")
+        }
+        Err(ReadError::Malformed(why)) => Response::new(400, "Bad Request", format!("{}\n", why)),
+        Err(ReadError::Io) => return, // client went away, nobody to answer
+    };
+    // Write errors (including write timeouts) just drop the connection
+    if response.write_to(&mut stream).is_ok() {
+        close_gracefully(stream);
+    }
+}
+
+// How long the accept loop may spend turning one client away
+const REJECT_TIMEOUT: Duration = Duration::from_millis(20);
+
+// Answer 503 without reading the request. This runs on the accept loop
+// itself, so a flood of clients costs no threads, and every step has a
+// short timeout. Request bytes that already arrived are drained before
+// closing; otherwise the OS resets the connection and the client may
+// never see the response.
+fn reject(mut stream: TcpStream) {
+    let busy = Response::new(503, "Service Unavailable", "too many connections\n");
+    let sent = stream
+        .set_nonblocking(false)
+        .and_then(|_| stream.set_write_timeout(Some(REJECT_TIMEOUT)))
+        .and_then(|_| busy.write_to(&mut stream));
+    if sent.is_ok() {
+        close_gracefully(stream);
+    }
+}
+
+// Send our FIN, then read and discard a bounded amount of what the client
+// is still sending, so closing does not reset the connection under a
+// response that was just written
+fn close_gracefully(mut stream: TcpStream) {
+    let _ = stream.shutdown(Shutdown::Write);
+    let _ = stream.set_read_timeout(Some(REJECT_TIMEOUT));
+    let mut scratch = [0; 4096];
+    for _ in 0..16 {
+        match stream.read(&mut scratch) {
+            Ok(n) if n > 0 => continue,
+            _ => break,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------
+// Loopback client used by the demo
+// ---------------------------------------------------------------------
+
+// Send a raw request and return (status code, body)
+fn send(addr: SocketAddr, raw: &str) -> io::Result<(u16, String)> {
+    let mut stream = TcpStream::connect(addr)?;
+    stream.write_all(raw.as_bytes())?;
+    read_reply(stream)
+}
+
+// Read whatever the server sends on an already-open connection
+fn read_reply(mut stream: TcpStream) -> io::Result<(u16, String)> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+    let status = reply
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .unwrap_or(0);
+    let body = reply.split_once("\r\n\r\n").map(|(_, b)| b.to_string()).unwrap_or_default();
+    Ok((status, body))
+}
+
+fn get(addr: SocketAddr, path: &str) -> io::Result<(u16, String)> {
+    send(addr, &format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path))
+}
+
+fn main() -> io::Result<()> {
+    println!("=== Serving HTTP from a built Server ===\n");
+
+    // Port 0 asks the OS for any free port; a short timeout keeps the demo quick
+    let server = ServerBuilder::new()
+        .host("127.0.0.1")
+        .port(0)
+        .max_connections(2)
+        .timeout(1)
+        .build()
+        .expect("valid config");
+    println!(
+        "Config: {}:{} (max: {}, timeout: {}s)",
+        server.host, server.port, server.max_connections, server.timeout_seconds
+    );
+
+    // The handler is just a closure from Request to Response
+    let running = server.spawn(|req: &Request| match (req.method.as_str(), req.path.as_str()) {
+        ("GET", "/") => Response::ok("hello from hacks\n"),
+        ("POST", "/echo") => Response::ok(req.body.clone()),
+        ("GET", "/agent") => {
+            let agent = req.headers.get("user-agent").map(String::as_str).unwrap_or("unknown");
+            Response::ok(format!("you are {}\n", agent))
+        }
+        _ => Response::not_found(),
+    })?;
+    println!("Listening on {}\n", running.addr);
+
+    // Routing through the handler
+    let (status, body) = get(running.addr, "/")?;
+    println!("GET /        -> {} {:?}", status, body);
+    assert_eq!((status, body.as_str()), (200, "hello from hacks\n"));
+
+    let (status, body) = send(
+        running.addr,
+        "POST /echo HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello",
+    )?;
+    println!("POST /echo   -> {} {:?}", status, body);
+    assert_eq!((status, body.as_str()), (200, "hello"));
+
+    let (status, body) = send(running.addr, "GET /agent HTTP/1.1\r\nUser-Agent: demo\r\n\r\n")?;
+    println!("GET /agent   -> {} {:?}", status, body);
+    assert_eq!(status, 200);
+
+    let (status, _) = get(running.addr, "/missing")?;
+    println!("GET /missing -> {}", status);
+    assert_eq!(status, 404);
+
+    let (status, body) = send(running.addr, "NONSENSE\r\n\r\n")?;
+    println!("Garbage      -> {} {:?}", status, body);
+    assert_eq!(status, 400);
+
+    // max_connections: hold two idle connections open, the third is refused
+    println!("\nHolding {} idle connections open...", 2);
+    let idle1 = TcpStream::connect(running.addr)?;
+    let idle2 = TcpStream::connect(running.addr)?;
+    thread::sleep(Duration::from_millis(100)); // let the server accept them
+    let (status, body) = get(running.addr, "/")?;
+    println!("Third client -> {} {:?}", status, body);
+    assert_eq!(status, 503);
+
+    // timeout_seconds: the idle connections get 408 and free their slots
+    let (status, _) = read_reply(idle1)?;
+    println!("Idle client  -> {} after the read timeout", status);
+    assert_eq!(status, 408);
+    let _ = read_reply(idle2)?;
+    thread::sleep(Duration::from_millis(50)); // the slot frees just after the close
+    let (status, _) = get(running.addr, "/")?;
+    println!("Retry        -> {} (slots are free again)", status);
+    assert_eq!(status, 200);
+
+    // Graceful shutdown: a slow request in flight still gets its answer
+    println!("\n=== Graceful shutdown ===");
+    let addr = running.addr;
+    let slow = thread::spawn(move || {
+        let mut stream = TcpStream::connect(addr)?;
+        stream.write_all(b"POST /echo HTTP/1.1\r\nContent-Length: 4\r\n\r\n")?;
+        thread::sleep(Duration::from_millis(300)); // body arrives late
+        stream.write_all(b"late")?;
+        read_reply(stream)
+    });
+    thread::sleep(Duration::from_millis(100));
+    running.shutdown()?;
+    let (status, body) = slow.join().unwrap()?;
+    println!("In-flight request finished: {} {:?}", status, body);
+    assert_eq!((status, body.as_str()), (200, "late"));
+    println!("New connections refused: {}", TcpStream::connect(addr).is_err());
+
+    // Blocking run() with a channel acting as the shutdown signal
+    println!("\n=== run() until a signal ===");
+    let (signal, on_signal) = std::sync::mpsc::channel();
+    let shutdown = ShutdownHandle::default();
+    shutdown.trigger_on(on_signal);
+    let server = ServerBuilder::new().host("127.0.0.1").port(0).build().expect("valid config");
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(200));
+        let _ = signal.send(()); // e.g. from a Ctrl+C callback
+    });
+    server.run(|_req: &Request| Response::ok("bye\n"), shutdown)?;
+    println!("run() returned after the signal");
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------
+// Loopback tests
+// ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn router(req: &Request) -> Response {
+        match (req.method.as_str(), req.path.as_str()) {
+            ("GET", "/") => Response::ok("hello\n"),
+            ("POST", "/echo") => Response::ok(req.body.clone()),
+            _ => Response::not_found(),
+        }
+    }
+
+    fn start(max_connections: u32, timeout_seconds: u32) -> RunningServer {
+        ServerBuilder::new()
+            .host("127.0.0.1")
+            .port(0)
+            .max_connections(max_connections)
+            .timeout(timeout_seconds)
+            .build()
+            .expect("valid config")
+            .spawn(router)
+            .expect("bind to loopback")
+    }
+
+    // Send raw bytes (not necessarily UTF-8) and read the reply
+    fn send_bytes(addr: SocketAddr, raw: &[u8]) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(raw).unwrap();
+        read_reply(stream).unwrap()
+    }
+
+    #[test]
+    fn routes_requests_through_the_handler() {
+        let server = start(4, 5);
+        assert_eq!(get(server.addr, "/").unwrap(), (200, String::from("hello\n")));
+        assert_eq!(send(server.addr, "POST /echo HTTP/1.1\r\nContent-Length: 3\r\n\r\nabc").unwrap(), (200, String::from("abc")));
+        assert_eq!(get(server.addr, "/nope").unwrap().0, 404);
+        server.shutdown().unwrap();
+    }
+
+    #[test]
+    fn malformed_requests_get_400() {
+        let server = start(4, 5);
+        assert_eq!(send(server.addr, "NONSENSE\r\n\r\n").unwrap().0, 400);
+        assert_eq!(send(server.addr, "GET / SPDY/3\r\n\r\n").unwrap().0, 400);
+        assert_eq!(send(server.addr, "GET / HTTP/1.1\r\nno colon here\r\n\r\n").unwrap().0, 400);
+        server.shutdown().unwrap();
+    }
+
+    #[test]
+    fn non_utf8_head_gets_400() {
+        let server = start(4, 5);
+        let (status, body) = send_bytes(server.addr, b"GET / HTTP/1.1\r\nX-Name: \xff\xfe\r\n\r\n");
+        assert_eq!((status, body.as_str()), (400, "request head is not valid UTF-8\n"));
+        server.shutdown().unwrap();
+    }
+
+    #[test]
+    fn head_without_newline_is_cut_off_at_the_limit() {
+        let server = start(4, 5);
+        // One "line" more than twice the limit, never terminated
+        let huge = vec![b'a'; 2 * MAX_HEAD_BYTES + 100];
+        assert_eq!(send_bytes(server.addr, &huge).0, 431);
+        // The same with a valid request line and one huge header
+        let mut request = b"GET / HTTP/1.1\r\nX-Big: ".to_vec();
+        request.extend(vec![b'b'; MAX_HEAD_BYTES]);
+        assert_eq!(send_bytes(server.addr, &request).0, 431);
+        server.shutdown().unwrap();
+    }
+
+    #[test]
+    fn connections_over_the_limit_get_503() {
+        let server = start(1, 2);
+        let idle = TcpStream::connect(server.addr).unwrap();
+        thread::sleep(Duration::from_millis(100)); // let the server accept it
+        for _ in 0..5 {
+            assert_eq!(get(server.addr, "/").unwrap().0, 503);
+        }
+        drop(idle);
+        thread::sleep(Duration::from_millis(100)); // the slot frees after the close
+        assert_eq!(get(server.addr, "/").unwrap().0, 200);
+        server.shutdown().unwrap();
+    }
+
+    #[test]
+    fn idle_connections_time_out_with_408() {
+        let server = start(4, 1);
+        let idle = TcpStream::connect(server.addr).unwrap();
+        let started = std::time::Instant::now();
+        assert_eq!(read_reply(idle).unwrap().0, 408);
+        assert!(started.elapsed() >= Duration::from_millis(900));
+        server.shutdown().unwrap();
+    }
+
+    #[test]
+    fn shutdown_finishes_requests_in_flight() {
+        let server = start(4, 5);
+        let addr = server.addr;
+        let slow = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(b"POST /echo HTTP/1.1\r\nContent-Length: 4\r\n\r\n").unwrap();
+            thread::sleep(Duration::from_millis(200));
+            stream.write_all(b"late").unwrap();
+            read_reply(stream).unwrap()
+        });
+        thread::sleep(Duration::from_millis(50));
+        server.shutdown().unwrap();
+        assert_eq!(slow.join().unwrap(), (200, String::from("late")));
+        assert!(TcpStream::connect(addr).is_err());
+    }
+
+    #[test]
+    fn run_returns_after_a_signal() {
+        let (signal, on_signal) = std::sync::mpsc::channel();
+        let shutdown = ShutdownHandle::default();
+        shutdown.trigger_on(on_signal);
+        let server = ServerBuilder::new().host("127.0.0.1").port(0).build().unwrap();
+        signal.send(()).unwrap();
+        server.run(router, shutdown).unwrap();
+    }
+
+    #[test]
+    fn zero_limits_are_rejected() {
+        let no_timeout = ServerBuilder::new().timeout(0).build();
+        assert_eq!(no_timeout.err(), Some(server::ServerBuilderError::Invalid(String::from("timeout_seconds must be at least 1"))));
+        assert!(ServerBuilder::new().max_connections(0).build().is_err());
+    }
+}
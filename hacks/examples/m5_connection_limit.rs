@@ -0,0 +1,284 @@
+// cargo run --example m5_connection_limit
+//
+// Demonstrates a COUNTING LIMITER (a semaphore) that actually enforces
+// Server::max_connections.
+//
+// A limiter hands out PERMITS. Holding a permit means "I may use one
+// connection slot". Permits are RAII guards: when a permit is dropped -
+// at the end of a scope, on early return, even on panic - its slot is
+// given back automatically. Forgetting to release is impossible.
+//
+// Three ways to get a permit:
+//   acquire()              - wait as long as it takes
+//   try_acquire()          - succeed now or fail now
+//   acquire_timeout(d)     - wait at most `d`
+//
+// The limiter itself lives in common/limiter.rs so that m5_builder_serve
+// enforces max_connections with exactly the same code.
+//
+// cargo test --example m5_connection_limit runs the stress tests.
+
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[path = "common/limiter.rs"]
+mod limiter;
+use limiter::{ConnectionLimiter, Metrics};
+
+// The Server and ServerBuilder from m5_builder; its validate() refuses
+// a server that allows no connections at all
+#[path = "common/server.rs"]
+mod server;
+use server::{Server, ServerBuilder};
+
+impl Server {
+    // Each server gets a limiter sized from its own config
+    fn connection_limiter(&self) -> ConnectionLimiter {
+        ConnectionLimiter::new(self.max_connections as usize)
+            .expect("build() rejects max_connections = 0")
+    }
+}
+
+// Many threads fight over `capacity` slots, mixing all three ways to get
+// a permit. Returns the highest concurrency seen from outside the
+// limiter, and the limiter's own metrics.
+fn stress(capacity: usize, threads: usize, rounds: usize) -> (usize, Metrics) {
+    let limiter = ConnectionLimiter::new(capacity).expect("capacity must be at least 1");
+    let concurrent = Arc::new(AtomicUsize::new(0)); // measured independently
+    let max_seen = Arc::new(AtomicUsize::new(0));
+
+    let workers: Vec<_> = (0..threads)
+        .map(|id| {
+            let limiter = limiter.clone();
+            let concurrent = Arc::clone(&concurrent);
+            let max_seen = Arc::clone(&max_seen);
+            thread::spawn(move || {
+                for round in 0..rounds {
+                    let permit = match (id + round) % 3 {
+                        0 => Some(limiter.acquire()),
+                        1 => limiter.try_acquire(),
+                        _ => limiter.acquire_timeout(Duration::from_micros(200)),
+                    };
+                    let Some(_permit) = permit else { continue };
+
+                    let now = concurrent.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_seen.fetch_max(now, Ordering::SeqCst);
+                    thread::yield_now(); // hold the slot briefly
+                    concurrent.fetch_sub(1, Ordering::SeqCst);
+                }
+            })
+        })
+        .collect();
+
+    for worker in workers {
+        worker.join().expect("a worker panicked");
+    }
+    (max_seen.load(Ordering::SeqCst), limiter.metrics())
+}
+
+fn main() {
+    println!("=== Connection Limiter ===\n");
+
+    // Zero slots is rejected up front, both by the builder and the limiter
+    assert!(ServerBuilder::new().max_connections(0).build().is_err());
+    assert!(ConnectionLimiter::new(0).is_err());
+
+    let server = ServerBuilder::new().max_connections(3).build().unwrap();
+    let limiter = server.connection_limiter();
+    println!("{}:{} allows {} connections (timeout: {}s)",
+        server.host, server.port, server.max_connections, server.timeout_seconds);
+
+    // try_acquire: immediate answer
+    let a = limiter.try_acquire().expect("slot 1");
+    let b = limiter.try_acquire().expect("slot 2");
+    let c = limiter.try_acquire().expect("slot 3");
+    println!("Took 3 permits:       {:?}", limiter.metrics());
+    println!("4th try_acquire:      {}", if limiter.try_acquire().is_some() { "granted" } else { "rejected" });
+
+    // acquire_timeout: gives up after the deadline
+    let start = Instant::now();
+    let waited = limiter.acquire_timeout(Duration::from_millis(50));
+    println!("acquire_timeout(50ms): {} after {:?}", if waited.is_some() { "granted" } else { "timed out" }, start.elapsed());
+
+    // RAII: dropping a permit frees its slot
+    drop(a);
+    println!("Dropped one permit:   {:?}", limiter.metrics());
+
+    // A permit released by another thread wakes a blocked acquire()
+    let releaser = {
+        let held = b;
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(30));
+            drop(held);
+        })
+    };
+    let d = limiter.try_acquire().expect("slot freed above");
+    let start = Instant::now();
+    let e = limiter.acquire(); // blocks until the other thread drops `b`
+    println!("acquire() waited {:?} for a release", start.elapsed());
+    releaser.join().unwrap();
+    drop((c, d, e));
+    println!("All permits dropped:  {:?}", limiter.metrics());
+
+    // Permits are released even when a thread panics while holding one.
+    // A silent panic hook keeps the expected panic off stderr.
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let panicking = limiter.clone();
+    let crashed = thread::spawn(move || {
+        let _permit = panicking.acquire();
+        panic!("connection handler crashed");
+    })
+    .join();
+    panic::set_hook(default_hook);
+    assert!(crashed.is_err());
+    println!("After a panic:        in_use = {}", limiter.metrics().in_use);
+    assert_eq!(limiter.metrics().in_use, 0);
+
+    // STRESS: many threads fight over a few slots; the limit must hold.
+    println!("\n=== Stress test ===");
+    const CAPACITY: usize = 4;
+    const THREADS: usize = 32;
+    const ROUNDS: usize = 200;
+
+    let (max_seen, metrics) = stress(CAPACITY, THREADS, ROUNDS);
+    println!("{} threads x {} rounds, capacity {}", THREADS, ROUNDS, CAPACITY);
+    println!("Max concurrent observed: {}", max_seen);
+    println!("Final metrics: {:?}", metrics);
+    assert!(max_seen <= CAPACITY);
+    assert!(metrics.peak <= CAPACITY);
+    assert_eq!(metrics.in_use, 0);
+    println!("Limit never exceeded.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn zero_capacity_is_rejected() {
+        assert!(ConnectionLimiter::new(0).is_err());
+        assert!(Server::builder().max_connections(0).build().is_err());
+        assert_eq!(Server::builder().max_connections(1).build().unwrap().connection_limiter().metrics().capacity, 1);
+    }
+
+    #[test]
+    fn try_acquire_counts_rejections() {
+        let limiter = ConnectionLimiter::new(2).unwrap();
+        let permits = [limiter.try_acquire(), limiter.try_acquire()];
+        assert!(permits.iter().all(Option::is_some));
+        assert!(limiter.try_acquire().is_none());
+        assert!(limiter.try_acquire().is_none());
+        let metrics = limiter.metrics();
+        assert_eq!((metrics.in_use, metrics.peak, metrics.rejected), (2, 2, 2));
+
+        drop(permits);
+        assert_eq!(limiter.metrics().in_use, 0);
+        assert!(limiter.try_acquire().is_some());
+    }
+
+    #[test]
+    fn acquire_timeout_gives_up_after_the_deadline() {
+        let limiter = ConnectionLimiter::new(1).unwrap();
+        let _held = limiter.acquire();
+        let start = Instant::now();
+        assert!(limiter.acquire_timeout(Duration::from_millis(20)).is_none());
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert_eq!(limiter.metrics().rejected, 1);
+    }
+
+    #[test]
+    fn an_endless_timeout_waits_without_a_deadline() {
+        let limiter = ConnectionLimiter::new(1).unwrap();
+        assert!(limiter.acquire_timeout(Duration::MAX).is_some());
+        let held = limiter.acquire();
+        let (tx, rx) = mpsc::channel();
+        let waiter = {
+            let limiter = limiter.clone();
+            thread::spawn(move || {
+                tx.send(limiter.acquire_timeout(Duration::MAX).is_some()).unwrap();
+            })
+        };
+        assert!(rx.recv_timeout(Duration::from_millis(20)).is_err());
+        drop(held);
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Ok(true));
+        waiter.join().unwrap();
+        assert_eq!(limiter.metrics().rejected, 0);
+    }
+
+    #[test]
+    fn a_release_wakes_a_blocked_acquire() {
+        let limiter = ConnectionLimiter::new(1).unwrap();
+        let held = limiter.acquire();
+        let (tx, rx) = mpsc::channel();
+        let waiter = {
+            let limiter = limiter.clone();
+            thread::spawn(move || {
+                let _permit = limiter.acquire();
+                tx.send(()).unwrap();
+            })
+        };
+        // Still blocked while the slot is held
+        assert!(rx.recv_timeout(Duration::from_millis(20)).is_err());
+        drop(held);
+        rx.recv_timeout(Duration::from_secs(5)).expect("acquire() was not woken");
+        waiter.join().unwrap();
+    }
+
+    #[test]
+    fn wait_idle_returns_once_every_permit_is_dropped() {
+        let limiter = ConnectionLimiter::new(3).unwrap();
+        limiter.wait_idle(); // nothing held: returns at once
+
+        let holders: Vec<_> = (0..3)
+            .map(|i| {
+                let permit = limiter.acquire();
+                thread::spawn(move || {
+                    thread::sleep(Duration::from_millis(10 * i));
+                    drop(permit);
+                })
+            })
+            .collect();
+        limiter.wait_idle();
+        assert_eq!(limiter.metrics().in_use, 0);
+        for holder in holders {
+            holder.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn a_panicking_holder_releases_its_permit() {
+        let limiter = ConnectionLimiter::new(1).unwrap();
+        let panicking = limiter.clone();
+        let result = thread::spawn(move || {
+            let _permit = panicking.acquire();
+            panic!("expected panic: handler crashed while holding a permit");
+        })
+        .join();
+        assert!(result.is_err());
+        assert_eq!(limiter.metrics().in_use, 0);
+        assert!(limiter.try_acquire().is_some());
+    }
+
+    #[test]
+    fn stress_never_exceeds_capacity() {
+        for capacity in [1, 2, 4, 7] {
+            let (max_seen, metrics) = stress(capacity, 24, 300);
+            assert!(max_seen <= capacity, "saw {} with capacity {}", max_seen, capacity);
+            assert!(metrics.peak <= capacity);
+            assert_eq!(metrics.in_use, 0);
+        }
+    }
+
+    #[test]
+    fn stress_with_more_slots_than_threads_never_rejects() {
+        let (max_seen, metrics) = stress(16, 8, 500);
+        assert!(max_seen <= 8);
+        assert_eq!(metrics.rejected, 0);
+        assert_eq!(metrics.in_use, 0);
+    }
+}
@@ -3,25 +3,33 @@
 // Demonstrates #[derive] - automatic implementation of common traits.
 // Derive macros generate boilerplate code for standard behaviors.
 
+// Both structs live in common/shapes.rs, shared with m5_serde_csv, and
+// are defined there like this:
+//
+//     #[derive(Debug, Clone, PartialEq)]
+//     struct Point {
+//         x: i32,
+//         y: i32,
+//     }
+//
+//     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+//     struct SimplePoint {
+//         x: i32,
+//         y: i32,
+//     }
+//
 // #[derive(...)] tells Rust to auto-implement these traits:
 // - Debug: enables {:?} formatting for printing
 // - Clone: enables .clone() to create a deep copy
 // - PartialEq: enables == and != comparison
-#[derive(Debug, Clone, PartialEq)]
-struct Point {
-    x: i32,
-    y: i32,
-}
-
-// More derivable traits example
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct SimplePoint {
-    x: i32,
-    y: i32,
-}
-// Copy: enables implicit copying (only for types that are entirely stack-based)
-// Eq: full equality (PartialEq that's also reflexive)
-// Hash: can be used as HashMap keys
+// - Copy: enables implicit copying (only for types that are entirely stack-based)
+// - Eq: full equality (PartialEq that's also reflexive)
+// - Hash: can be used as HashMap keys
+// A cfg_attr there adds Serialize/Deserialize only with `--features serde`
+// (see m5_serde_csv).
+#[path = "common/shapes.rs"]
+mod shapes;
+use shapes::{Point, SimplePoint};
 
 fn main() {
     let p1 = Point { x: 1, y: 2 };
@@ -0,0 +1,161 @@
+// cargo run --example m5_derive_describe
+//
+// Demonstrates a CUSTOM derive macro: #[derive(Describe)] from the
+// workspace crate `hacks_derive`.
+//
+// The standard derives (see m5_derive) generate trait impls. A custom
+// derive can generate anything - here, code that describes the struct
+// itself at runtime:
+//
+//   Point::describe()     -> name, kind, and each field's name/type/doc
+//   point.field_values()  -> [("x", "1"), ("y", "2")]
+
+use hacks_derive::Describe;
+
+// The schema types the macro fills in. They live here (not in the macro
+// crate) because proc-macro crates can only export macros. The generated
+// code names them `crate::StructSchema` etc., so they sit at the crate
+// root; #[describe(schema = some::module)] points it elsewhere.
+#[derive(Debug, PartialEq)]
+enum StructKind {
+    Named, // struct Point { x: i32 }
+    Tuple, // struct Color(u8, u8, u8);
+    Unit,  // struct Marker;
+}
+
+#[derive(Debug)]
+struct FieldSchema {
+    name: &'static str,
+    ty: &'static str,
+    doc: &'static str,
+}
+
+#[derive(Debug)]
+struct StructSchema {
+    name: &'static str,
+    kind: StructKind,
+    doc: &'static str,
+    fields: Vec<FieldSchema>,
+}
+
+impl StructSchema {
+    // Print the schema as an indented table
+    fn print(&self) {
+        println!("{} ({:?} struct){}", self.name, self.kind,
+            if self.doc.is_empty() { String::new() } else { format!(" - {}", self.doc) });
+        for field in &self.fields {
+            let mut line = format!("  {:<8} : {:<16}", field.name, field.ty);
+            if !field.doc.is_empty() {
+                line.push_str(&format!(" // {}", field.doc));
+            }
+            println!("{}", line.trim_end());
+        }
+    }
+}
+
+// Custom derives combine with the standard ones
+/// A point on the 2D integer grid.
+#[derive(Debug, Clone, PartialEq, Describe)]
+struct Point {
+    /// Horizontal position
+    x: i32,
+    /// Vertical position
+    y: i32,
+}
+
+/// Copyable grid position, usable as a HashMap key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Describe)]
+struct SimplePoint {
+    x: i32,
+    y: i32,
+}
+
+// Tuple struct: fields are named "0", "1", "2"
+/// An RGB color.
+#[derive(Describe)]
+struct Color(u8, u8, u8);
+
+// Unit struct: no fields at all
+#[derive(Describe)]
+struct Marker;
+
+// Field types are reported as written, including generics and references
+#[derive(Describe)]
+struct Labelled<'a, T> {
+    /// What the value means
+    label: &'a str,
+    value: T,
+    tags: Vec<String>,
+}
+
+// Deriving in another module needs no imports of the schema types
+mod shapes {
+    use hacks_derive::Describe;
+
+    /// A circle around the origin.
+    #[derive(Describe)]
+    pub struct Circle {
+        pub radius: f64,
+    }
+}
+
+// describe() only looks at the types; field_values() needs T: Debug
+struct Opaque;
+
+fn main() {
+    println!("=== #[derive(Describe)] ===\n");
+
+    // Named struct
+    Point::describe().print();
+    let p = Point { x: 1, y: 2 };
+    println!("  values: {:?}\n", p.field_values());
+
+    let schema = Point::describe();
+    assert_eq!(schema.kind, StructKind::Named);
+    assert_eq!(schema.doc, "A point on the 2D integer grid.");
+    assert_eq!(schema.fields.len(), 2);
+    assert_eq!((schema.fields[0].name, schema.fields[0].ty), ("x", "i32"));
+    assert_eq!(schema.fields[1].doc, "Vertical position");
+    assert_eq!(p.field_values(), vec![("x", String::from("1")), ("y", String::from("2"))]);
+
+    SimplePoint::describe().print();
+    let sp = SimplePoint { x: -5, y: 7 };
+    println!("  values: {:?}\n", sp.field_values());
+    assert_eq!(SimplePoint::describe().fields[0].doc, ""); // no doc comment
+
+    // Tuple struct
+    Color::describe().print();
+    let red = Color(255, 0, 0);
+    println!("  values: {:?}\n", red.field_values());
+    assert_eq!(Color::describe().kind, StructKind::Tuple);
+    let names: Vec<_> = Color::describe().fields.iter().map(|f| f.name).collect();
+    assert_eq!(names, ["0", "1", "2"]);
+    assert_eq!(red.field_values()[0], ("0", String::from("255")));
+
+    // Unit struct
+    Marker::describe().print();
+    println!("  values: {:?}\n", Marker.field_values());
+    assert_eq!(Marker::describe().kind, StructKind::Unit);
+    assert!(Marker::describe().fields.is_empty());
+    assert!(Marker.field_values().is_empty());
+
+    // Generic struct
+    Labelled::<f64>::describe().print();
+    let item = Labelled { label: "ratio", value: 1.5, tags: vec![String::from("math")] };
+    println!("  values: {:?}", item.field_values());
+    let types: Vec<_> = Labelled::<f64>::describe().fields.iter().map(|f| f.ty).collect();
+    assert_eq!(types, ["&'a str", "T", "Vec<String>"]);
+
+    // describe() works even when a field type is not Debug
+    assert_eq!(Labelled::<Opaque>::describe().fields[1].ty, "T");
+
+    shapes::Circle::describe().print();
+    println!("  values: {:?}", shapes::Circle { radius: 2.0 }.field_values());
+    assert_eq!(shapes::Circle::describe().doc, "A circle around the origin.");
+
+    // A generic use: print any describable value as a table
+    println!("\nGeneric table printing:");
+    for (name, value) in p.field_values().into_iter().chain(sp.field_values()) {
+        println!("  {} = {}", name, value);
+    }
+}
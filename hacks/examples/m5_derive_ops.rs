@@ -0,0 +1,262 @@
+// cargo run --example m5_derive_ops
+//
+// Demonstrates OPERATOR OVERLOADING and parsing for the points from m5_derive.
+// Some traits can be derived (Default, PartialOrd, Ord); others must be
+// implemented by hand because only we know what they should mean:
+//
+//   a + b, a - b, -a, a * 3, a += b      std::ops::{Add, Sub, Neg, Mul, AddAssign}
+//   a < b, points.sort()                 Ord (derived: compares x, then y)
+//   format!("{}", a)   -> "(1, 2)"        Display
+//   "(1, 2)".parse()                     FromStr
+//
+// The plain operators behave like i32 (they panic on overflow in debug
+// builds); checked_* versions return None instead.
+
+use std::fmt;
+use std::num::ParseIntError;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+use std::str::FromStr;
+
+// Derived Ord compares fields in declaration order: lexicographic (x, then y)
+// Derived Default gives (0, 0)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+struct SimplePoint {
+    x: i32,
+    y: i32,
+}
+
+// Why a string could not be parsed as a point
+#[derive(Debug, PartialEq)]
+enum ParsePointError {
+    Empty,
+    // Not "(x, y)", "x,y" or "x=.. y=.."
+    BadFormat(String),
+    // A coordinate that is not a valid i32 (includes out-of-range numbers)
+    BadCoordinate { text: String, source: ParseIntError },
+}
+
+impl fmt::Display for ParsePointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParsePointError::Empty => write!(f, "empty input"),
+            ParsePointError::BadFormat(input) => {
+                write!(f, "expected \"(x, y)\", \"x,y\" or \"x=.. y=..\", got {:?}", input)
+            }
+            ParsePointError::BadCoordinate { text, source } => {
+                write!(f, "invalid coordinate {:?}: {}", text, source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParsePointError {}
+
+// Shared parser for both point types; returns (x, y)
+fn parse_coordinates(input: &str) -> Result<(i32, i32), ParsePointError> {
+    let s = input.trim();
+    if s.is_empty() {
+        return Err(ParsePointError::Empty);
+    }
+    let bad_format = || ParsePointError::BadFormat(input.to_string());
+    let number = |text: &str| {
+        text.trim().parse::<i32>().map_err(|source| ParsePointError::BadCoordinate {
+            text: text.trim().to_string(),
+            source,
+        })
+    };
+
+    // "x=1 y=2" (either order, commas optional)
+    if s.contains('=') {
+        let (mut x, mut y) = (None, None);
+        for part in s.split(|c: char| c.is_whitespace() || c == ',').filter(|p| !p.is_empty()) {
+            let (name, value) = part.split_once('=').ok_or_else(bad_format)?;
+            let slot = match name.trim() {
+                "x" => &mut x,
+                "y" => &mut y,
+                _ => return Err(bad_format()),
+            };
+            if slot.replace(number(value)?).is_some() {
+                return Err(bad_format()); // same name twice
+            }
+        }
+        return match (x, y) {
+            (Some(x), Some(y)) => Ok((x, y)),
+            _ => Err(bad_format()),
+        };
+    }
+
+    // "(1, 2)" or "1,2" - parentheses are optional but must match
+    let inner = match (s.strip_prefix('('), s.strip_suffix(')')) {
+        (Some(_), Some(_)) => &s[1..s.len() - 1],
+        (None, None) => s,
+        _ => return Err(bad_format()),
+    };
+    let (x, y) = inner.split_once(',').ok_or_else(bad_format)?;
+    if y.contains(',') {
+        return Err(bad_format());
+    }
+    Ok((number(x)?, number(y)?))
+}
+
+// The same set of impls for both types, written once with macro_rules!
+macro_rules! point_ops {
+    ($Point:ident) => {
+        impl $Point {
+            fn new(x: i32, y: i32) -> Self {
+                $Point { x, y }
+            }
+
+            // CHECKED arithmetic: None instead of overflowing
+            fn checked_add(&self, other: &Self) -> Option<Self> {
+                Some($Point::new(self.x.checked_add(other.x)?, self.y.checked_add(other.y)?))
+            }
+
+            fn checked_sub(&self, other: &Self) -> Option<Self> {
+                Some($Point::new(self.x.checked_sub(other.x)?, self.y.checked_sub(other.y)?))
+            }
+
+            fn checked_neg(&self) -> Option<Self> {
+                Some($Point::new(self.x.checked_neg()?, self.y.checked_neg()?))
+            }
+
+            fn checked_mul(&self, scalar: i32) -> Option<Self> {
+                Some($Point::new(self.x.checked_mul(scalar)?, self.y.checked_mul(scalar)?))
+            }
+        }
+
+        // a + b
+        impl Add for $Point {
+            type Output = $Point;
+            fn add(self, other: $Point) -> $Point {
+                $Point::new(self.x + other.x, self.y + other.y)
+            }
+        }
+
+        // a - b
+        impl Sub for $Point {
+            type Output = $Point;
+            fn sub(self, other: $Point) -> $Point {
+                $Point::new(self.x - other.x, self.y - other.y)
+            }
+        }
+
+        // -a
+        impl Neg for $Point {
+            type Output = $Point;
+            fn neg(self) -> $Point {
+                $Point::new(-self.x, -self.y)
+            }
+        }
+
+        // a * 3 (scalar multiplication)
+        impl Mul<i32> for $Point {
+            type Output = $Point;
+            fn mul(self, scalar: i32) -> $Point {
+                $Point::new(self.x * scalar, self.y * scalar)
+            }
+        }
+
+        // a += b
+        impl AddAssign for $Point {
+            fn add_assign(&mut self, other: $Point) {
+                self.x += other.x;
+                self.y += other.y;
+            }
+        }
+
+        // "(x, y)"
+        impl fmt::Display for $Point {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "({}, {})", self.x, self.y)
+            }
+        }
+
+        impl FromStr for $Point {
+            type Err = ParsePointError;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let (x, y) = parse_coordinates(s)?;
+                Ok($Point::new(x, y))
+            }
+        }
+    };
+}
+
+point_ops!(Point);
+point_ops!(SimplePoint);
+
+fn main() {
+    println!("=== Operators ===\n");
+
+    let a = Point::new(1, 2);
+    let b = Point::new(10, 20);
+
+    // Point is not Copy, so operators consume their operands: clone to reuse
+    println!("{} + {} = {}", a, b, a.clone() + b.clone());
+    println!("{} - {} = {}", b, a, b.clone() - a.clone());
+    println!("-{} = {}", a, -a.clone());
+    println!("{} * 3 = {}", a, a.clone() * 3);
+
+    let mut total = Point::default();
+    for p in [a.clone(), b.clone(), Point::new(100, 200)] {
+        total += p;
+    }
+    println!("Sum with += starting from Default: {}", total);
+    assert_eq!(total, Point::new(111, 222));
+
+    // SimplePoint is Copy, so no clones are needed
+    let s = SimplePoint::new(3, 4);
+    println!("SimplePoint: {} + {} = {}", s, s, s + s);
+    assert_eq!(-(s - s * 2), s);
+
+    println!("\n=== Ordering ===");
+    let mut points = [Point::new(2, 1), Point::new(1, 5), Point::new(2, 0), Point::new(1, -3)];
+    points.sort(); // uses Ord: by x, then by y
+    let sorted: Vec<String> = points.iter().map(|p| p.to_string()).collect();
+    println!("Sorted: {}", sorted.join(" "));
+    println!("Max:    {}", points.iter().max().unwrap());
+    assert!(Point::new(1, 100) < Point::new(2, 0));
+
+    println!("\n=== Parsing ===");
+    for input in ["(1, 2)", "1,2", "x=1 y=2", "y=-7, x=3", " ( -4 ,5 ) ",
+                  "", "(1, 2", "1 2", "x=1", "(a, 2)", "(99999999999, 0)"] {
+        match input.parse::<Point>() {
+            Ok(p) => println!("  {:<20} -> {}", format!("{:?}", input), p),
+            Err(e) => println!("  {:<20} -> Error: {}", format!("{:?}", input), e),
+        }
+    }
+    assert_eq!("(1, 2)".parse(), Ok(Point::new(1, 2)));
+    assert_eq!("1,2".parse(), Ok(SimplePoint::new(1, 2)));
+    assert_eq!("x=1 y=2".parse(), Ok(Point::new(1, 2)));
+    assert_eq!("".parse::<Point>(), Err(ParsePointError::Empty));
+    assert!(matches!("x=1 x=2".parse::<Point>(), Err(ParsePointError::BadFormat(_))));
+    assert!(matches!("(1, z)".parse::<Point>(), Err(ParsePointError::BadCoordinate { .. })));
+
+    // Display and FromStr round-trip
+    let p = Point::new(-12, 34);
+    assert_eq!(p.to_string().parse(), Ok(p.clone()));
+
+    println!("\n=== Checked arithmetic ===");
+    let big = Point::new(i32::MAX, 0);
+    let one = Point::new(1, 1);
+    println!("{} checked_add {} = {:?}", big, one, big.checked_add(&one));
+    println!("{} checked_sub {} = {:?}", one, big, one.checked_sub(&big));
+    println!("{} checked_mul 2 = {:?}", big, big.checked_mul(2));
+    println!("({}, 0) checked_neg = {:?}", i32::MIN, Point::new(i32::MIN, 0).checked_neg());
+    assert_eq!(big.checked_add(&one), None);
+    assert_eq!(Point::new(i32::MIN, 0).checked_neg(), None);
+    assert_eq!(one.checked_mul(-5), Some(Point::new(-5, -5)));
+
+    // SimplePoint gets exactly the same checked API
+    let edge = SimplePoint::new(0, i32::MIN);
+    assert_eq!(edge.checked_neg(), None);
+    assert_eq!(edge.checked_add(&SimplePoint::new(1, -1)), None);
+    assert_eq!(edge.checked_mul(1), Some(edge));
+    assert_eq!(SimplePoint::new(2, 2).checked_sub(&SimplePoint::new(1, 1)), Some(SimplePoint::new(1, 1)));
+    // big.clone() + one would panic in a debug build: "attempt to add with overflow"
+}
@@ -0,0 +1,472 @@
+// cargo run --example m5_derive_spatial
+//
+// Demonstrates why deriving Hash + Eq matters: SimplePoint (from m5_derive)
+// can be a HashMap key, which is all a UNIFORM GRID spatial index needs.
+//
+// The plane is cut into square cells of `cell_size`. Each point lives in
+// the bucket of its cell:
+//
+//     cell (x / size, y / size)  ->  Vec<SimplePoint>
+//
+// A query only looks at the cells that can possibly contain an answer
+// instead of scanning every point:
+//   - points inside an axis-aligned box
+//   - points within a radius
+//   - the k nearest neighbours (searching rings of cells outward)
+//
+// main() checks every query against a brute-force scan (an "oracle");
+// cargo test --example m5_derive_spatial runs the same checks as tests.
+
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct SimplePoint {
+    x: i32,
+    y: i32,
+}
+
+impl SimplePoint {
+    // Squared distance, in i128: between i32 extremes it exceeds even u64
+    fn distance_squared(&self, other: &SimplePoint) -> i128 {
+        let dx = self.x as i128 - other.x as i128;
+        let dy = self.y as i128 - other.y as i128;
+        dx * dx + dy * dy
+    }
+}
+
+// A cell coordinate; also Hash + Eq, so it can key the HashMap too
+type Cell = (i32, i32);
+
+// A cell size of 0 or less cannot cut the plane into cells
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BadCellSize(i32);
+
+impl fmt::Display for BadCellSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cell size must be positive, got {}", self.0)
+    }
+}
+
+impl std::error::Error for BadCellSize {}
+
+struct GridIndex {
+    cell_size: i32,
+    cells: HashMap<Cell, Vec<SimplePoint>>,
+    len: usize,
+}
+
+impl GridIndex {
+    fn new(cell_size: i32) -> Result<Self, BadCellSize> {
+        if cell_size <= 0 {
+            return Err(BadCellSize(cell_size));
+        }
+        Ok(GridIndex { cell_size, cells: HashMap::new(), len: 0 })
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    // div_euclid rounds toward negative infinity, so -1 is in cell -1, not 0
+    fn cell_of(&self, p: &SimplePoint) -> Cell {
+        (p.x.div_euclid(self.cell_size), p.y.div_euclid(self.cell_size))
+    }
+
+    // Returns false if the point was already present (the index is a set)
+    fn insert(&mut self, p: SimplePoint) -> bool {
+        let bucket = self.cells.entry(self.cell_of(&p)).or_default();
+        if bucket.contains(&p) {
+            return false;
+        }
+        bucket.push(p);
+        self.len += 1;
+        true
+    }
+
+    // Returns false if the point was not present
+    fn remove(&mut self, p: &SimplePoint) -> bool {
+        let cell = self.cell_of(p);
+        let Some(bucket) = self.cells.get_mut(&cell) else {
+            return false;
+        };
+        let Some(pos) = bucket.iter().position(|q| q == p) else {
+            return false;
+        };
+        bucket.swap_remove(pos);
+        if bucket.is_empty() {
+            self.cells.remove(&cell); // keep only occupied cells
+        }
+        self.len -= 1;
+        true
+    }
+
+    fn contains(&self, p: &SimplePoint) -> bool {
+        self.cells.get(&self.cell_of(p)).is_some_and(|bucket| bucket.contains(p))
+    }
+
+    // Every point with min.x <= x <= max.x and min.y <= y <= max.y
+    fn in_box(&self, min: SimplePoint, max: SimplePoint) -> Vec<SimplePoint> {
+        let (lo, hi) = (self.cell_of(&min), self.cell_of(&max));
+        let mut found = Vec::new();
+
+        // If the box covers more cells than exist, walking the cells is cheaper.
+        // i128: a box over the whole i32 plane with cell_size 1 has 2^64 cells.
+        let box_cells = (hi.0 as i128 - lo.0 as i128 + 1) * (hi.1 as i128 - lo.1 as i128 + 1);
+        let inside = |p: &&SimplePoint| p.x >= min.x && p.x <= max.x && p.y >= min.y && p.y <= max.y;
+        if box_cells > self.cells.len() as i128 {
+            for (cell, bucket) in &self.cells {
+                if cell.0 >= lo.0 && cell.0 <= hi.0 && cell.1 >= lo.1 && cell.1 <= hi.1 {
+                    found.extend(bucket.iter().filter(inside));
+                }
+            }
+        } else {
+            for cx in lo.0..=hi.0 {
+                for cy in lo.1..=hi.1 {
+                    if let Some(bucket) = self.cells.get(&(cx, cy)) {
+                        found.extend(bucket.iter().filter(inside));
+                    }
+                }
+            }
+        }
+        found.sort();
+        found
+    }
+
+    // Every point at distance <= radius from center. The radius is unsigned,
+    // so a negative radius cannot even be passed in.
+    fn within_radius(&self, center: SimplePoint, radius: u32) -> Vec<SimplePoint> {
+        let r2 = radius as i128 * radius as i128;
+        // Candidates come from the bounding box of the circle
+        let min = SimplePoint {
+            x: center.x.saturating_sub_unsigned(radius),
+            y: center.y.saturating_sub_unsigned(radius),
+        };
+        let max = SimplePoint {
+            x: center.x.saturating_add_unsigned(radius),
+            y: center.y.saturating_add_unsigned(radius),
+        };
+        self.in_box(min, max)
+            .into_iter()
+            .filter(|p| p.distance_squared(&center) <= r2)
+            .collect()
+    }
+
+    // The k closest points, nearest first (ties broken by point order)
+    fn nearest(&self, center: SimplePoint, k: usize) -> Vec<SimplePoint> {
+        if k == 0 || self.len == 0 {
+            return Vec::new();
+        }
+        let origin = self.cell_of(&center);
+
+        // Rings beyond the farthest occupied cell cannot add anything
+        let max_ring = self
+            .cells
+            .keys()
+            .map(|c| (c.0 as i64 - origin.0 as i64).abs().max((c.1 as i64 - origin.1 as i64).abs()))
+            .max()
+            .unwrap_or(0);
+
+        let mut best: Vec<(i128, SimplePoint)> = Vec::new();
+        for ring in 0..=max_ring {
+            // Once a ring has more cells than are occupied, scanning the
+            // occupied cells directly is cheaper (sparse or far-apart data)
+            if 8 * ring > self.cells.len() as i64 {
+                best = self
+                    .cells
+                    .values()
+                    .flatten()
+                    .map(|p| (p.distance_squared(&center), *p))
+                    .collect();
+                best.sort();
+                best.truncate(k);
+                break;
+            }
+
+            for cell in ring_cells(origin, ring) {
+                if let Some(bucket) = self.cells.get(&cell) {
+                    best.extend(bucket.iter().map(|p| (p.distance_squared(&center), *p)));
+                }
+            }
+            best.sort();
+            best.truncate(k);
+
+            // Any point in a ring further out is at least ring * cell_size away
+            let reach = ring as i128 * self.cell_size as i128;
+            if best.len() == k && best[k - 1].0 < reach * reach {
+                break;
+            }
+        }
+        best.into_iter().map(|(_, p)| p).collect()
+    }
+}
+
+// The cells at Chebyshev distance exactly `ring` from `origin`
+fn ring_cells(origin: Cell, ring: i64) -> Vec<Cell> {
+    let (ox, oy) = (origin.0 as i64, origin.1 as i64);
+    let mut cells = Vec::new();
+    let mut push = |x: i64, y: i64| {
+        if let (Ok(x), Ok(y)) = (i32::try_from(x), i32::try_from(y)) {
+            cells.push((x, y));
+        }
+    };
+    if ring == 0 {
+        push(ox, oy);
+        return cells;
+    }
+    for d in -ring..=ring {
+        push(ox + d, oy - ring); // bottom edge
+        push(ox + d, oy + ring); // top edge
+    }
+    for d in -ring + 1..ring {
+        push(ox - ring, oy + d); // left edge (corners already done)
+        push(ox + ring, oy + d); // right edge
+    }
+    cells
+}
+
+// ---------------------------------------------------------------------
+// Brute-force oracle: obviously correct, just slow
+// ---------------------------------------------------------------------
+
+fn oracle_box(points: &[SimplePoint], min: SimplePoint, max: SimplePoint) -> Vec<SimplePoint> {
+    let mut found: Vec<_> = points
+        .iter()
+        .copied()
+        .filter(|p| p.x >= min.x && p.x <= max.x && p.y >= min.y && p.y <= max.y)
+        .collect();
+    found.sort();
+    found
+}
+
+fn oracle_radius(points: &[SimplePoint], center: SimplePoint, radius: u32) -> Vec<SimplePoint> {
+    let r2 = radius as i128 * radius as i128;
+    let mut found: Vec<_> =
+        points.iter().copied().filter(|p| p.distance_squared(&center) <= r2).collect();
+    found.sort();
+    found
+}
+
+fn oracle_nearest(points: &[SimplePoint], center: SimplePoint, k: usize) -> Vec<SimplePoint> {
+    let mut all: Vec<_> = points.iter().map(|p| (p.distance_squared(&center), *p)).collect();
+    all.sort();
+    all.into_iter().take(k).map(|(_, p)| p).collect()
+}
+
+// Tiny deterministic random number generator (xorshift), no crates needed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn range(&mut self, lo: i32, hi: i32) -> i32 {
+        lo + (self.next() % (hi - lo + 1) as u64) as i32
+    }
+
+    fn point(&mut self, extent: i32) -> SimplePoint {
+        SimplePoint { x: self.range(-extent, extent), y: self.range(-extent, extent) }
+    }
+}
+
+// Random inserts and removes; every 20 steps, each query is compared with
+// the brute-force answer. Returns the index and how many queries ran.
+fn check_against_oracle(cell_size: i32, steps: usize, rng: &mut Rng) -> Result<(GridIndex, usize), BadCellSize> {
+    let mut index = GridIndex::new(cell_size)?;
+    let mut oracle: Vec<SimplePoint> = Vec::new();
+    let mut queries = 0;
+
+    for step in 0..steps {
+        // Mostly inserts, some removes (both of present and absent points)
+        let p = if step % 4 == 3 && !oracle.is_empty() {
+            oracle[rng.next() as usize % oracle.len()]
+        } else {
+            rng.point(100)
+        };
+        if step % 4 == 3 {
+            let expected = oracle.iter().position(|q| *q == p).map(|i| oracle.swap_remove(i));
+            assert_eq!(index.remove(&p), expected.is_some());
+        } else {
+            let fresh = !oracle.contains(&p);
+            if fresh {
+                oracle.push(p);
+            }
+            assert_eq!(index.insert(p), fresh);
+        }
+        assert_eq!(index.len(), oracle.len());
+
+        if step % 20 == 0 {
+            let center = rng.point(150);
+            let (a, b) = (rng.point(120), rng.point(120));
+            let min = SimplePoint { x: a.x.min(b.x), y: a.y.min(b.y) };
+            let max = SimplePoint { x: a.x.max(b.x), y: a.y.max(b.y) };
+            let radius = rng.range(0, 60) as u32;
+            let k = rng.range(0, 12) as usize;
+
+            assert_eq!(index.in_box(min, max), oracle_box(&oracle, min, max));
+            assert_eq!(index.within_radius(center, radius), oracle_radius(&oracle, center, radius));
+            assert_eq!(index.nearest(center, k), oracle_nearest(&oracle, center, k));
+            queries += 3;
+        }
+    }
+    Ok((index, queries))
+}
+
+fn main() -> Result<(), BadCellSize> {
+    println!("=== Spatial Index over SimplePoint ===\n");
+
+    let mut index = GridIndex::new(10)?;
+    for (x, y) in [(0, 0), (3, 4), (-1, -1), (12, 5), (25, 25), (-30, 8), (9, 9)] {
+        index.insert(SimplePoint { x, y });
+    }
+    println!("Indexed {} points in {} cells", index.len(), index.cells.len());
+    println!("Insert duplicate (3, 4): {}", index.insert(SimplePoint { x: 3, y: 4 }));
+
+    let origin = SimplePoint { x: 0, y: 0 };
+    println!("\nBox (-5,-5)..(10,10):  {:?}", index.in_box(SimplePoint { x: -5, y: -5 }, SimplePoint { x: 10, y: 10 }));
+    println!("Within 5 of origin:    {:?}", index.within_radius(origin, 5));
+    println!("3 nearest to (20, 20): {:?}", index.nearest(SimplePoint { x: 20, y: 20 }, 3));
+
+    println!("\nRemove (0, 0): {}", index.remove(&origin));
+    println!("Remove (0, 0) again: {}", index.remove(&origin));
+    println!("Contains (0, 0): {}", index.contains(&origin));
+    println!("Nearest to origin now: {:?}", index.nearest(origin, 1));
+
+    // ORACLE CHECK: random inserts/removes, every query compared to brute force
+    println!("\n=== Checking against a brute-force oracle ===");
+    let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+    let mut queries = 0;
+
+    for cell_size in [1, 7, 50] {
+        let (index, checked) = check_against_oracle(cell_size, 2_000, &mut rng)?;
+        println!("  cell size {:>2}: {} points, {} cells - all queries match", cell_size, index.len(), index.cells.len());
+        queries += checked;
+    }
+
+    // Edge cases: empty index, k larger than the index, extreme coordinates
+    let mut edge = GridIndex::new(1_000)?;
+    assert!(edge.nearest(origin, 3).is_empty());
+    let far = [SimplePoint { x: i32::MAX, y: i32::MIN }, SimplePoint { x: i32::MIN, y: i32::MAX }];
+    for p in far {
+        edge.insert(p);
+    }
+    assert_eq!(edge.nearest(origin, 10), oracle_nearest(&far, origin, 10));
+    assert_eq!(edge.within_radius(far[0], 0), vec![far[0]]);
+    // A box over the whole plane: 2^64 cells would overflow an i64 count
+    let mut fine = GridIndex::new(1)?;
+    for p in far {
+        fine.insert(p);
+    }
+    let (min, max) = (SimplePoint { x: i32::MIN, y: i32::MIN }, SimplePoint { x: i32::MAX, y: i32::MAX });
+    assert_eq!(fine.in_box(min, max), oracle_box(&far, min, max));
+    assert_eq!(fine.within_radius(origin, u32::MAX), oracle_radius(&far, origin, u32::MAX));
+    println!("  edge cases (empty, k > len, i32 extremes) - ok");
+
+    // A cell size of zero or less is refused up front
+    for bad in [0, -5] {
+        println!("  GridIndex::new({}) -> {}", bad, GridIndex::new(bad).err().expect("refused"));
+    }
+
+    println!("\n{} queries agreed with the oracle.", queries);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pt(x: i32, y: i32) -> SimplePoint {
+        SimplePoint { x, y }
+    }
+
+    #[test]
+    fn random_queries_match_the_oracle() {
+        let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+        for cell_size in [1, 3, 16, 100, 1_000] {
+            let (index, queries) = check_against_oracle(cell_size, 1_000, &mut rng).unwrap();
+            assert!(index.len() > 0);
+            assert_eq!(queries, 150);
+        }
+    }
+
+    #[test]
+    fn non_positive_cell_sizes_are_rejected() {
+        assert_eq!(GridIndex::new(0).err(), Some(BadCellSize(0)));
+        assert_eq!(GridIndex::new(i32::MIN).err(), Some(BadCellSize(i32::MIN)));
+        assert!(GridIndex::new(1).is_ok());
+    }
+
+    #[test]
+    fn negative_coordinates_use_floor_cells() {
+        let index = GridIndex::new(10).unwrap();
+        assert_eq!(index.cell_of(&pt(-1, -10)), (-1, -1));
+        assert_eq!(index.cell_of(&pt(-11, 9)), (-2, 0));
+        assert_eq!(index.cell_of(&pt(i32::MIN, i32::MAX)), (i32::MIN / 10 - 1, i32::MAX / 10));
+    }
+
+    #[test]
+    fn whole_plane_box_does_not_overflow() {
+        let points = [pt(i32::MIN, i32::MIN), pt(0, 0), pt(i32::MAX, i32::MAX), pt(-7, 12)];
+        for cell_size in [1, 2, i32::MAX] {
+            let mut index = GridIndex::new(cell_size).unwrap();
+            for p in points {
+                index.insert(p);
+            }
+            let (min, max) = (pt(i32::MIN, i32::MIN), pt(i32::MAX, i32::MAX));
+            assert_eq!(index.in_box(min, max), oracle_box(&points, min, max));
+            // Half the plane
+            let (min, max) = (pt(i32::MIN, 0), pt(i32::MAX, i32::MAX));
+            assert_eq!(index.in_box(min, max), oracle_box(&points, min, max));
+        }
+    }
+
+    #[test]
+    fn huge_radii_do_not_overflow() {
+        let points = [pt(i32::MIN, i32::MIN), pt(i32::MAX, 0), pt(5, 5)];
+        let mut index = GridIndex::new(1).unwrap();
+        for p in points {
+            index.insert(p);
+        }
+        for center in [pt(0, 0), pt(i32::MAX, i32::MAX), pt(i32::MIN, 0)] {
+            for radius in [i32::MAX as u32, u32::MAX, 1 << 20] {
+                assert_eq!(index.within_radius(center, radius), oracle_radius(&points, center, radius));
+            }
+        }
+    }
+
+    #[test]
+    fn inverted_box_is_empty() {
+        let mut index = GridIndex::new(4).unwrap();
+        index.insert(pt(1, 1));
+        assert!(index.in_box(pt(5, 5), pt(0, 0)).is_empty());
+        assert!(oracle_box(&[pt(1, 1)], pt(5, 5), pt(0, 0)).is_empty());
+    }
+
+    #[test]
+    fn nearest_breaks_ties_by_point_order() {
+        let points = [pt(1, 0), pt(0, 1), pt(-1, 0), pt(0, -1), pt(3, 3)];
+        let mut index = GridIndex::new(2).unwrap();
+        for p in points {
+            index.insert(p);
+        }
+        assert_eq!(index.nearest(pt(0, 0), 2), [pt(-1, 0), pt(0, -1)]);
+        assert_eq!(index.nearest(pt(0, 0), 9), oracle_nearest(&points, pt(0, 0), 9));
+        assert!(index.nearest(pt(0, 0), 0).is_empty());
+    }
+
+    #[test]
+    fn insert_and_remove_keep_the_index_a_set() {
+        let mut index = GridIndex::new(5).unwrap();
+        assert!(index.insert(pt(1, 2)));
+        assert!(!index.insert(pt(1, 2)));
+        assert_eq!(index.len(), 1);
+        assert!(index.remove(&pt(1, 2)));
+        assert!(!index.remove(&pt(1, 2)));
+        assert!(!index.contains(&pt(1, 2)));
+        assert!(index.cells.is_empty(), "empty cells are dropped");
+    }
+}
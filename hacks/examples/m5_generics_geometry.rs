@@ -0,0 +1,550 @@
+// cargo run --example m5_generics_geometry
+//
+// Demonstrates a small GENERIC GEOMETRY module built around Point<T>.
+//
+//   Point<T>         - from m5_generics, now with vector math
+//   LineSegment<T>   - two endpoints; intersection tests
+//   Polygon<T>       - area (shoelace), perimeter, point-in-polygon
+//   Aabb<T>          - axis-aligned bounding box
+//   convex_hull      - Andrew's monotone chain
+//
+// Every algorithm is written once for any number type T. With integer
+// coordinates all the yes/no questions (does it intersect? is it inside?)
+// are answered EXACTLY - no floating point rounding involved.
+//
+// Exact needs room: a cross product multiplies two coordinate differences,
+// which does not fit back into T. Each Coord names a wider type for that:
+//   i32 -> i128   exact over the whole i32 range
+//   f32 -> f64, f64 -> f64   rounding as usual
+// i64 is deliberately NOT a Coord: a difference of two i64s takes 65 bits,
+// a product of two of those 130, and i128 only has 127. No built-in type
+// is wide enough, so it could only be exact for small coordinates - and
+// would silently overflow for large ones.
+//
+// Rectangle from m5_methods converts into a Polygon<i32>, so the shapes
+// from both examples can be used together.
+
+use std::fmt::{self, Debug};
+use std::ops::{Add, Mul, Sub};
+
+// Arithmetic shared by coordinates and their wide versions
+trait Number:
+    Copy + PartialOrd + Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+    fn to_f64(self) -> f64;
+}
+
+// What the algorithms need from a coordinate type
+trait Coord: Number {
+    // Big enough for the product of two differences of coordinates
+    type Wide: Number;
+    fn widen(self) -> Self::Wide;
+}
+
+macro_rules! impl_number {
+    ($($t:ty => $zero:expr),*) => {
+        $(impl Number for $t {
+            const ZERO: Self = $zero;
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        })*
+    };
+}
+
+macro_rules! impl_coord {
+    ($($t:ty => $wide:ty),*) => {
+        $(impl Coord for $t {
+            type Wide = $wide;
+            fn widen(self) -> $wide {
+                self as $wide
+            }
+        })*
+    };
+}
+
+impl_number!(i32 => 0, i128 => 0, f32 => 0.0, f64 => 0.0);
+impl_coord!(i32 => i128, f32 => f64, f64 => f64);
+
+// ---------------------------------------------------------------------
+// Point
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+struct Point<T> {
+    x: T,
+    y: T,
+}
+
+impl<T: Coord> Point<T> {
+    fn new(x: T, y: T) -> Self {
+        Point { x, y }
+    }
+
+    fn sub(self, other: Self) -> Self {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+
+    // 2D cross product (z of the 3D one): > 0 if `other` is counter-clockwise.
+    // Computed in T::Wide so that it cannot overflow.
+    fn cross(self, other: Self) -> T::Wide {
+        self.x.widen() * other.y.widen() - self.y.widen() * other.x.widen()
+    }
+
+    fn distance(self, other: Self) -> f64 {
+        let dx = self.x.to_f64() - other.x.to_f64();
+        let dy = self.y.to_f64() - other.y.to_f64();
+        (dx * dx + dy * dy).sqrt()
+    }
+
+    fn to_f64(self) -> Point<f64> {
+        Point::new(self.x.to_f64(), self.y.to_f64())
+    }
+}
+
+// Which way do we turn going a -> b -> c?
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Orientation {
+    CounterClockwise,
+    Clockwise,
+    Collinear,
+}
+
+// (b - a) x (c - a), subtracting in T::Wide too: i32::MAX - i32::MIN
+// does not fit in an i32
+fn turn<T: Coord>(a: Point<T>, b: Point<T>, c: Point<T>) -> T::Wide {
+    let (ax, ay) = (a.x.widen(), a.y.widen());
+    (b.x.widen() - ax) * (c.y.widen() - ay) - (b.y.widen() - ay) * (c.x.widen() - ax)
+}
+
+fn orientation<T: Coord>(a: Point<T>, b: Point<T>, c: Point<T>) -> Orientation {
+    let turn = turn(a, b, c);
+    if turn > T::Wide::ZERO {
+        Orientation::CounterClockwise
+    } else if turn < T::Wide::ZERO {
+        Orientation::Clockwise
+    } else {
+        Orientation::Collinear
+    }
+}
+
+// ---------------------------------------------------------------------
+// Bounding box
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Aabb<T> {
+    min: Point<T>,
+    max: Point<T>,
+}
+
+fn min_of<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a { b } else { a }
+}
+
+fn max_of<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a { b } else { a }
+}
+
+impl<T: Coord> Aabb<T> {
+    // None for an empty set of points
+    fn from_points(points: &[Point<T>]) -> Option<Self> {
+        let (first, rest) = points.split_first()?;
+        let mut bounds = Aabb { min: *first, max: *first };
+        for p in rest {
+            bounds = bounds.union(&Aabb { min: *p, max: *p });
+        }
+        Some(bounds)
+    }
+
+    // In T::Wide: i32::MAX - i32::MIN does not fit in an i32
+    fn width(&self) -> T::Wide {
+        self.max.x.widen() - self.min.x.widen()
+    }
+
+    fn height(&self) -> T::Wide {
+        self.max.y.widen() - self.min.y.widen()
+    }
+
+    // Edges count as inside
+    fn contains(&self, p: Point<T>) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
+
+    fn intersects(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
+    fn union(&self, other: &Self) -> Self {
+        Aabb {
+            min: Point::new(min_of(self.min.x, other.min.x), min_of(self.min.y, other.min.y)),
+            max: Point::new(max_of(self.max.x, other.max.x), max_of(self.max.y, other.max.y)),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------
+// Line segments
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LineSegment<T> {
+    start: Point<T>,
+    end: Point<T>,
+}
+
+// How two segments meet
+#[derive(Debug, Clone, PartialEq)]
+enum Intersection {
+    None,
+    Point(Point<f64>),
+    // Collinear segments sharing a stretch (may be a single point's worth)
+    Overlap(LineSegment<f64>),
+}
+
+impl<T: Coord> LineSegment<T> {
+    fn new(start: Point<T>, end: Point<T>) -> Self {
+        LineSegment { start, end }
+    }
+
+    fn length(&self) -> f64 {
+        self.start.distance(self.end)
+    }
+
+    fn bounding_box(&self) -> Aabb<T> {
+        Aabb::from_points(&[self.start, self.end]).unwrap()
+    }
+
+    // p lies on this segment (exact for integers)
+    fn contains(&self, p: Point<T>) -> bool {
+        orientation(self.start, self.end, p) == Orientation::Collinear
+            && self.bounding_box().contains(p)
+    }
+
+    // Exact yes/no test using only orientations
+    fn intersects(&self, other: &Self) -> bool {
+        let (a, b, c, d) = (self.start, self.end, other.start, other.end);
+        let (o1, o2) = (orientation(a, b, c), orientation(a, b, d));
+        let (o3, o4) = (orientation(c, d, a), orientation(c, d, b));
+
+        // General case: each segment's endpoints are on opposite sides of the other
+        if o1 != o2 && o3 != o4 && ![o1, o2, o3, o4].contains(&Orientation::Collinear) {
+            return true;
+        }
+        // Touching or collinear cases: some endpoint lies on the other segment
+        self.contains(c) || self.contains(d) || other.contains(a) || other.contains(b)
+    }
+
+    // Where they meet. Whether the segments are parallel is decided exactly;
+    // only the crossing point itself is computed in f64.
+    fn intersection(&self, other: &Self) -> Intersection {
+        if !self.intersects(other) {
+            return Intersection::None;
+        }
+        // r x s for the two directions r and s; zero means parallel
+        let (rx, ry) = (self.end.x.widen() - self.start.x.widen(), self.end.y.widen() - self.start.y.widen());
+        let (sx, sy) = (other.end.x.widen() - other.start.x.widen(), other.end.y.widen() - other.start.y.widen());
+        let parallel = rx * sy - ry * sx == T::Wide::ZERO;
+
+        if !parallel {
+            let (p, r) = (self.start.to_f64(), self.end.to_f64().sub(self.start.to_f64()));
+            let (q, s) = (other.start.to_f64(), other.end.to_f64().sub(other.start.to_f64()));
+            // p + t*r = q + u*s  =>  t = (q - p) x s / (r x s)
+            let t = q.sub(p).cross(s) / r.cross(s);
+            return Intersection::Point(Point::new(p.x + t * r.x, p.y + t * r.y));
+        }
+
+        // Parallel and intersecting: collinear. The overlap is the middle two
+        // of the four endpoints when sorted along the line. A segment that
+        // is a single point lands in the middle twice, so it is caught by
+        // ends[1] == ends[2].
+        let mut ends = [self.start, self.end, other.start, other.end];
+        ends.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        if ends[1] == ends[2] {
+            Intersection::Point(ends[1].to_f64())
+        } else {
+            Intersection::Overlap(LineSegment::new(ends[1].to_f64(), ends[2].to_f64()))
+        }
+    }
+}
+
+// ---------------------------------------------------------------------
+// Polygons
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+struct Polygon<T> {
+    vertices: Vec<Point<T>>, // in order; the last connects back to the first
+}
+
+impl<T: Coord> Polygon<T> {
+    fn new(vertices: Vec<Point<T>>) -> Self {
+        Polygon { vertices }
+    }
+
+    fn edges(&self) -> impl Iterator<Item = LineSegment<T>> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| LineSegment::new(self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    // Shoelace formula: sum of cross products of consecutive vertices.
+    // Returns TWICE the signed area (in T::Wide) so integers stay exact.
+    // Positive = counter-clockwise, negative = clockwise.
+    fn signed_area_doubled(&self) -> T::Wide {
+        self.edges().fold(T::Wide::ZERO, |sum, e| sum + e.start.cross(e.end))
+    }
+
+    fn area(&self) -> f64 {
+        self.signed_area_doubled().to_f64().abs() / 2.0
+    }
+
+    fn perimeter(&self) -> f64 {
+        self.edges().map(|e| e.length()).sum()
+    }
+
+    fn bounding_box(&self) -> Option<Aabb<T>> {
+        Aabb::from_points(&self.vertices)
+    }
+
+    // Winding-number test; points on an edge count as inside.
+    // Works for concave polygons too.
+    fn contains(&self, p: Point<T>) -> bool {
+        let mut winding = 0;
+        for edge in self.edges() {
+            if edge.contains(p) {
+                return true;
+            }
+            let (a, b) = (edge.start, edge.end);
+            if a.y <= p.y {
+                // Upward crossing with p to the left of the edge
+                if b.y > p.y && orientation(a, b, p) == Orientation::CounterClockwise {
+                    winding += 1;
+                }
+            } else if b.y <= p.y && orientation(a, b, p) == Orientation::Clockwise {
+                // Downward crossing with p to the right
+                winding -= 1;
+            }
+        }
+        winding != 0
+    }
+}
+
+// Andrew's monotone chain: sort the points, then build the lower and upper
+// hulls, popping any point that would make a clockwise (or straight) turn.
+// The result is counter-clockwise with collinear points removed.
+fn convex_hull<T: Coord>(points: &[Point<T>]) -> Polygon<T> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).expect("coordinates must not be NaN"));
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return Polygon::new(sorted); // a point or a segment: nothing to wrap
+    }
+
+    let mut hull: Vec<Point<T>> = Vec::with_capacity(sorted.len() + 1);
+    // Lower hull, left to right, then upper hull, right to left
+    for pass in [sorted.clone(), sorted.iter().rev().copied().collect()] {
+        let start = hull.len();
+        for p in pass {
+            while hull.len() >= start + 2
+                && orientation(hull[hull.len() - 2], hull[hull.len() - 1], p)
+                    != Orientation::CounterClockwise
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        hull.pop(); // the last point starts the other half
+    }
+
+    // All points collinear: the "hull" is just the two extreme points
+    if hull.len() < 3 {
+        hull.truncate(2);
+    }
+    Polygon::new(hull)
+}
+
+// ---------------------------------------------------------------------
+// Interop with Rectangle from m5_methods
+// ---------------------------------------------------------------------
+
+#[path = "common/shapes.rs"]
+mod shapes;
+use shapes::Rectangle;
+
+// x + width can pass i32::MAX, and i32 is the widest exact coordinate
+#[derive(Debug, PartialEq)]
+struct OutOfRange;
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the rectangle's far edges do not fit in i32 coordinates")
+    }
+}
+
+impl std::error::Error for OutOfRange {}
+
+// The corners, counter-clockwise from the rectangle's (x, y) origin
+impl TryFrom<&Rectangle> for Polygon<i32> {
+    type Error = OutOfRange;
+    fn try_from(rect: &Rectangle) -> Result<Self, OutOfRange> {
+        let edge = |start: i32, len: u32| i32::try_from(start as i64 + len as i64).map_err(|_| OutOfRange);
+        let (left, top) = (rect.x, rect.y);
+        let (right, bottom) = (edge(left, rect.width)?, edge(top, rect.height)?);
+        Ok(Polygon::new(vec![
+            Point::new(left, top),
+            Point::new(right, top),
+            Point::new(right, bottom),
+            Point::new(left, bottom),
+        ]))
+    }
+}
+
+fn main() -> Result<(), OutOfRange> {
+    println!("=== Polygons ===");
+    // A concave "L" shape, counter-clockwise
+    let l_shape = Polygon::new(vec![
+        Point::new(0, 0),
+        Point::new(4, 0),
+        Point::new(4, 1),
+        Point::new(1, 1),
+        Point::new(1, 3),
+        Point::new(0, 3),
+    ]);
+    println!("L shape: area = {}, perimeter = {}", l_shape.area(), l_shape.perimeter());
+    println!("  signed area x2 = {} (positive: counter-clockwise)", l_shape.signed_area_doubled());
+    println!("  bounding box   = {:?}", l_shape.bounding_box());
+    for p in [Point::new(2, 0), Point::new(0, 2), Point::new(2, 2), Point::new(1, 1), Point::new(5, 5)] {
+        println!("  contains {:?}: {}", p, l_shape.contains(p));
+    }
+    assert_eq!(l_shape.area(), 6.0);
+    assert_eq!(l_shape.perimeter(), 14.0);
+    assert!(l_shape.contains(Point::new(0, 2)));
+    assert!(!l_shape.contains(Point::new(2, 2))); // in the notch
+    assert!(l_shape.contains(Point::new(4, 1))); // a vertex
+
+    // Reversing the vertex order flips the sign of the area
+    let clockwise = Polygon::new(l_shape.vertices.iter().rev().copied().collect());
+    assert_eq!(clockwise.signed_area_doubled(), -12_i128); // i32 coordinates, i128 area
+    assert!(clockwise.contains(Point::new(0, 2)));
+
+    // The same code works for floats
+    let triangle = Polygon::new(vec![Point::new(0.0, 0.0), Point::new(3.0, 0.0), Point::new(0.0, 4.0)]);
+    println!("Triangle: area = {}, perimeter = {}", triangle.area(), triangle.perimeter());
+    assert_eq!(triangle.area(), 6.0);
+    assert!(triangle.contains(Point::new(0.5, 0.5)));
+    assert!(!triangle.contains(Point::new(2.0, 2.0)));
+
+    // Degenerate polygons: no area, but still well behaved
+    let flat = Polygon::new(vec![Point::new(0, 0), Point::new(2, 2), Point::new(4, 4)]);
+    assert_eq!(flat.area(), 0.0);
+    assert!(flat.contains(Point::new(1, 1)));
+    assert_eq!(Polygon::<i32>::new(vec![]).area(), 0.0);
+    assert_eq!(Polygon::<i32>::new(vec![]).bounding_box(), None);
+    assert!(!Polygon::<i32>::new(vec![]).contains(Point::new(0, 0)));
+
+    println!("\n=== Line segments ===");
+    let seg = |x1, y1, x2, y2| LineSegment::new(Point::new(x1, y1), Point::new(x2, y2));
+    let cases = [
+        ("crossing", seg(0, 0, 4, 4), seg(0, 4, 4, 0)),
+        ("touching at an end", seg(0, 0, 2, 2), seg(2, 2, 5, 0)),
+        ("T junction", seg(0, 0, 4, 0), seg(2, 0, 2, 3)),
+        ("parallel", seg(0, 0, 4, 0), seg(0, 1, 4, 1)),
+        ("collinear, apart", seg(0, 0, 1, 1), seg(2, 2, 3, 3)),
+        ("collinear, overlapping", seg(0, 0, 3, 3), seg(1, 1, 5, 5)),
+        ("collinear, end to end", seg(0, 0, 1, 0), seg(1, 0, 2, 0)),
+        ("near miss", seg(0, 0, 2, 2), seg(3, 0, 2, 1)),
+        ("point on segment", seg(1, 1, 1, 1), seg(0, 0, 2, 2)),
+    ];
+    for (label, a, b) in &cases {
+        println!("  {:<24} {:?}", label, a.intersection(b));
+    }
+    assert_eq!(cases[0].1.intersection(&cases[0].2), Intersection::Point(Point::new(2.0, 2.0)));
+    assert_eq!(cases[2].1.intersection(&cases[2].2), Intersection::Point(Point::new(2.0, 0.0)));
+    assert_eq!(cases[3].1.intersection(&cases[3].2), Intersection::None);
+    assert_eq!(cases[4].1.intersection(&cases[4].2), Intersection::None);
+    assert_eq!(
+        cases[5].1.intersection(&cases[5].2),
+        Intersection::Overlap(LineSegment::new(Point::new(1.0, 1.0), Point::new(3.0, 3.0)))
+    );
+    assert_eq!(cases[6].1.intersection(&cases[6].2), Intersection::Point(Point::new(1.0, 0.0)));
+    assert_eq!(cases[7].1.intersection(&cases[7].2), Intersection::None);
+    assert_eq!(cases[8].1.intersection(&cases[8].2), Intersection::Point(Point::new(1.0, 1.0)));
+    // Intersection is symmetric
+    for (_, a, b) in &cases {
+        assert_eq!(a.intersects(b), b.intersects(a));
+    }
+
+    println!("\n=== Convex hull ===");
+    let cloud = [
+        Point::new(0, 0), Point::new(2, 1), Point::new(4, 0), Point::new(3, 2),
+        Point::new(4, 4), Point::new(2, 4), Point::new(0, 4), Point::new(1, 2),
+        Point::new(2, 0), // collinear with (0,0)-(4,0): dropped from the hull
+        Point::new(0, 4), // duplicate
+    ];
+    let hull = convex_hull(&cloud);
+    println!("  hull of {} points: {:?}", cloud.len(), hull.vertices);
+    assert_eq!(
+        hull.vertices,
+        vec![Point::new(0, 0), Point::new(4, 0), Point::new(4, 4), Point::new(0, 4)]
+    );
+    assert!(hull.signed_area_doubled() > 0_i128); // counter-clockwise
+    assert!(cloud.iter().all(|p| hull.contains(*p)));
+
+    // Degenerate inputs
+    assert!(convex_hull::<i32>(&[]).vertices.is_empty());
+    assert_eq!(convex_hull(&[Point::new(1, 1), Point::new(1, 1)]).vertices, vec![Point::new(1, 1)]);
+    let line = convex_hull(&[Point::new(0, 0), Point::new(3, 3), Point::new(1, 1), Point::new(2, 2)]);
+    println!("  hull of collinear points: {:?}", line.vertices);
+    assert_eq!(line.vertices, vec![Point::new(0, 0), Point::new(3, 3)]);
+    let float_hull = convex_hull(&[Point::new(0.0, 0.0), Point::new(1.0, 0.0), Point::new(0.5, 0.1), Point::new(0.5, 1.0)]);
+    assert_eq!(float_hull.vertices.len(), 3);
+
+    println!("\n=== Large coordinates ===");
+    // 100_000^2 no longer fits in an i32; the products are done in i128
+    let (o, far_x, far_y) = (Point::new(0, 0), Point::new(100_000, 0), Point::new(0, 100_000));
+    assert_eq!(orientation(o, far_x, far_y), Orientation::CounterClockwise);
+    assert_eq!(orientation(o, Point::new(50_000, 50_000), Point::new(100_000, 100_000)), Orientation::Collinear);
+    // A square over the whole i32 plane
+    let (lo, hi) = (i32::MIN, i32::MAX);
+    let plane = Polygon::new(vec![Point::new(lo, lo), Point::new(hi, lo), Point::new(hi, hi), Point::new(lo, hi)]);
+    let side = hi as i128 - lo as i128;
+    println!("  i32 plane: signed area x2 = {}", plane.signed_area_doubled());
+    assert_eq!(plane.signed_area_doubled(), 2 * side * side);
+    assert!(plane.contains(Point::new(0, 0)) && plane.contains(Point::new(hi, 7)));
+    assert_eq!(convex_hull(&plane.vertices).vertices, plane.vertices);
+    // Diagonals of that square cross in the middle
+    let (d1, d2) = (seg(lo, lo, hi, hi), seg(lo, hi, hi, lo));
+    println!("  diagonals meet at {:?}", d1.intersection(&d2));
+    assert_eq!(d1.intersection(&d2), Intersection::Point(Point::new(-0.5, -0.5)));
+    // Parallel, one unit apart over their whole length: exactly no contact
+    assert!(!seg(lo, lo, hi, hi - 1).intersects(&seg(lo, lo + 1, hi, hi)));
+
+    println!("\n=== Bounding boxes and Rectangle interop ===");
+    let rect = Rectangle { x: 10, y: 5, width: 30, height: 50 };
+    let as_polygon = Polygon::try_from(&rect)?;
+    let bounds = as_polygon.bounding_box().unwrap();
+    println!("  {:?} -> area {} (width x height = {})", rect, as_polygon.area(), rect.width * rect.height);
+    println!("  bounding box {:?}..{:?}, {}x{}", bounds.min, bounds.max, bounds.width(), bounds.height());
+    assert_eq!(as_polygon.area(), (rect.width * rect.height) as f64);
+    assert_eq!((bounds.min, bounds.max), (Point::new(10, 5), Point::new(40, 55)));
+    assert!(as_polygon.contains(Point::new(10, 5)));
+    assert!(!as_polygon.contains(Point::new(0, 0)));
+    let other = Aabb { min: Point::new(25, 45), max: Point::new(60, 60) };
+    println!("  overlaps {:?}: {}", other, bounds.intersects(&other));
+    println!("  union: {:?}", bounds.union(&other));
+    assert!(bounds.intersects(&other));
+    assert!(!bounds.intersects(&Aabb { min: Point::new(41, 0), max: Point::new(50, 10) }));
+    // The far edge of this one is past i32::MAX
+    let far = Rectangle { x: i32::MAX - 5, y: 0, width: 10, height: 10 };
+    println!("  {:?} -> {}", far, Polygon::try_from(&far).unwrap_err());
+    assert_eq!(Polygon::try_from(&far).unwrap_err(), OutOfRange);
+    let whole = Aabb { min: Point::new(i32::MIN, 0), max: Point::new(i32::MAX, 0) };
+    assert_eq!(whole.width(), u32::MAX as i128);
+
+    println!("\nAll checks passed.");
+    Ok(())
+}
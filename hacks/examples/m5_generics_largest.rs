@@ -0,0 +1,295 @@
+// cargo run --example m5_generics_largest
+//
+// Demonstrates a NON-PANICKING family of `largest` functions.
+//
+// The `largest` from m5_generics has two problems:
+//   1. `&list[0]` panics on an empty slice
+//   2. With f64, `item > largest` is false for NaN, so NaN is silently
+//      ignored - or, if NaN comes first, it "wins" forever
+//
+// The fixes:
+//   - return Option<&T>: None for an empty slice
+//   - require `Ord` (a TOTAL order) for the general functions; f64 is only
+//     PartialOrd, so it cannot be passed by accident
+//   - floats go through largest_float with an explicit NanPolicy
+//
+// cargo test --example m5_generics_largest checks each of them.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+// The original, for comparison
+fn largest<T: PartialOrd>(list: &[T]) -> &T {
+    let mut largest = &list[0];
+    for item in list {
+        if item > largest {
+            largest = item;
+        }
+    }
+    largest
+}
+
+// ---------------------------------------------------------------------
+// Ord-based family
+// ---------------------------------------------------------------------
+
+// None for an empty slice; on ties the FIRST largest element wins
+fn try_largest<T: Ord>(list: &[T]) -> Option<&T> {
+    largest_by(list, T::cmp)
+}
+
+// Largest according to a comparison function
+fn largest_by<T, F>(list: &[T], mut compare: F) -> Option<&T>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let (first, rest) = list.split_first()?;
+    let mut largest = first;
+    for item in rest {
+        if compare(item, largest) == Ordering::Greater {
+            largest = item;
+        }
+    }
+    Some(largest)
+}
+
+// Largest by a derived key, e.g. the longest string
+fn largest_by_key<T, K, F>(list: &[T], mut key: F) -> Option<&T>
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    largest_by(list, |a, b| key(a).cmp(&key(b)))
+}
+
+// Smallest and largest in ONE pass. Taking elements in pairs needs about
+// 3 comparisons per 2 elements instead of 4.
+fn min_max<T: Ord>(list: &[T]) -> Option<(&T, &T)> {
+    let (first, rest) = list.split_first()?;
+    let (mut min, mut max) = (first, first);
+    let mut pairs = rest.chunks_exact(2);
+    for pair in &mut pairs {
+        let (small, big) = if pair[0] <= pair[1] { (&pair[0], &pair[1]) } else { (&pair[1], &pair[0]) };
+        if small < min {
+            min = small;
+        }
+        if big > max {
+            max = big;
+        }
+    }
+    if let [last] = pairs.remainder() {
+        if last < min {
+            min = last;
+        }
+        if last > max {
+            max = last;
+        }
+    }
+    Some((min, max))
+}
+
+// The k largest elements, largest first.
+// A min-heap holds the best k seen so far; its top is the weakest of them,
+// so each new element only has to beat that one. O(n log k) time, O(k) memory.
+fn top_k<T: Ord>(list: &[T], k: usize) -> Vec<&T> {
+    if k == 0 {
+        return Vec::new();
+    }
+    // Reverse turns Rust's max-heap into a min-heap
+    let mut heap: BinaryHeap<Reverse<&T>> = BinaryHeap::with_capacity(k + 1);
+    for item in list {
+        if heap.len() < k {
+            heap.push(Reverse(item));
+        } else if let Some(Reverse(weakest)) = heap.peek() {
+            if item > *weakest {
+                heap.pop();
+                heap.push(Reverse(item));
+            }
+        }
+    }
+    // into_sorted_vec is ascending by Reverse, i.e. descending by value
+    heap.into_sorted_vec().into_iter().map(|Reverse(item)| item).collect()
+}
+
+// ---------------------------------------------------------------------
+// Floats
+// ---------------------------------------------------------------------
+
+// What to do about NaN - there is no single right answer, so callers choose
+#[derive(Debug, Clone, Copy)]
+enum NanPolicy {
+    // Skip NaN values; None if there is nothing else
+    Ignore,
+    // Any NaN makes the result NaN (like SQL NULL, or f64 arithmetic)
+    Propagate,
+    // IEEE 754 total order: -NaN < -inf < ... < +inf < +NaN
+    TotalOrder,
+}
+
+// The little bit of float behaviour we need, for both f32 and f64
+trait Float: Copy + PartialOrd {
+    fn is_nan(self) -> bool;
+    fn total_cmp(&self, other: &Self) -> Ordering;
+}
+
+impl Float for f32 {
+    fn is_nan(self) -> bool {
+        f32::is_nan(self)
+    }
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f32::total_cmp(self, other)
+    }
+}
+
+impl Float for f64 {
+    fn is_nan(self) -> bool {
+        f64::is_nan(self)
+    }
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f64::total_cmp(self, other)
+    }
+}
+
+fn largest_float<F: Float>(list: &[F], policy: NanPolicy) -> Option<&F> {
+    match policy {
+        NanPolicy::Ignore => {
+            let mut best: Option<&F> = None;
+            for item in list.iter().filter(|x| !x.is_nan()) {
+                if best.is_none_or(|b| item > b) {
+                    best = Some(item);
+                }
+            }
+            best
+        }
+        NanPolicy::Propagate => match list.iter().find(|x| x.is_nan()) {
+            Some(nan) => Some(nan),
+            None => largest_float(list, NanPolicy::Ignore),
+        },
+        NanPolicy::TotalOrder => largest_by(list, F::total_cmp),
+    }
+}
+
+fn main() {
+    println!("=== The original largest ===");
+    println!("largest(&[34, 50, 25, 100]) = {}", largest(&[34, 50, 25, 100]));
+    println!("largest(&[1.0, NaN, 3.0])   = {}  (NaN ignored silently)", largest(&[1.0, f64::NAN, 3.0]));
+    println!("largest(&[NaN, 1.0, 3.0])   = {}  (NaN wins because it came first)", largest(&[f64::NAN, 1.0, 3.0]));
+    println!("largest(&[]) would panic: index out of bounds");
+
+    println!("\n=== try_largest / largest_by / largest_by_key ===");
+    let numbers = [34, 50, 25, 100, 100, 7];
+    let empty: [i32; 0] = [];
+    println!("try_largest({:?}) = {:?}", numbers, try_largest(&numbers));
+    println!("try_largest([])  = {:?}", try_largest(&empty));
+    // Ties: the first of the equal largest elements is returned
+    let dupes = [(1, "first"), (3, "a"), (3, "b"), (2, "c")];
+    println!("largest_by(first field) on duplicates = {:?}", largest_by(&dupes, |a, b| a.0.cmp(&b.0)));
+
+    let words = ["pear", "banana", "fig", "cherry"];
+    // "cherry" ties with "banana" but comes later
+    println!("largest_by_key(len) = {:?}", largest_by_key(&words, |w| w.len()));
+
+    // largest_by also handles floats when you supply the comparison
+    let temps = [21.5, -3.0, 37.2];
+    println!("largest_by(total_cmp) = {:?}", largest_by(&temps, f64::total_cmp));
+    // try_largest(&temps) does not compile: f64 is not Ord because of NaN
+
+    println!("\n=== min_max ===");
+    for list in [&[][..], &[5][..], &[3, 1, 4, 1, 5, 9, 2, 6][..], &[7, 7, 7][..]] {
+        println!("min_max({:?}) = {:?}", list, min_max(list));
+    }
+
+    println!("\n=== top_k ===");
+    let scores = [88, 92, 75, 92, 60, 99, 81];
+    // Duplicates are kept; k > len returns everything
+    for k in [0, 1, 3, 10] {
+        println!("top_k({:?}, {}) = {:?}", scores, k, top_k(&scores, k));
+    }
+
+    println!("\n=== Floats with an explicit NaN policy ===");
+    let readings = [1.0, f64::NAN, 3.5, -2.0];
+    let all_nan = [f64::NAN, f64::NAN];
+    for policy in [NanPolicy::Ignore, NanPolicy::Propagate, NanPolicy::TotalOrder] {
+        println!(
+            "{:<10} {:?} -> {:?},  all NaN -> {:?},  empty -> {:?}",
+            format!("{:?}", policy),
+            readings,
+            largest_float(&readings, policy),
+            largest_float(&all_nan, policy),
+            largest_float::<f64>(&[], policy)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_largest_handles_empty_and_single_slices() {
+        let empty: [i32; 0] = [];
+        assert_eq!(try_largest(&empty), None);
+        assert_eq!(try_largest(&[42]), Some(&42));
+        assert_eq!(try_largest(&['a', 'z', 'm']), Some(&'z'));
+        assert_eq!(try_largest(&[34, 50, 25, 100, 100, 7]), Some(&100));
+    }
+
+    #[test]
+    fn ties_return_the_first_largest() {
+        let dupes = [(1, "first"), (3, "a"), (3, "b"), (2, "c")];
+        assert_eq!(largest_by(&dupes, |a, b| a.0.cmp(&b.0)), Some(&(3, "a")));
+        let words = ["pear", "banana", "fig", "cherry"];
+        assert_eq!(largest_by_key(&words, |w| w.len()), Some(&"banana"));
+    }
+
+    #[test]
+    fn largest_by_takes_a_float_comparison() {
+        assert_eq!(largest_by(&[21.5, -3.0, 37.2], f64::total_cmp), Some(&37.2));
+    }
+
+    #[test]
+    fn min_max_covers_odd_and_even_lengths() {
+        assert_eq!(min_max::<i32>(&[]), None);
+        assert_eq!(min_max(&[5]), Some((&5, &5)));
+        assert_eq!(min_max(&[3, 1, 4, 1, 5, 9, 2, 6]), Some((&1, &9)));
+        assert_eq!(min_max(&[2, 1]), Some((&1, &2)));
+        assert_eq!(min_max(&[2, 1, 0]), Some((&0, &2)));
+        assert_eq!(min_max(&[7, 7, 7]), Some((&7, &7)));
+    }
+
+    #[test]
+    fn top_k_keeps_duplicates_and_matches_sorting() {
+        let scores = [88, 92, 75, 92, 60, 99, 81];
+        assert_eq!(top_k(&scores, 3), vec![&99, &92, &92]);
+        assert_eq!(top_k(&scores, 10).len(), scores.len());
+        assert!(top_k(&scores, 0).is_empty());
+        assert!(top_k::<i32>(&[], 3).is_empty());
+        assert_eq!(top_k(&[1], 1), vec![&1]);
+        let mut sorted = scores;
+        sorted.sort_by(|a, b| b.cmp(a));
+        for k in 0..=scores.len() {
+            assert_eq!(top_k(&scores, k), sorted.iter().take(k).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn each_nan_policy() {
+        let readings = [1.0, f64::NAN, 3.5, -2.0];
+        assert_eq!(largest_float(&readings, NanPolicy::Ignore), Some(&3.5));
+        assert!(largest_float(&readings, NanPolicy::Propagate).unwrap().is_nan());
+        assert!(largest_float(&readings, NanPolicy::TotalOrder).unwrap().is_nan());
+        assert_eq!(largest_float(&[f64::NAN, f64::NAN], NanPolicy::Ignore), None);
+        assert_eq!(largest_float::<f64>(&[], NanPolicy::Propagate), None);
+        // NaN first no longer hides the real answer
+        assert_eq!(largest_float(&[f64::NAN, 1.0, 3.0], NanPolicy::Ignore), Some(&3.0));
+    }
+
+    #[test]
+    fn infinity_signed_zero_and_f32() {
+        assert_eq!(largest_float(&[1.0, f64::INFINITY], NanPolicy::Ignore), Some(&f64::INFINITY));
+        // Total order puts -0.0 below 0.0
+        let zero: f64 = *largest_float(&[-0.0, 0.0], NanPolicy::TotalOrder).unwrap();
+        assert!(zero.is_sign_positive());
+        assert_eq!(largest_float(&[1.5f32, 2.5], NanPolicy::Ignore), Some(&2.5f32));
+    }
+}
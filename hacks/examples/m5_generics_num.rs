@@ -0,0 +1,438 @@
+// cargo run --example m5_generics_num
+//
+// Demonstrates NUMERIC GENERICS with our own trait hierarchy.
+//
+// In m5_generics, distance_from_origin only exists for Point<f64>:
+//     impl Point<f64> { fn distance_from_origin(&self) -> f64 { ... } }
+//
+// To write vector math ONCE for i32, i64, f32 and f64 we describe what a
+// "number" can do with traits (no external `num` crate needed):
+//
+//   Num   - +, -, *, /, zero, one, convert to f64     (all four types)
+//   Float - Num + sqrt, negation, convert from f64    (f32 and f64 only)
+//
+// Then methods are added with trait bounds: `impl<T: Num> Point<T>` works
+// for every number, `impl<T: Float> Point<T>` only for floating point.
+//
+// cargo test --example m5_generics_num runs the checks for each type.
+
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// ---------------------------------------------------------------------
+// The trait hierarchy
+// ---------------------------------------------------------------------
+
+trait Num:
+    Copy
+    + PartialOrd
+    + Debug
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    fn to_f64(self) -> f64;
+}
+
+// Float is a SUBTRAIT: every Float is also a Num
+trait Float: Num + Neg<Output = Self> {
+    fn sqrt(self) -> Self;
+    fn from_f64(value: f64) -> Self;
+}
+
+// The impls are identical apart from the type, so a macro writes them
+macro_rules! impl_num {
+    ($($t:ty => $zero:expr, $one:expr);*) => {
+        $(impl Num for $t {
+            const ZERO: Self = $zero;
+            const ONE: Self = $one;
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        })*
+    };
+}
+
+impl_num!(i32 => 0, 1; i64 => 0, 1; f32 => 0.0, 1.0; f64 => 0.0, 1.0);
+
+impl Float for f32 {
+    fn sqrt(self) -> Self {
+        f32::sqrt(self)
+    }
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+impl Float for f64 {
+    fn sqrt(self) -> Self {
+        f64::sqrt(self)
+    }
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+// ---------------------------------------------------------------------
+// Point<T>
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Point<T> {
+    x: T,
+    y: T,
+}
+
+// Methods for ANY T - no bounds needed
+impl<T> Point<T> {
+    fn new(x: T, y: T) -> Self {
+        Point { x, y }
+    }
+
+    fn x(&self) -> &T {
+        &self.x
+    }
+
+    // Lossless conversion using std's From, e.g. Point<i32> -> Point<f64>
+    fn cast<U: From<T>>(self) -> Point<U> {
+        Point { x: U::from(self.x), y: U::from(self.y) }
+    }
+}
+
+// Methods for any NUMBER
+impl<T: Num> Point<T> {
+    fn origin() -> Self {
+        Point::new(T::ZERO, T::ZERO)
+    }
+
+    fn dot(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    // Exact for integers - no square root involved
+    fn length_squared(&self) -> T {
+        self.dot(self)
+    }
+
+    // Distance is rarely a whole number, so it is always an f64
+    fn distance(&self, other: &Self) -> f64 {
+        let dx = self.x.to_f64() - other.x.to_f64();
+        let dy = self.y.to_f64() - other.y.to_f64();
+        (dx * dx + dy * dy).sqrt()
+    }
+
+    // Possibly lossy conversion (i64 -> f64 can round), so not a From impl
+    fn to_f64(self) -> Point<f64> {
+        Point::new(self.x.to_f64(), self.y.to_f64())
+    }
+}
+
+// Methods only for FLOATING POINT types
+impl<T: Float> Point<T> {
+    fn length(&self) -> T {
+        self.length_squared().sqrt()
+    }
+
+    // The generic version of m5_generics' Point<f64>::distance_from_origin
+    fn distance_from_origin(&self) -> T {
+        self.length()
+    }
+
+    // Linear interpolation: t = 0 gives self, t = 1 gives other
+    fn lerp(&self, other: &Self, t: T) -> Self {
+        Point::new(self.x + (other.x - self.x) * t, self.y + (other.y - self.y) * t)
+    }
+
+    // Unit vector in the same direction; None for the zero vector
+    fn normalize(&self) -> Option<Self> {
+        let len = self.length();
+        if len == T::ZERO {
+            None
+        } else {
+            Some(Point::new(self.x / len, self.y / len))
+        }
+    }
+
+    // from_f64 turns a constant into whichever float type T is
+    fn midpoint(&self, other: &Self) -> Self {
+        self.lerp(other, T::from_f64(0.5))
+    }
+}
+
+// Vector arithmetic for any number
+impl<T: Num> Add for Point<T> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Num> Sub for Point<T> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Num> Mul<T> for Point<T> {
+    type Output = Self;
+    fn mul(self, scalar: T) -> Self {
+        Point::new(self.x * scalar, self.y * scalar)
+    }
+}
+
+impl<T: Float> Neg for Point<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+// Lossless conversions as From impls, so `.into()` works
+impl From<Point<i32>> for Point<f64> {
+    fn from(p: Point<i32>) -> Self {
+        p.cast()
+    }
+}
+
+impl From<Point<i32>> for Point<i64> {
+    fn from(p: Point<i32>) -> Self {
+        p.cast()
+    }
+}
+
+impl From<Point<f32>> for Point<f64> {
+    fn from(p: Point<f32>) -> Self {
+        p.cast()
+    }
+}
+
+// ---------------------------------------------------------------------
+// Point3<T> - the same idea in three dimensions
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Point3<T> {
+    x: T,
+    y: T,
+    z: T,
+}
+
+impl<T> Point3<T> {
+    fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+
+    fn cast<U: From<T>>(self) -> Point3<U> {
+        Point3 { x: U::from(self.x), y: U::from(self.y), z: U::from(self.z) }
+    }
+}
+
+impl<T: Num> Point3<T> {
+    fn dot(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    // Perpendicular to both inputs (only exists in 3D)
+    fn cross(&self, other: &Self) -> Self {
+        Point3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    fn length_squared(&self) -> T {
+        self.dot(self)
+    }
+
+    fn distance(&self, other: &Self) -> f64 {
+        let dx = self.x.to_f64() - other.x.to_f64();
+        let dy = self.y.to_f64() - other.y.to_f64();
+        let dz = self.z.to_f64() - other.z.to_f64();
+        (dx * dx + dy * dy + dz * dz).sqrt()
+    }
+
+    fn to_f64(self) -> Point3<f64> {
+        Point3::new(self.x.to_f64(), self.y.to_f64(), self.z.to_f64())
+    }
+}
+
+impl<T: Float> Point3<T> {
+    fn length(&self) -> T {
+        self.length_squared().sqrt()
+    }
+
+    fn lerp(&self, other: &Self, t: T) -> Self {
+        Point3::new(
+            self.x + (other.x - self.x) * t,
+            self.y + (other.y - self.y) * t,
+            self.z + (other.z - self.z) * t,
+        )
+    }
+
+    fn normalize(&self) -> Option<Self> {
+        let len = self.length();
+        if len == T::ZERO {
+            None
+        } else {
+            Some(Point3::new(self.x / len, self.y / len, self.z / len))
+        }
+    }
+}
+
+impl From<Point3<i32>> for Point3<f64> {
+    fn from(p: Point3<i32>) -> Self {
+        p.cast()
+    }
+}
+
+// One generic report, run for every number type below
+fn show_2d<T: Num>(label: &str, a: Point<T>, b: Point<T>) {
+    println!(
+        "  {:<4} a={:?} b={:?}  dot={}  |a|^2={}  distance={:.4}",
+        label, a, b, a.dot(&b), a.length_squared(), a.distance(&b)
+    );
+}
+
+fn main() {
+    println!("=== Num: works for i32, i64, f32, f64 ===");
+    show_2d("i32", Point::new(3, 4), Point::new(6, 8));
+    show_2d("i64", Point::new(3_000_000_000i64, 4), Point::new(-1, 2));
+    show_2d("f32", Point::new(3.0f32, 4.0), Point::new(0.5, -1.5));
+    show_2d("f64", Point::new(3.0, 4.0), Point::new(0.5, -1.5));
+    let a = Point::new(3, 4);
+    println!("  a.x() = {} (methods without bounds still work)", a.x());
+    println!("  distance from {:?} to the origin = {}", a, a.distance(&Point::origin()));
+    println!("  a * ONE = {:?}", a * i32::ONE);
+
+    println!("\n=== Float: only f32 and f64 ===");
+    let p = Point::new(3.0, 4.0);
+    println!("  {:?}.length()     = {}", p, p.length());
+    println!("  {:?}.normalize()  = {:?}", p, p.normalize());
+    println!("  lerp to (13, 14) at 0.5 = {:?}", p.lerp(&Point::new(13.0, 14.0), 0.5));
+    println!("  midpoint with (13, 14)  = {:?}", p.midpoint(&Point::new(13.0, 14.0)));
+    println!("  distance_from_origin    = {}", p.distance_from_origin());
+    println!("  -p = {:?}", -p);
+    // Point::new(3, 4).length() does not compile: i32 is not Float
+
+    println!("\n=== Conversions ===");
+    let int_point = Point::new(5, -10);
+    let as_f64: Point<f64> = int_point.into();
+    let as_i64: Point<i64> = int_point.into();
+    let from_f32: Point<f64> = Point::new(1.5f32, 2.5).into();
+    println!("  Point<i32> {:?} -> Point<f64> {:?}", int_point, as_f64);
+    println!("  Point<i32> {:?} -> Point<i64> {:?}", int_point, as_i64);
+    println!("  Point<f32> -> Point<f64> {:?}", from_f32);
+    println!("  Point<i64> -> Point<f64> (lossy, explicit) {:?}", Point::new(i64::MAX, 0).to_f64());
+
+    println!("\n=== Point3 ===");
+    let x = Point3::new(1, 0, 0);
+    let y = Point3::new(0, 1, 0);
+    println!("  x cross y = {:?}", x.cross(&y));
+    let v = Point3::new(2.0, 3.0, 6.0);
+    println!("  {:?}.length() = {}", v, v.length());
+    println!("  normalize = {:?}", v.normalize());
+    println!("  lerp to (4, 5, 8) at 0.5 = {:?}", v.lerp(&Point3::new(4.0, 5.0, 8.0), 0.5));
+    let z = Point3::new(1i64, 2, 2);
+    println!("  {:?}.to_f64() = {:?}, distance to the origin = {}", z, z.to_f64(), z.distance(&Point3::new(0, 0, 0)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Approximate equality for float results
+    fn close<T: Float>(a: T, b: T) -> bool {
+        let diff = (a - b).to_f64();
+        diff.abs() < 1e-5
+    }
+
+    // One generic check, run for every number type
+    fn check_2d<T: Num>(a: Point<T>, b: Point<T>) {
+        assert_eq!(Point::<T>::origin().length_squared(), T::ZERO);
+        assert_eq!((a + b) - b, a);
+        assert_eq!(a * T::ONE, a);
+        assert_eq!(a.dot(&b), b.dot(&a));
+    }
+
+    #[test]
+    fn num_methods_work_for_every_number_type() {
+        check_2d(Point::new(3, 4), Point::new(6, 8));
+        check_2d(Point::new(3_000_000_000i64, 4), Point::new(-1, 2));
+        check_2d(Point::new(3.0f32, 4.0), Point::new(0.5, -1.5));
+        check_2d(Point::new(3.0, 4.0), Point::new(0.5, -1.5));
+    }
+
+    #[test]
+    fn integer_lengths_are_exact() {
+        let a = Point::new(3, 4);
+        assert_eq!(a.length_squared(), 25);
+        assert_eq!(a.distance(&Point::origin()), 5.0);
+        assert_eq!(*a.x(), 3);
+        assert_eq!(Point::new(2_000_000_000i64, 0).length_squared(), 4_000_000_000_000_000_000);
+    }
+
+    #[test]
+    fn float_methods_f64() {
+        let p = Point::new(3.0, 4.0);
+        assert_eq!(p.distance_from_origin(), 5.0);
+        assert_eq!(p.normalize(), Some(Point::new(0.6, 0.8)));
+        assert_eq!(Point::new(0.0, 0.0).normalize(), None);
+        assert_eq!(p.lerp(&Point::new(13.0, 14.0), 0.0), p);
+        assert_eq!(p.lerp(&Point::new(13.0, 14.0), 1.0), Point::new(13.0, 14.0));
+        assert_eq!(p.midpoint(&Point::new(13.0, 14.0)), Point::new(8.0, 9.0));
+        assert_eq!(-p, Point::new(-3.0, -4.0));
+    }
+
+    #[test]
+    fn float_methods_f32() {
+        let q = Point::new(1.0f32, 1.0);
+        let unit = q.normalize().unwrap();
+        assert!(close(unit.length(), 1.0f32));
+        assert!(close(q.lerp(&Point::new(3.0, 5.0), 0.25).y, 2.0f32));
+        assert_eq!(q.midpoint(&Point::new(3.0, 5.0)), Point::new(2.0f32, 3.0));
+    }
+
+    #[test]
+    fn conversions() {
+        let int_point = Point::new(5, -10);
+        let as_f64: Point<f64> = int_point.into();
+        let as_i64: Point<i64> = int_point.into();
+        let from_f32: Point<f64> = Point::new(1.5f32, 2.5).into();
+        assert_eq!(as_f64, Point::new(5.0, -10.0));
+        assert_eq!(as_i64, Point::new(5i64, -10));
+        assert_eq!(from_f32, Point::new(1.5, 2.5));
+        assert!(close(as_f64.normalize().unwrap().length(), 1.0));
+        // i64 -> f64 rounds, which is why it is not a From impl
+        assert_eq!(Point::new(i64::MAX, 0).to_f64(), Point::new(9.223372036854776e18, 0.0));
+    }
+
+    #[test]
+    fn point3_integer_math() {
+        let x = Point3::new(1, 0, 0);
+        let y = Point3::new(0, 1, 0);
+        assert_eq!(x.cross(&y), Point3::new(0, 0, 1));
+        assert_eq!(x.dot(&y), 0);
+        assert_eq!(Point3::new(1i64, 2, 2).length_squared(), 9);
+        assert_eq!(Point3::new(0, 0, 0).distance(&Point3::new(1, 2, 2)), 3.0);
+    }
+
+    #[test]
+    fn point3_float_math() {
+        let v = Point3::new(2.0, 3.0, 6.0);
+        assert_eq!(v.length(), 7.0);
+        assert!(close(v.normalize().unwrap().length(), 1.0));
+        assert_eq!(Point3::new(0.0f32, 0.0, 0.0).normalize(), None);
+        assert_eq!(v.lerp(&Point3::new(4.0, 5.0, 8.0), 0.5), Point3::new(3.0, 4.0, 7.0));
+        let w: Point3<f64> = Point3::new(1, 2, 3).into();
+        assert_eq!(w, Point3::new(1.0, 2.0, 3.0));
+        assert_eq!(Point3::new(1i64, 2, 3).to_f64(), w);
+        let c = Point3::new(1.0f32, 2.0, 3.0).cross(&Point3::new(4.0, 5.0, 6.0));
+        assert_eq!(c, Point3::new(-3.0, 6.0, -3.0));
+    }
+}
@@ -0,0 +1,201 @@
+// cargo run --example m5_generics_pair
+//
+// Demonstrates a full-featured generic container: Pair<T, U>.
+// The Pair from m5_generics only holds two values. Here it gets:
+//
+//   swap, map_first, map_second, map_both    - transform one or both sides
+//   as_ref, as_mut                           - borrow both sides at once
+//   From<(T, U)> / Into<(T, U)>              - convert to and from tuples
+//   Display (when both sides are Display)    - "(5, hello)"
+//   derived comparisons                      - ==, <, sort, HashMap key
+//   zip_pairs / unzip_pairs                  - iterator helpers
+//
+// Notice how each impl block only asks for the trait bounds it needs.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+// Derives only apply when T and U support them:
+// Pair<i32, &str> is Ord, Pair<f64, i32> is only PartialOrd
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+struct Pair<T, U> {
+    first: T,
+    second: U, // Can be different types
+}
+
+// No bounds: these work for ANY T and U
+impl<T, U> Pair<T, U> {
+    fn new(first: T, second: U) -> Self {
+        Pair { first, second }
+    }
+
+    // The types swap too: Pair<T, U> becomes Pair<U, T>
+    fn swap(self) -> Pair<U, T> {
+        Pair::new(self.second, self.first)
+    }
+
+    // map_* may change the type of the mapped side
+    fn map_first<V, F: FnOnce(T) -> V>(self, f: F) -> Pair<V, U> {
+        Pair::new(f(self.first), self.second)
+    }
+
+    fn map_second<V, F: FnOnce(U) -> V>(self, f: F) -> Pair<T, V> {
+        Pair::new(self.first, f(self.second))
+    }
+
+    fn map_both<V, W, F, G>(self, f: F, g: G) -> Pair<V, W>
+    where
+        F: FnOnce(T) -> V,
+        G: FnOnce(U) -> W,
+    {
+        Pair::new(f(self.first), g(self.second))
+    }
+
+    // Borrow both sides without moving the pair, like Option::as_ref
+    fn as_ref(&self) -> Pair<&T, &U> {
+        Pair::new(&self.first, &self.second)
+    }
+
+    fn as_mut(&mut self) -> Pair<&mut T, &mut U> {
+        Pair::new(&mut self.first, &mut self.second)
+    }
+
+    fn into_tuple(self) -> (T, U) {
+        (self.first, self.second)
+    }
+}
+
+// When both sides have the SAME type, more operations make sense
+impl<T: PartialOrd> Pair<T, T> {
+    fn largest(&self) -> &T {
+        if self.first >= self.second {
+            &self.first
+        } else {
+            &self.second
+        }
+    }
+}
+
+// Tuple -> Pair
+impl<T, U> From<(T, U)> for Pair<T, U> {
+    fn from((first, second): (T, U)) -> Self {
+        Pair::new(first, second)
+    }
+}
+
+// Pair -> tuple. Implementing From gives us Into for free:
+// `let t: (i32, &str) = pair.into();`
+impl<T, U> From<Pair<T, U>> for (T, U) {
+    fn from(pair: Pair<T, U>) -> Self {
+        pair.into_tuple()
+    }
+}
+
+// Display only exists when BOTH sides are Display
+impl<T: fmt::Display, U: fmt::Display> fmt::Display for Pair<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.first, self.second)
+    }
+}
+
+// Iterator helpers --------------------------------------------------
+
+// Walk two iterators in step, yielding Pairs; stops at the shorter one
+fn zip_pairs<A, B>(a: A, b: B) -> impl Iterator<Item = Pair<A::Item, B::Item>>
+where
+    A: IntoIterator,
+    B: IntoIterator,
+{
+    a.into_iter().zip(b).map(Pair::from)
+}
+
+// Split Pairs back into two collections of any kind (Vec, HashSet, String...)
+fn unzip_pairs<T, U, CT, CU, I>(pairs: I) -> (CT, CU)
+where
+    I: IntoIterator<Item = Pair<T, U>>,
+    CT: Default + Extend<T>,
+    CU: Default + Extend<U>,
+{
+    pairs.into_iter().map(Pair::into_tuple).unzip()
+}
+
+fn main() {
+    println!("=== Pair<T, U> ===\n");
+
+    let pair = Pair::new(5, "hello");
+    println!("pair          = {}", pair);
+    println!("swap          = {}", pair.swap());
+    println!("map_first     = {}", pair.map_first(|n| n * 10));
+    println!("map_second    = {}", pair.map_second(str::len));
+    println!("map_both      = {}", pair.map_both(|n| n as f64 / 2.0, |s| s.to_uppercase()));
+    assert_eq!(pair.swap(), Pair::new("hello", 5));
+    assert_eq!(pair.swap().swap(), pair);
+    assert_eq!(pair.map_second(str::len), Pair::new(5, 5));
+
+    // as_ref: look inside a pair of non-Copy values without moving it
+    let owned = Pair::new(String::from("key"), vec![1, 2, 3]);
+    let lengths = owned.as_ref().map_both(|s| s.len(), |v| v.len());
+    println!("\nas_ref lengths = {}, owned is still usable: {:?}", lengths, owned);
+
+    // as_mut: change both sides in place
+    let mut counters = Pair::new(0, String::from("log:"));
+    for word in ["a", "b", "c"] {
+        let Pair { first, second } = counters.as_mut();
+        *first += 1;
+        second.push_str(word);
+    }
+    println!("as_mut result  = {}", counters);
+    assert_eq!(counters, Pair::new(3, String::from("log:abc")));
+
+    // Same-typed pairs get extra methods
+    println!("largest of {}  = {}", Pair::new(3, 9), Pair::new(3, 9).largest());
+
+    println!("\n=== Conversions ===");
+    let from_tuple: Pair<i32, char> = (1, 'x').into();
+    let back: (i32, char) = from_tuple.into();
+    println!("(1, 'x').into()  -> {:?}", from_tuple);
+    println!("pair.into()      -> {:?}", back);
+    assert_eq!(back, (1, 'x'));
+
+    println!("\n=== Comparisons ===");
+    // Derived Ord compares `first`, then `second`
+    let mut pairs = [Pair::new(2, "b"), Pair::new(1, "z"), Pair::new(2, "a")];
+    pairs.sort();
+    println!("sorted: {:?}", pairs.iter().map(|p| p.to_string()).collect::<Vec<_>>());
+    assert!(Pair::new(1, "z") < Pair::new(2, "a"));
+    assert_eq!(Pair::<i32, String>::default(), Pair::new(0, String::new()));
+
+    // Hash + Eq: usable as a HashMap key; Ord: usable in a BTreeSet
+    let mut grid: HashMap<Pair<i32, i32>, &str> = HashMap::new();
+    grid.insert(Pair::new(0, 0), "origin");
+    grid.insert(Pair::new(1, 2), "treasure");
+    println!("grid[(1, 2)] = {}", grid[&Pair::new(1, 2)]);
+    let unique: BTreeSet<_> = [Pair::new(1, 1), Pair::new(0, 5), Pair::new(1, 1)].into_iter().collect();
+    println!("unique, ordered: {:?}", unique);
+    assert_eq!(unique.len(), 2);
+
+    println!("\n=== Iterator helpers ===");
+    let names = ["ada", "grace", "linus"];
+    let years = [1815, 1906, 1969, 2000]; // longer: the extra item is ignored
+    let people: Vec<Pair<&str, i32>> = zip_pairs(names, years).collect();
+    for person in &people {
+        println!("  {}", person);
+    }
+    assert_eq!(people.len(), 3);
+
+    // Unzip into two different collection types
+    let (who, when): (Vec<&str>, Vec<i32>) = unzip_pairs(people.clone());
+    println!("unzipped: {:?} / {:?}", who, when);
+    assert_eq!(who, names);
+    assert_eq!(when, [1815, 1906, 1969]);
+
+    let (initials, as_usize): (String, Vec<usize>) =
+        unzip_pairs(people.iter().map(|p| p.map_both(|n| n.chars().next().unwrap(), |y| y as usize)));
+    println!("initials = {:?}, years = {:?}", initials, as_usize);
+    assert_eq!(initials, "agl");
+
+    // Round trip: zip then unzip gives back the inputs
+    let (a, b): (Vec<i32>, Vec<char>) = unzip_pairs(zip_pairs(vec![1, 2, 3], "xyz".chars()));
+    assert_eq!((a, b), (vec![1, 2, 3], vec!['x', 'y', 'z']));
+    println!("\nAll checks passed.");
+}
@@ -0,0 +1,338 @@
+// cargo run --example m5_generics_stats
+//
+// Demonstrates GENERIC DESCRIPTIVE STATISTICS over numeric slices.
+//
+// `largest` from m5_generics was our only aggregate. Here is a whole family,
+// each written once for i32, i64, u32, u64, f32 and f64:
+//
+//   mean, median, mode
+//   variance, stddev         - sample (n - 1) or population (n)
+//   percentile               - five interpolation methods
+//   z_scores                 - how many standard deviations from the mean
+//
+// Nothing panics: empty input, NaN, or a bad percentile come back as a
+// StatsError the caller can match on.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+// The one thing we need from a number: compare it, and turn it into f64
+trait Numeric: Copy + PartialOrd {
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_numeric {
+    ($($t:ty),*) => {
+        $(impl Numeric for $t {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        })*
+    };
+}
+
+impl_numeric!(i32, i64, u32, u64, f32, f64);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StatsError {
+    Empty,
+    // Sample variance needs at least two values
+    NotEnoughData { needed: usize, found: usize },
+    // A NaN has no place in an ordering
+    NotANumber,
+    // Percentiles run from 0 to 100
+    InvalidPercentile(f64),
+    // z-scores divide by the standard deviation
+    ZeroDeviation,
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StatsError::Empty => write!(f, "no data"),
+            StatsError::NotEnoughData { needed, found } => {
+                write!(f, "need at least {} values, found {}", needed, found)
+            }
+            StatsError::NotANumber => write!(f, "data contains NaN"),
+            StatsError::InvalidPercentile(p) => write!(f, "percentile {} is outside 0..=100", p),
+            StatsError::ZeroDeviation => write!(f, "standard deviation is zero"),
+        }
+    }
+}
+
+impl std::error::Error for StatsError {}
+
+type Result<T> = std::result::Result<T, StatsError>;
+
+// Divide by n (the whole population) or n - 1 (a sample of it)
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Spread {
+    Population,
+    Sample,
+}
+
+// How to pick a value BETWEEN two data points, for percentile
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Interpolation {
+    Linear,   // straight line between the neighbours (spreadsheets, NumPy default)
+    Lower,    // the neighbour below
+    Higher,   // the neighbour above
+    Nearest,  // whichever is closer; halfway goes to the even index
+    Midpoint, // the average of both neighbours
+}
+
+// ---------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------
+
+// A sorted copy, refusing empty input and NaN
+fn sorted<T: Numeric>(data: &[T]) -> Result<Vec<T>> {
+    if data.is_empty() {
+        return Err(StatsError::Empty);
+    }
+    let mut values = data.to_vec();
+    let mut has_nan = false;
+    values.sort_by(|a, b| {
+        a.partial_cmp(b).unwrap_or_else(|| {
+            has_nan = true;
+            Ordering::Equal
+        })
+    });
+    // A NaN compared against itself is not caught by sort, so check too
+    if has_nan || values.iter().any(|v| v.to_f64().is_nan()) {
+        return Err(StatsError::NotANumber);
+    }
+    Ok(values)
+}
+
+// ---------------------------------------------------------------------
+// Central tendency
+// ---------------------------------------------------------------------
+
+fn mean<T: Numeric>(data: &[T]) -> Result<f64> {
+    if data.is_empty() {
+        return Err(StatsError::Empty);
+    }
+    let sum: f64 = data.iter().map(|v| v.to_f64()).sum();
+    if sum.is_nan() {
+        return Err(StatsError::NotANumber);
+    }
+    Ok(sum / data.len() as f64)
+}
+
+// The middle value; for an even count, the mean of the two middle values
+fn median<T: Numeric>(data: &[T]) -> Result<f64> {
+    let values = sorted(data)?;
+    let mid = values.len() / 2;
+    if values.len() % 2 == 1 {
+        Ok(values[mid].to_f64())
+    } else {
+        Ok((values[mid - 1].to_f64() + values[mid].to_f64()) / 2.0)
+    }
+}
+
+// The most frequent value(s), ascending. A tie returns every winner, and
+// data where every value is unique returns all of them.
+fn mode<T: Numeric>(data: &[T]) -> Result<Vec<T>> {
+    let values = sorted(data)?;
+    let mut modes = Vec::new();
+    let mut best = 0;
+    // Equal values are neighbours after sorting, so count runs
+    for run in values.chunk_by(|a, b| a == b) {
+        match run.len().cmp(&best) {
+            Ordering::Greater => {
+                best = run.len();
+                modes = vec![run[0]];
+            }
+            Ordering::Equal => modes.push(run[0]),
+            Ordering::Less => {}
+        }
+    }
+    Ok(modes)
+}
+
+// ---------------------------------------------------------------------
+// Spread
+// ---------------------------------------------------------------------
+
+// Two passes: mean first, then squared distances from it. Subtracting the
+// mean before squaring avoids the precision loss of sum(x^2) - n*mean^2.
+fn variance<T: Numeric>(data: &[T], spread: Spread) -> Result<f64> {
+    let m = mean(data)?;
+    let divisor = match spread {
+        Spread::Population => data.len(),
+        Spread::Sample if data.len() < 2 => {
+            return Err(StatsError::NotEnoughData { needed: 2, found: data.len() });
+        }
+        Spread::Sample => data.len() - 1,
+    };
+    let squares: f64 = data.iter().map(|v| (v.to_f64() - m).powi(2)).sum();
+    if squares.is_nan() {
+        return Err(StatsError::NotANumber);
+    }
+    Ok(squares / divisor as f64)
+}
+
+fn stddev<T: Numeric>(data: &[T], spread: Spread) -> Result<f64> {
+    variance(data, spread).map(f64::sqrt)
+}
+
+// ---------------------------------------------------------------------
+// Percentiles
+// ---------------------------------------------------------------------
+
+// p in 0..=100. The rank p/100 * (n - 1) usually falls between two data
+// points; `method` decides what value to report.
+fn percentile<T: Numeric>(data: &[T], p: f64, method: Interpolation) -> Result<f64> {
+    if !(0.0..=100.0).contains(&p) {
+        return Err(StatsError::InvalidPercentile(p));
+    }
+    let values = sorted(data)?;
+    let rank = p / 100.0 * (values.len() - 1) as f64;
+    let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+    let (below, above) = (values[lo].to_f64(), values[hi].to_f64());
+    let fraction = rank - lo as f64;
+
+    Ok(match method {
+        Interpolation::Linear => below + (above - below) * fraction,
+        Interpolation::Lower => below,
+        Interpolation::Higher => above,
+        Interpolation::Nearest => match fraction.partial_cmp(&0.5) {
+            Some(Ordering::Less) => below,
+            Some(Ordering::Greater) => above,
+            _ if lo % 2 == 0 => below,
+            _ => above,
+        },
+        Interpolation::Midpoint => (below + above) / 2.0,
+    })
+}
+
+// ---------------------------------------------------------------------
+// Normalization
+// ---------------------------------------------------------------------
+
+// (x - mean) / stddev for every value
+fn z_scores<T: Numeric>(data: &[T], spread: Spread) -> Result<Vec<f64>> {
+    let m = mean(data)?;
+    let sd = stddev(data, spread)?;
+    if sd == 0.0 {
+        return Err(StatsError::ZeroDeviation);
+    }
+    Ok(data.iter().map(|v| (v.to_f64() - m) / sd).collect())
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+fn main() -> std::result::Result<(), StatsError> {
+    println!("=== Central tendency ===");
+    let scores = [2, 4, 4, 4, 5, 5, 7, 9];
+    println!("data   = {:?}", scores);
+    println!("mean   = {}", mean(&scores)?);
+    println!("median = {}  (even length: average of 4 and 5)", median(&scores)?);
+    println!("mode   = {:?}", mode(&scores)?);
+    assert_eq!(mean(&scores)?, 5.0);
+    assert_eq!(median(&scores)?, 4.5);
+    assert_eq!(median(&[3, 1, 2])?, 2.0);
+    assert_eq!(mode(&scores)?, vec![4]);
+    assert_eq!(mode(&[1, 1, 2, 2, 3])?, vec![1, 2]); // a tie returns both
+    assert_eq!(mode(&[2.5, 0.5])?, vec![0.5, 2.5]);
+
+    // Same functions, other types
+    let big: [u64; 3] = [u64::MAX, u64::MAX, u64::MAX];
+    println!("mean of three u64::MAX = {:e}  (no integer overflow)", mean(&big)?);
+    assert_eq!(mean(&[1.5f32, 2.5])?, 2.0);
+    assert_eq!(median(&[-10i64, 10])?, 0.0);
+
+    println!("\n=== Spread ===");
+    for spread in [Spread::Population, Spread::Sample] {
+        println!(
+            "{:<10} variance = {:.4}, stddev = {:.4}",
+            format!("{:?}", spread),
+            variance(&scores, spread)?,
+            stddev(&scores, spread)?
+        );
+    }
+    assert_eq!(variance(&scores, Spread::Population)?, 4.0);
+    assert_eq!(stddev(&scores, Spread::Population)?, 2.0);
+    assert!(close(variance(&scores, Spread::Sample)?, 32.0 / 7.0));
+    // One value has no sample spread, but does have a population one
+    assert_eq!(variance(&[7], Spread::Population)?, 0.0);
+    assert_eq!(
+        variance(&[7], Spread::Sample),
+        Err(StatsError::NotEnoughData { needed: 2, found: 1 })
+    );
+    // Large offsets do not destroy precision thanks to the two-pass method
+    let offset = [1e9 + 4.0, 1e9 + 7.0, 1e9 + 13.0, 1e9 + 16.0];
+    assert!(close(variance(&offset, Spread::Sample)?, 30.0));
+
+    println!("\n=== Percentiles ===");
+    let data = [15, 20, 35, 40, 50];
+    let methods = [
+        Interpolation::Linear,
+        Interpolation::Lower,
+        Interpolation::Higher,
+        Interpolation::Nearest,
+        Interpolation::Midpoint,
+    ];
+    println!("data = {:?}", data);
+    for p in [0.0, 30.0, 40.0, 62.5, 100.0] {
+        let row: Vec<String> = methods
+            .iter()
+            .map(|&m| format!("{:?}={}", m, percentile(&data, p, m).unwrap()))
+            .collect();
+        println!("  p{:<5} {}", p, row.join("  "));
+    }
+    // rank for p40 = 0.4 * 4 = 1.6: between 20 (index 1) and 35 (index 2)
+    assert!(close(percentile(&data, 40.0, Interpolation::Linear)?, 29.0));
+    assert_eq!(percentile(&data, 40.0, Interpolation::Lower)?, 20.0);
+    assert_eq!(percentile(&data, 40.0, Interpolation::Higher)?, 35.0);
+    assert_eq!(percentile(&data, 40.0, Interpolation::Nearest)?, 35.0);
+    assert_eq!(percentile(&data, 40.0, Interpolation::Midpoint)?, 27.5);
+    // rank 2.5 is exactly halfway: Nearest picks the even index (2)
+    assert_eq!(percentile(&data, 62.5, Interpolation::Nearest)?, 35.0);
+    // The ends are the min and max for every method; p50 is the median
+    for m in methods {
+        assert_eq!(percentile(&data, 0.0, m)?, 15.0);
+        assert_eq!(percentile(&data, 100.0, m)?, 50.0);
+    }
+    assert_eq!(percentile(&scores, 50.0, Interpolation::Linear)?, median(&scores)?);
+    assert_eq!(percentile(&[42], 73.0, Interpolation::Linear)?, 42.0);
+
+    println!("\n=== z-scores ===");
+    let z = z_scores(&scores, Spread::Population)?;
+    println!("z_scores({:?}) = {:?}", scores, z);
+    assert_eq!(z, vec![-1.5, -0.5, -0.5, -0.5, 0.0, 0.0, 1.0, 2.0]);
+    // Normalized data has mean 0 and stddev 1
+    assert!(close(mean(&z)?, 0.0));
+    assert!(close(stddev(&z, Spread::Population)?, 1.0));
+    assert_eq!(z_scores(&[3, 3, 3], Spread::Population), Err(StatsError::ZeroDeviation));
+
+    println!("\n=== Errors instead of panics ===");
+    let empty: [f64; 0] = [];
+    let with_nan = [1.0, f64::NAN, 3.0];
+    let results = [
+        ("mean([])", mean(&empty)),
+        ("median([])", median(&empty)),
+        ("mean([1, NaN, 3])", mean(&with_nan)),
+        ("median([1, NaN, 3])", median(&with_nan)),
+        ("variance([1, NaN, 3])", variance(&with_nan, Spread::Population)),
+        ("percentile(.., 101)", percentile(&data, 101.0, Interpolation::Linear)),
+        ("percentile(.., NaN)", percentile(&data, f64::NAN, Interpolation::Linear)),
+        ("stddev([5], Sample)", stddev(&[5.0], Spread::Sample)),
+    ];
+    for (call, result) in &results {
+        match result {
+            Ok(value) => println!("  {:<24} -> {}", call, value),
+            Err(e) => println!("  {:<24} -> error: {}", call, e),
+        }
+        assert!(result.is_err());
+    }
+    assert_eq!(mode(&empty), Err(StatsError::Empty));
+    assert_eq!(mode(&[f64::NAN]), Err(StatsError::NotANumber));
+    assert_eq!(z_scores(&empty, Spread::Sample), Err(StatsError::Empty));
+
+    println!("\nAll checks passed.");
+    Ok(())
+}
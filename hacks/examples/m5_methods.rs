@@ -2,38 +2,62 @@
 //
 // Demonstrates methods and associated functions using impl blocks.
 // Methods are functions attached to a struct (or enum/trait).
+//
+// The second half adds overflow-safe methods. `width * height` on two u32s
+// can overflow (a panic in debug builds, a wrong answer in release), so the
+// checked_* versions widen to u64 or return an Option instead.
 
+// The struct lives in common/shapes.rs, shared with m5_serde_csv, and is
+// defined there like this:
+//
+//     #[derive(Debug, PartialEq)]
+//     struct Rectangle {
+//         // Top-left corner. x grows to the right, y grows downwards.
+//         x: i32,
+//         y: i32,
+//         width: u32,
+//         height: u32,
+//     }
+//
 // #[derive(Debug)] automatically implements the Debug trait
 // allowing us to print the struct with {:?}
-#[derive(Debug)]
-struct Rectangle {
-    width: u32,
-    height: u32,
-}
+// PartialEq lets us compare rectangles with ==
+// With `--features serde` it can also be saved as JSON (see m5_serde_csv)
+#[path = "common/shapes.rs"]
+mod shapes;
+use shapes::Rectangle;
 
 // impl block: where we define methods for Rectangle
 impl Rectangle {
     // ASSOCIATED FUNCTION (no self parameter)
     // Called with :: syntax: Rectangle::new(30, 50)
     // Often used as constructors
+    // The rectangle starts at the origin (0, 0)
     fn new(width: u32, height: u32) -> Rectangle {
-        Rectangle { width, height }
+        Rectangle::at(0, 0, width, height)
     }
 
     // Another associated function - creates a square
     fn square(size: u32) -> Rectangle {
-        Rectangle { width: size, height: size }
+        Rectangle::new(size, size)
+    }
+
+    // A rectangle with its top-left corner at (x, y)
+    fn at(x: i32, y: i32, width: u32, height: u32) -> Rectangle {
+        Rectangle { x, y, width, height }
     }
 
     // METHOD with &self - borrows the instance immutably
     // Called with dot syntax: rect.area()
     // Most common type - reads data but doesn't modify
+    // Overflows for large rectangles: see checked_area
     fn area(&self) -> u32 {
         self.width * self.height
     }
 
     // METHOD with &mut self - borrows the instance mutably
     // Can modify the struct's fields
+    // Overflows for large rectangles: see checked_double
     fn double(&mut self) {
         self.width *= 2;
         self.height *= 2;
@@ -44,7 +68,86 @@ impl Rectangle {
     // Useful for transformations that return a new value
     fn into_square(self) -> Rectangle {
         let side = self.width.max(self.height);
-        Rectangle { width: side, height: side }
+        Rectangle { width: side, height: side, ..self }
+    }
+
+    // ---------------------------------------------------------------
+    // Overflow-safe methods
+    // ---------------------------------------------------------------
+
+    // u32 * u32 always fits in a u64, so this can never overflow
+    fn checked_area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    // None instead of overflowing; the original is left untouched
+    fn checked_double(&self) -> Option<Rectangle> {
+        Some(Rectangle {
+            width: self.width.checked_mul(2)?,
+            height: self.height.checked_mul(2)?,
+            ..*self
+        })
+    }
+
+    // The far edges, in i64 because x + width may not fit in an i32
+    fn right(&self) -> i64 {
+        self.x as i64 + self.width as i64
+    }
+
+    fn bottom(&self) -> i64 {
+        self.y as i64 + self.height as i64
+    }
+
+    // Could `other` fit inside self? Compares sizes only, not positions.
+    fn can_hold(&self, other: &Rectangle) -> bool {
+        self.width >= other.width && self.height >= other.height
+    }
+
+    // The left and top edges are inside, the right and bottom edges are not,
+    // so two rectangles sharing an edge never both contain a point
+    fn contains_point(&self, x: i32, y: i32) -> bool {
+        let (x, y) = (x as i64, y as i64);
+        x >= self.x as i64 && x < self.right() && y >= self.y as i64 && y < self.bottom()
+    }
+
+    // The overlapping part, or None if they only touch or are apart
+    fn intersection(&self, other: &Rectangle) -> Option<Rectangle> {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        if right <= left as i64 || bottom <= top as i64 {
+            return None;
+        }
+        // Both fit: the overlap is never bigger than either rectangle
+        let width = (right - left as i64) as u32;
+        let height = (bottom - top as i64) as u32;
+        Some(Rectangle::at(left, top, width, height))
+    }
+
+    // The smallest rectangle covering both.
+    // None if it is too wide or tall to describe with u32 sizes.
+    fn union_bounds(&self, other: &Rectangle) -> Option<Rectangle> {
+        let left = self.x.min(other.x);
+        let top = self.y.min(other.y);
+        let width = self.right().max(other.right()) - left as i64;
+        let height = self.bottom().max(other.bottom()) - top as i64;
+        Some(Rectangle::at(left, top, u32::try_from(width).ok()?, u32::try_from(height).ok()?))
+    }
+
+    // Resize by a factor, keeping the top-left corner where it is.
+    // Sizes are rounded; None for a negative, NaN or too-large result.
+    fn scale(&self, factor: f64) -> Option<Rectangle> {
+        let scale_side = |side: u32| {
+            let scaled = (side as f64 * factor).round();
+            // The range check is false for NaN too
+            (0.0..=u32::MAX as f64).contains(&scaled).then_some(scaled as u32)
+        };
+        Some(Rectangle {
+            width: scale_side(self.width)?,
+            height: scale_side(self.height)?,
+            ..*self
+        })
     }
 }
 
@@ -69,4 +172,62 @@ fn main() {
     let square = rect3.into_square();  // rect3 is moved here
     println!("\nConverted to square: {:?}", square);
     // println!("{:?}", rect3);  // ERROR: rect3 was moved
+
+    // Overflow-safe methods
+    println!("\n=== Overflow-safe methods ===");
+    let huge = Rectangle::new(100_000, 100_000);
+    // huge.area() would overflow: 10 billion does not fit in a u32
+    println!("checked_area of {:?}: {}", huge, huge.checked_area());
+    assert_eq!(huge.checked_area(), 10_000_000_000);
+    assert_eq!(Rectangle::new(u32::MAX, u32::MAX).checked_area(), u32::MAX as u64 * u32::MAX as u64);
+
+    println!("checked_double of {:?}: {:?}", rect, rect.checked_double());
+    println!("checked_double of a u32::MAX-wide rectangle: {:?}", Rectangle::new(u32::MAX, 1).checked_double());
+    assert_eq!(rect.checked_double(), Some(Rectangle::new(60, 100)));
+    assert_eq!(Rectangle::new(u32::MAX / 2 + 1, 1).checked_double(), None);
+
+    // Positions
+    println!("\n=== Positions ===");
+    let a = Rectangle::at(0, 0, 10, 10);
+    let b = Rectangle::at(5, 5, 10, 10);
+    let beside = Rectangle::at(10, 0, 5, 5); // shares a's right edge
+    println!("a = {:?}\nb = {:?}", a, b);
+    println!("a can hold a 10x10 rectangle: {}", a.can_hold(&Rectangle::square(10)));
+    println!("a contains (0, 0): {}, (10, 10): {}", a.contains_point(0, 0), a.contains_point(10, 10));
+    println!("a intersection b = {:?}", a.intersection(&b));
+    println!("a intersection beside = {:?}", a.intersection(&beside));
+    println!("a union b = {:?}", a.union_bounds(&b));
+    assert!(a.can_hold(&Rectangle::new(10, 3)));
+    assert!(!a.can_hold(&Rectangle::new(11, 3)));
+    assert!(a.contains_point(9, 9));
+    assert!(!a.contains_point(10, 5)); // the right edge belongs to `beside`
+    assert!(beside.contains_point(10, 0));
+    assert!(!a.contains_point(-1, 0));
+    assert_eq!(a.intersection(&b), Some(Rectangle::at(5, 5, 5, 5)));
+    assert_eq!(a.intersection(&beside), None);
+    assert_eq!(a.intersection(&a), Some(Rectangle::at(0, 0, 10, 10)));
+    assert_eq!(a.union_bounds(&b), Some(Rectangle::at(0, 0, 15, 15)));
+
+    // Negative coordinates and the extremes of i32
+    let far_left = Rectangle::at(i32::MIN, 0, 1, 1);
+    let far_right = Rectangle::at(i32::MAX, 0, u32::MAX, 1);
+    assert!(far_right.contains_point(i32::MAX, 0)); // right edge is past i32::MAX
+    assert_eq!(far_left.intersection(&far_right), None);
+    // Spanning both ends is wider than any u32 can describe
+    assert_eq!(far_left.union_bounds(&far_right), None);
+    assert_eq!(
+        Rectangle::at(-5, -5, 10, 10).union_bounds(&Rectangle::at(3, 3, 1, 1)),
+        Some(Rectangle::at(-5, -5, 10, 10))
+    );
+
+    println!("\n=== scale ===");
+    let base = Rectangle::at(2, 3, 10, 5);
+    for factor in [2.0, 0.5, 0.0, -1.0, f64::NAN, 1e10] {
+        println!("scale({}) = {:?}", factor, base.scale(factor));
+    }
+    assert_eq!(base.scale(2.0), Some(Rectangle::at(2, 3, 20, 10)));
+    assert_eq!(base.scale(0.5), Some(Rectangle::at(2, 3, 5, 3))); // 2.5 rounds up
+    assert_eq!(base.scale(-1.0), None);
+    assert_eq!(base.scale(f64::NAN), None);
+    assert_eq!(base.scale(1e10), None);
 }
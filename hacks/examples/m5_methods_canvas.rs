@@ -0,0 +1,620 @@
+// cargo run --example m5_methods_canvas
+//
+// Demonstrates drawing shapes as ASCII art on a terminal CANVAS.
+// `{:?}` on a Rectangle prints numbers; this prints a picture:
+//
+//   +--------+
+//   |  1500  |
+//   +--------+
+//
+// The canvas maps WORLD coordinates (the shapes' own units) to character
+// CELLS:
+//   origin  - the world position shown in the top-left cell
+//   scale   - world units per cell; terminal cells are about twice as tall
+//             as wide, so (1, 2) keeps squares looking square
+//   clip    - an optional world rectangle; nothing is drawn outside it
+//
+// Every shape has a z value. A cell keeps whatever was drawn with the
+// highest z, so the drawing order only matters for equal z.
+//
+// cargo test --example m5_methods_canvas checks more rendered frames.
+
+use std::ops::Range;
+
+// Rectangle from m5_methods (positioned)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rectangle {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl Rectangle {
+    fn at(x: i32, y: i32, width: u32, height: u32) -> Rectangle {
+        Rectangle { x, y, width, height }
+    }
+
+    fn checked_area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    fn contains_point(&self, x: i64, y: i64) -> bool {
+        x >= self.x as i64
+            && x < self.x as i64 + self.width as i64
+            && y >= self.y as i64
+            && y < self.y as i64 + self.height as i64
+    }
+}
+
+// Point<T> from m5_generics, for any number type that converts to f64
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Point<T> {
+    x: T,
+    y: T,
+}
+
+trait Coord: Copy {
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_coord {
+    ($($t:ty),*) => {
+        $(impl Coord for $t {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        })*
+    };
+}
+
+impl_coord!(i32, i64, u32, f32, f64);
+
+// ---------------------------------------------------------------------
+// Styles
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+struct Glyphs {
+    horizontal: char,
+    vertical: char,
+    corner: char,
+    fill: Option<char>, // None leaves rectangles hollow
+    point: char,
+    line: Option<char>, // None picks - | / \ from the slope
+}
+
+impl Default for Glyphs {
+    fn default() -> Self {
+        Glyphs { horizontal: '-', vertical: '|', corner: '+', fill: None, point: '*', line: None }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Label {
+    None,
+    Area, // rectangles only: width x height in world units
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Style {
+    z: i32,
+    glyphs: Glyphs,
+    label: Label,
+}
+
+impl Style {
+    fn new(z: i32) -> Self {
+        Style { z, glyphs: Glyphs::default(), label: Label::None }
+    }
+
+    fn fill(mut self, glyph: char) -> Self {
+        self.glyphs.fill = Some(glyph);
+        self
+    }
+
+    fn glyphs(mut self, glyphs: Glyphs) -> Self {
+        self.glyphs = glyphs;
+        self
+    }
+
+    fn label(mut self, label: Label) -> Self {
+        self.label = label;
+        self
+    }
+}
+
+// ---------------------------------------------------------------------
+// Canvas
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+struct CanvasOptions {
+    columns: usize,
+    rows: usize,
+    origin: (i32, i32),
+    scale: (u32, u32),
+    clip: Option<Rectangle>,
+    border: bool, // frame the picture so its edges are visible
+}
+
+impl Default for CanvasOptions {
+    fn default() -> Self {
+        CanvasOptions { columns: 40, rows: 12, origin: (0, 0), scale: (1, 1), clip: None, border: false }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Cell {
+    glyph: char,
+    z: i32,
+}
+
+const EMPTY: Cell = Cell { glyph: ' ', z: i32::MIN };
+
+struct Canvas {
+    options: CanvasOptions,
+    cells: Vec<Cell>,
+}
+
+impl Canvas {
+    fn new(mut options: CanvasOptions) -> Canvas {
+        // A scale of 0 would divide by zero
+        options.scale = (options.scale.0.max(1), options.scale.1.max(1));
+        let cells = vec![EMPTY; options.columns * options.rows];
+        Canvas { options, cells }
+    }
+
+    fn clear(&mut self) {
+        self.cells.fill(EMPTY);
+    }
+
+    // World position -> fractional cell position
+    fn to_cell(&self, x: f64, y: f64) -> (f64, f64) {
+        let (ox, oy) = self.options.origin;
+        let (sx, sy) = self.options.scale;
+        ((x - ox as f64) / sx as f64, (y - oy as f64) / sy as f64)
+    }
+
+    // The cell containing a world position
+    fn cell_of(&self, x: i64, y: i64) -> (i64, i64) {
+        let (ox, oy) = self.options.origin;
+        let (sx, sy) = self.options.scale;
+        ((x - ox as i64).div_euclid(sx as i64), (y - oy as i64).div_euclid(sy as i64))
+    }
+
+    // Set one cell, unless it is off the grid, outside the clip rectangle,
+    // or already holds something with a higher z
+    fn plot(&mut self, col: i64, row: i64, glyph: char, z: i32) {
+        let (columns, rows) = (self.options.columns as i64, self.options.rows as i64);
+        if !(0..columns).contains(&col) || !(0..rows).contains(&row) {
+            return;
+        }
+        if let Some(clip) = &self.options.clip {
+            // A cell belongs to the clip area if its top-left corner does
+            let (ox, oy) = self.options.origin;
+            let (sx, sy) = self.options.scale;
+            if !clip.contains_point(ox as i64 + col * sx as i64, oy as i64 + row * sy as i64) {
+                return;
+            }
+        }
+        let cell = &mut self.cells[(row * columns + col) as usize];
+        if z >= cell.z {
+            *cell = Cell { glyph, z };
+        }
+    }
+
+    // Write text starting at a cell, clipped like everything else
+    fn text(&mut self, col: i64, row: i64, text: &str, z: i32) {
+        for (i, c) in text.chars().enumerate() {
+            self.plot(col + i as i64, row, c, z);
+        }
+    }
+
+    fn draw_rect(&mut self, rect: &Rectangle, style: &Style) {
+        if rect.width == 0 || rect.height == 0 {
+            return;
+        }
+        // The cells holding the first and last world unit on each axis
+        let (left, top) = self.cell_of(rect.x as i64, rect.y as i64);
+        let (right, bottom) =
+            self.cell_of(rect.x as i64 + rect.width as i64 - 1, rect.y as i64 + rect.height as i64 - 1);
+
+        // Only visit cells that are on the grid: a huge rectangle stays cheap
+        let visible = |lo: i64, hi: i64, len: usize| -> Range<i64> { lo.max(0)..hi.min(len as i64 - 1) + 1 };
+        let g = &style.glyphs;
+        for row in visible(top, bottom, self.options.rows) {
+            for col in visible(left, right, self.options.columns) {
+                let on_row_edge = row == top || row == bottom;
+                let on_col_edge = col == left || col == right;
+                let glyph = match (on_row_edge, on_col_edge) {
+                    (true, true) => Some(g.corner),
+                    (true, false) => Some(g.horizontal),
+                    (false, true) => Some(g.vertical),
+                    (false, false) => g.fill,
+                };
+                if let Some(glyph) = glyph {
+                    self.plot(col, row, glyph, style.z);
+                }
+            }
+        }
+
+        // Labels go in the middle, inside the border; too long ones are cut
+        let text = match &style.label {
+            Label::None => return,
+            Label::Area => rect.checked_area().to_string(),
+            Label::Text(text) => text.clone(),
+        };
+        let inside = (right - left - 1).max(0) as usize;
+        let text: String = text.chars().take(inside).collect();
+        let start = left + 1 + (inside - text.chars().count()) as i64 / 2;
+        self.text(start, (top + bottom) / 2, &text, style.z);
+    }
+
+    fn draw_point<T: Coord>(&mut self, p: Point<T>, style: &Style) {
+        let (col, row) = self.to_cell(p.x.to_f64(), p.y.to_f64());
+        // `NaN as i64` is 0: a NaN point would land in the top-left cell
+        if !col.is_finite() || !row.is_finite() {
+            return;
+        }
+        let (col, row) = (col.floor() as i64, row.floor() as i64);
+        self.plot(col, row, style.glyphs.point, style.z);
+        if let Label::Text(text) = &style.label {
+            // The leading space keeps the label clear of lines behind it
+            self.text(col + 1, row, &format!(" {}", text), style.z);
+        }
+    }
+
+    fn draw_line<T: Coord>(&mut self, a: Point<T>, b: Point<T>, style: &Style) {
+        let (x0, y0) = self.to_cell(a.x.to_f64(), a.y.to_f64());
+        let (x1, y1) = self.to_cell(b.x.to_f64(), b.y.to_f64());
+        // A NaN or infinite endpoint has no place on the grid
+        if ![x0, y0, x1, y1].iter().all(|v| v.is_finite()) {
+            return;
+        }
+        let (dx, dy) = (x1 - x0, y1 - y0);
+
+        // Pick a glyph from the slope; y grows downwards, so dx*dy > 0 is '\'
+        let glyph = style.glyphs.line.unwrap_or(if dy.abs() > 2.0 * dx.abs() {
+            '|'
+        } else if dx.abs() > 2.0 * dy.abs() {
+            '-'
+        } else if dx * dy > 0.0 {
+            '\\'
+        } else {
+            '/'
+        });
+
+        // Step one cell at a time along the longer axis. Only the part of
+        // the line that crosses the grid is walked, so far-away endpoints
+        // cost nothing.
+        let steps = dx.abs().max(dy.abs()).ceil().max(1.0);
+        let (columns, rows) = (self.options.columns as f64, self.options.rows as f64);
+        let (mut t0, mut t1) = (0.0f64, 1.0f64);
+        for (start, delta, limit) in [(x0, dx, columns), (y0, dy, rows)] {
+            if delta == 0.0 {
+                if start < 0.0 || start >= limit {
+                    return;
+                }
+                continue;
+            }
+            let (mut enter, mut exit) = ((0.0 - start) / delta, (limit - start) / delta);
+            if enter > exit {
+                std::mem::swap(&mut enter, &mut exit);
+            }
+            t0 = t0.max(enter);
+            t1 = t1.min(exit);
+        }
+        if t0 > t1 {
+            return;
+        }
+        let first = (t0 * steps).floor() as i64;
+        let last = (t1 * steps).ceil() as i64;
+        for i in first..=last {
+            // Multiply before dividing: dx * (i / steps) rounds unevenly
+            // and leaves gaps in long lines
+            let (x, y) = (x0 + dx * i as f64 / steps, y0 + dy * i as f64 / steps);
+            self.plot(x.floor() as i64, y.floor() as i64, glyph, style.z);
+        }
+
+        if let Label::Text(text) = &style.label {
+            let (mx, my) = ((x0 + x1) / 2.0, (y0 + y1) / 2.0);
+            self.text(mx.floor() as i64 + 1, my.floor() as i64, text, style.z);
+        }
+    }
+
+    // The picture as text. Without a border, trailing spaces are trimmed.
+    fn render(&self) -> String {
+        let columns = self.options.columns;
+        let rows = self.cells.chunks(columns.max(1)).take(self.options.rows);
+        let lines: Vec<String> = rows
+            .map(|row| {
+                let line: String = row.iter().map(|c| c.glyph).collect();
+                if self.options.border {
+                    format!("|{}|", line)
+                } else {
+                    line.trim_end().to_string()
+                }
+            })
+            .collect();
+        if self.options.border {
+            let edge = format!("+{}+", "-".repeat(columns));
+            format!("{}\n{}\n{}", edge, lines.join("\n"), edge)
+        } else {
+            lines.join("\n")
+        }
+    }
+}
+
+// Compare a frame with the expected picture, showing both on failure
+fn assert_frame(canvas: &Canvas, expected: &str) {
+    let actual = canvas.render();
+    assert_eq!(actual, expected, "\n--- rendered ---\n{}\n--- expected ---\n{}\n", actual, expected);
+}
+
+fn main() {
+    println!("=== A labelled rectangle ===");
+    let mut canvas = Canvas::new(CanvasOptions { columns: 12, rows: 3, ..Default::default() });
+    canvas.draw_rect(&Rectangle::at(0, 0, 10, 3), &Style::new(0).label(Label::Area));
+    println!("{}", canvas.render());
+    assert_frame(&canvas, "+--------+\n|   30   |\n+--------+");
+
+    println!("\n=== Scaling: Rectangle 30x50 at 5 units per cell ===");
+    let mut canvas = Canvas::new(CanvasOptions { columns: 8, rows: 10, scale: (5, 5), ..Default::default() });
+    canvas.draw_rect(&Rectangle::at(0, 0, 30, 50), &Style::new(0).fill('.').label(Label::Area));
+    println!("{}", canvas.render());
+    assert_frame(
+        &canvas,
+        "+----+\n|....|\n|....|\n|....|\n|1500|\n|....|\n|....|\n|....|\n|....|\n+----+",
+    );
+
+    println!("\n=== Z-order ===");
+    let back = Rectangle::at(0, 0, 8, 4);
+    let front = Rectangle::at(4, 2, 8, 4);
+    let hash = Glyphs { fill: Some('#'), ..Glyphs::default() };
+    let mut canvas = Canvas::new(CanvasOptions { columns: 14, rows: 6, ..Default::default() });
+    // Drawn back first, but `back` has the higher z, so it stays on top
+    canvas.draw_rect(&front, &Style::new(1).glyphs(hash.clone()));
+    canvas.draw_rect(&back, &Style::new(2).fill('.'));
+    println!("{}", canvas.render());
+    assert_frame(
+        &canvas,
+        "+------+\n|......|\n|......|---+\n+------+###|\n    |######|\n    +------+",
+    );
+    // Swapping the z values flips the picture
+    canvas.clear();
+    canvas.draw_rect(&front, &Style::new(2).glyphs(hash));
+    canvas.draw_rect(&back, &Style::new(1).fill('.'));
+    println!("\n{}", canvas.render());
+    assert_frame(
+        &canvas,
+        "+------+\n|......|\n|...+------+\n+---|######|\n    |######|\n    +------+",
+    );
+
+    println!("\n=== Points, lines and clipping ===");
+    let options = CanvasOptions { columns: 16, rows: 6, origin: (-2, -1), border: true, ..Default::default() };
+    let mut canvas = Canvas::new(options.clone());
+    canvas.draw_line(Point { x: -2, y: -1 }, Point { x: 3, y: 4 }, &Style::new(0));
+    canvas.draw_line(Point { x: 4, y: 0 }, Point { x: 13, y: 0 }, &Style::new(0).label(Label::None));
+    canvas.draw_line(Point { x: 12.0, y: 1.0 }, Point { x: 12.0, y: 3.5 }, &Style::new(0));
+    canvas.draw_point(Point { x: 6.5, y: 2.5 }, &Style::new(1).label(Label::Text(String::from("p"))));
+    // Shapes running off the canvas are cut at its edge
+    canvas.draw_rect(&Rectangle::at(10, 3, 100, 100), &Style::new(-1).fill(':'));
+    canvas.draw_line(Point { x: -1000, y: 2 }, Point { x: 1000, y: 2 }, &Style::new(-2));
+    println!("{}", canvas.render());
+    assert_frame(
+        &canvas,
+        "+----------------+\n\
+         |\\               |\n\
+         | \\    ----------|\n\
+         |  \\           | |\n\
+         |---\\----* p---|-|\n\
+         |    \\       +-|-|\n\
+         |     \\      |:::|\n\
+         +----------------+",
+    );
+
+    // The same scene with a clip rectangle: only the middle is drawn
+    let mut clipped = Canvas::new(CanvasOptions { clip: Some(Rectangle::at(0, 0, 8, 4)), ..options });
+    clipped.draw_line(Point { x: -2, y: -1 }, Point { x: 3, y: 4 }, &Style::new(0));
+    clipped.draw_line(Point { x: 4, y: 0 }, Point { x: 13, y: 0 }, &Style::new(0));
+    clipped.draw_point(Point { x: 6.5, y: 2.5 }, &Style::new(1).label(Label::Text(String::from("p"))));
+    clipped.draw_line(Point { x: -1000, y: 2 }, Point { x: 1000, y: 2 }, &Style::new(-2));
+    println!("\nclipped to {:?}:\n{}", Rectangle::at(0, 0, 8, 4), clipped.render());
+    assert_frame(
+        &clipped,
+        "+----------------+\n\
+         |                |\n\
+         |      ----      |\n\
+         |  \\             |\n\
+         |  -\\----*       |\n\
+         |    \\           |\n\
+         |                |\n\
+         +----------------+",
+    );
+
+    println!("\n=== Edge cases ===");
+    // Nothing visible: off-canvas shapes, empty rectangles, a zero-size canvas
+    let mut canvas = Canvas::new(CanvasOptions { columns: 5, rows: 2, scale: (0, 0), ..Default::default() });
+    canvas.draw_rect(&Rectangle::at(100, 100, 5, 5), &Style::new(0));
+    canvas.draw_rect(&Rectangle::at(0, 0, 0, 5), &Style::new(0));
+    canvas.draw_line(Point { x: -50, y: -50 }, Point { x: -10, y: -1 }, &Style::new(0));
+    assert_frame(&canvas, "\n");
+    // A rectangle far larger than the canvas is only walked where visible
+    canvas.draw_rect(&Rectangle::at(i32::MIN, i32::MIN, u32::MAX, u32::MAX), &Style::new(0).fill('#'));
+    assert_frame(&canvas, "#####\n#####");
+    // Long labels are cut to fit inside the border
+    let mut canvas = Canvas::new(CanvasOptions { columns: 6, rows: 3, ..Default::default() });
+    canvas.draw_rect(&Rectangle::at(0, 0, 6, 3), &Style::new(0).label(Label::Text(String::from("overflow"))));
+    assert_frame(&canvas, "+----+\n|over|\n+----+");
+    assert_eq!(Canvas::new(CanvasOptions { columns: 0, rows: 0, ..Default::default() }).render(), "");
+    println!("All frames match.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Snapshots are written one row per string
+    fn frame(rows: &[&str]) -> String {
+        rows.join("\n")
+    }
+
+    fn canvas(columns: usize, rows: usize) -> Canvas {
+        Canvas::new(CanvasOptions { columns, rows, ..Default::default() })
+    }
+
+    #[test]
+    fn hollow_rectangle_inside_a_border() {
+        let mut c = Canvas::new(CanvasOptions { columns: 10, rows: 4, border: true, ..Default::default() });
+        c.draw_rect(&Rectangle::at(1, 0, 6, 4), &Style::new(0));
+        assert_frame(
+            &c,
+            &frame(&[
+                "+----------+",
+                "| +----+   |",
+                "| |    |   |",
+                "| |    |   |",
+                "| +----+   |",
+                "+----------+",
+            ]),
+        );
+    }
+
+    #[test]
+    fn custom_glyphs_for_every_shape() {
+        let g = Glyphs { horizontal: '=', vertical: '!', corner: '#', fill: Some('~'), point: 'o', line: Some('x') };
+        let mut c = canvas(12, 5);
+        c.draw_rect(&Rectangle::at(0, 0, 5, 4), &Style::new(0).glyphs(g.clone()));
+        c.draw_line(Point { x: 6, y: 0 }, Point { x: 11, y: 4 }, &Style::new(0).glyphs(g.clone()));
+        c.draw_point(Point { x: 7u32, y: 4u32 }, &Style::new(1).glyphs(g));
+        assert_frame(
+            &c,
+            &frame(&[
+                "#===# xx",
+                "!~~~!   x",
+                "!~~~!    x",
+                "#===#     x",
+                "       o   x",
+            ]),
+        );
+    }
+
+    #[test]
+    fn line_glyphs_follow_the_slope() {
+        let mut c = Canvas::new(CanvasOptions { columns: 9, rows: 9, origin: (-4, -4), ..Default::default() });
+        let s = Style::new(0);
+        c.draw_line(Point { x: -4, y: 0 }, Point { x: 4, y: 0 }, &s);
+        c.draw_line(Point { x: 0, y: -4 }, Point { x: 0, y: 4 }, &s);
+        c.draw_line(Point { x: -4, y: -4 }, Point { x: 4, y: 4 }, &s);
+        c.draw_line(Point { x: -4, y: 4 }, Point { x: 4, y: -4 }, &s);
+        c.draw_point(Point { x: 0, y: 0 }, &Style::new(1));
+        assert_frame(
+            &c,
+            &frame(&[
+                r"\   |   /",
+                r" \  |  /",
+                r"  \ | /",
+                r"   \|/",
+                "----*----",
+                r"   /|\",
+                r"  / | \",
+                r" /  |  \",
+                r"/   |   \",
+            ]),
+        );
+    }
+
+    #[test]
+    fn tall_scale_keeps_the_label_centred() {
+        let mut c = Canvas::new(CanvasOptions { columns: 10, rows: 6, scale: (1, 2), ..Default::default() });
+        c.draw_rect(&Rectangle::at(0, 0, 10, 12), &Style::new(0).label(Label::Area));
+        assert_frame(
+            &c,
+            &frame(&[
+                "+--------+",
+                "|        |",
+                "|  120   |",
+                "|        |",
+                "|        |",
+                "+--------+",
+            ]),
+        );
+    }
+
+    #[test]
+    fn negative_origin_and_labels_at_the_edge() {
+        let mut c = Canvas::new(CanvasOptions { columns: 12, rows: 4, origin: (-3, -2), ..Default::default() });
+        c.draw_rect(&Rectangle::at(-3, -2, 12, 4), &Style::new(0).fill('.'));
+        // Two rows tall: the label sits on the top edge
+        c.draw_rect(&Rectangle::at(0, -1, 4, 2), &Style::new(1).label(Label::Text(String::from("hi"))));
+        // The point's label runs off the right side and is cut
+        c.draw_point(Point { x: 7.9, y: 0.99 }, &Style::new(2).label(Label::Text(String::from("edge"))));
+        assert_frame(
+            &c,
+            &frame(&[
+                "+----------+",
+                "|..+hi+....|",
+                "|..+--+...*",
+                "+----------+",
+            ]),
+        );
+    }
+
+    #[test]
+    fn clip_rectangle_masks_a_filled_shape() {
+        let clip = Some(Rectangle::at(2, 1, 4, 2));
+        let mut c = Canvas::new(CanvasOptions { columns: 8, rows: 4, clip, border: true, ..Default::default() });
+        c.draw_rect(&Rectangle::at(0, 0, 8, 4), &Style::new(0).fill('#'));
+        assert_frame(
+            &c,
+            &frame(&[
+                "+--------+",
+                "|        |",
+                "|  ####  |",
+                "|  ####  |",
+                "|        |",
+                "+--------+",
+            ]),
+        );
+    }
+
+    #[test]
+    fn equal_z_means_last_drawn_wins() {
+        let mut c = canvas(6, 3);
+        c.draw_rect(&Rectangle::at(0, 0, 6, 3), &Style::new(0).fill('a'));
+        c.draw_rect(&Rectangle::at(0, 0, 6, 3), &Style::new(0).fill('b'));
+        assert_frame(&c, "+----+\n|bbbb|\n+----+");
+        // ...while a lower z never overwrites
+        c.draw_rect(&Rectangle::at(0, 0, 6, 3), &Style::new(-1).fill('c'));
+        assert_frame(&c, "+----+\n|bbbb|\n+----+");
+    }
+
+    #[test]
+    fn non_finite_coordinates_draw_nothing() {
+        let mut c = canvas(6, 3);
+        let s = Style::new(0).label(Label::Text(String::from("x")));
+        for bad in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            c.draw_point(Point { x: bad, y: 1.0 }, &s);
+            c.draw_point(Point { x: 1.0, y: bad }, &s);
+            c.draw_line(Point { x: bad, y: 0.0 }, Point { x: 5.0, y: 2.0 }, &s);
+            c.draw_line(Point { x: 0.0, y: 0.0 }, Point { x: 5.0, y: bad }, &s);
+        }
+        assert_frame(&c, "\n\n");
+        // Finite ones next to them still draw
+        c.draw_point(Point { x: 2.0, y: 1.0 }, &Style::new(0));
+        assert_frame(&c, &frame(&["", "  *", ""]));
+    }
+
+    #[test]
+    fn clear_empties_every_row() {
+        let mut c = canvas(6, 2);
+        c.draw_rect(&Rectangle::at(0, 0, 6, 2), &Style::new(5));
+        c.clear();
+        assert_frame(&c, "\n");
+        // Cleared cells accept any z again
+        c.draw_point(Point { x: 0, y: 0 }, &Style::new(i32::MIN));
+        assert_frame(&c, "*\n");
+    }
+}
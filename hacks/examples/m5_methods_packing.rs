@@ -0,0 +1,645 @@
+// cargo run --example m5_methods_packing
+//
+// Demonstrates 2D BIN PACKING: fitting item Rectangles into a container.
+// Think of printing labels on a sheet.
+//
+// Three algorithms, from simplest to best:
+//
+//   Shelf       - fill rows left to right; start a new row when one is full
+//   Guillotine  - cut the free space in two after every placement
+//   MaxRects    - track every maximal free rectangle (they may overlap)
+//
+// Items may optionally be rotated by 90 degrees. The report says where
+// each item went, how much of the container is used, and why any item
+// could not be placed.
+//
+// cargo test --example m5_methods_packing checks every report with
+// verify() - no two placements overlap and all of them stay inside the
+// container - over hundreds of random layouts.
+
+use std::fmt;
+
+// Rectangle from m5_methods (positioned, overflow-safe)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rectangle {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl Rectangle {
+    const fn new(width: u32, height: u32) -> Rectangle {
+        Rectangle::at(0, 0, width, height)
+    }
+
+    const fn at(x: i32, y: i32, width: u32, height: u32) -> Rectangle {
+        Rectangle { x, y, width, height }
+    }
+
+    fn checked_area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+}
+
+// Only the tests' verify() needs these
+#[cfg(test)]
+impl Rectangle {
+    fn right(&self) -> i64 {
+        self.x as i64 + self.width as i64
+    }
+
+    fn bottom(&self) -> i64 {
+        self.y as i64 + self.height as i64
+    }
+
+    fn intersection(&self, other: &Rectangle) -> Option<Rectangle> {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        if right <= left as i64 || bottom <= top as i64 {
+            return None;
+        }
+        Some(Rectangle::at(left, top, (right - left as i64) as u32, (bottom - top as i64) as u32))
+    }
+
+    // `other` lies completely inside self
+    fn contains_rect(&self, other: &Rectangle) -> bool {
+        other.x >= self.x && other.y >= self.y && other.right() <= self.right() && other.bottom() <= self.bottom()
+    }
+}
+
+// ---------------------------------------------------------------------
+// Free space, measured from the container's top-left corner
+// ---------------------------------------------------------------------
+
+// Everything stays inside the container, so u32 offsets cannot overflow
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Slot {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+impl Slot {
+    fn area(&self) -> u64 {
+        self.w as u64 * self.h as u64
+    }
+
+    fn fits(&self, w: u32, h: u32) -> bool {
+        w <= self.w && h <= self.h
+    }
+
+    fn overlaps(&self, other: &Slot) -> bool {
+        self.x < other.x + other.w
+            && other.x < self.x + self.w
+            && self.y < other.y + other.h
+            && other.y < self.y + self.h
+    }
+
+    fn contains(&self, other: &Slot) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.x + other.w <= self.x + self.w
+            && other.y + other.h <= self.y + self.h
+    }
+
+    // Items are placed at a slot's top-left corner; can one start here?
+    fn starts_within(&self, reach: Reach) -> bool {
+        self.x <= reach.x && self.y <= reach.y
+    }
+}
+
+// The largest offsets at which an item may start. A container near
+// i32::MAX has room whose absolute position does not fit in an i32; the
+// packers skip those spots instead of using them up.
+#[derive(Debug, Clone, Copy)]
+struct Reach {
+    x: u32,
+    y: u32,
+}
+
+impl Reach {
+    fn of(container: &Rectangle) -> Reach {
+        // i32::MAX - i32::MIN is u32::MAX, so this always fits
+        let room = |origin: i32| (i32::MAX as i64 - origin as i64) as u32;
+        Reach { x: room(container.x), y: room(container.y) }
+    }
+}
+
+// The ways an item can be laid down: as given, and turned if allowed
+fn orientations(w: u32, h: u32, allow_rotation: bool) -> Vec<(u32, u32, bool)> {
+    if allow_rotation && w != h {
+        vec![(w, h, false), (h, w, true)]
+    } else {
+        vec![(w, h, false)]
+    }
+}
+
+// Each algorithm finds a spot for one item at a time.
+// Returns the slot it used and whether the item was rotated.
+trait Packer {
+    fn insert(&mut self, w: u32, h: u32, allow_rotation: bool) -> Option<(Slot, bool)>;
+}
+
+// ---------------------------------------------------------------------
+// Shelf
+// ---------------------------------------------------------------------
+
+struct Shelf {
+    y: u32,
+    height: u32,
+    used_width: u32,
+}
+
+struct ShelfPacker {
+    width: u32,
+    height: u32,
+    reach: Reach,
+    shelves: Vec<Shelf>,
+}
+
+impl Packer for ShelfPacker {
+    fn insert(&mut self, w: u32, h: u32, allow_rotation: bool) -> Option<(Slot, bool)> {
+        // First shelf with room; pick the orientation that wastes least height
+        for shelf in &mut self.shelves {
+            let best = orientations(w, h, allow_rotation)
+                .into_iter()
+                .filter(|_| shelf.used_width <= self.reach.x && shelf.y <= self.reach.y)
+                .filter(|&(w, h, _)| h <= shelf.height && w <= self.width - shelf.used_width)
+                .min_by_key(|&(_, h, _)| shelf.height - h);
+            if let Some((w, h, rotated)) = best {
+                let slot = Slot { x: shelf.used_width, y: shelf.y, w, h };
+                shelf.used_width += w;
+                return Some((slot, rotated));
+            }
+        }
+
+        // Open a new shelf below the last one, lying the item flat to keep it low
+        let top = self.shelves.last().map_or(0, |s| s.y + s.height);
+        if top > self.reach.y {
+            return None;
+        }
+        let (w, h, rotated) = orientations(w, h, allow_rotation)
+            .into_iter()
+            .filter(|&(w, h, _)| w <= self.width && h <= self.height - top)
+            .min_by_key(|&(_, h, _)| h)?;
+        self.shelves.push(Shelf { y: top, height: h, used_width: w });
+        Some((Slot { x: 0, y: top, w, h }, rotated))
+    }
+}
+
+// ---------------------------------------------------------------------
+// Guillotine
+// ---------------------------------------------------------------------
+
+// Free space is a list of non-overlapping slots. Each placement takes the
+// top-left corner of a slot; the rest of the slot is cut in two with one
+// straight "guillotine" cut.
+struct GuillotinePacker {
+    free: Vec<Slot>,
+    reach: Reach,
+}
+
+impl Packer for GuillotinePacker {
+    fn insert(&mut self, w: u32, h: u32, allow_rotation: bool) -> Option<(Slot, bool)> {
+        // Best area fit: the slot with the least area left over
+        let mut best: Option<(u64, usize, u32, u32, bool)> = None;
+        for (i, slot) in self.free.iter().enumerate() {
+            for (w, h, rotated) in orientations(w, h, allow_rotation) {
+                if !slot.fits(w, h) || !slot.starts_within(self.reach) {
+                    continue;
+                }
+                let waste = slot.area() - w as u64 * h as u64;
+                if best.is_none_or(|b| waste < b.0) {
+                    best = Some((waste, i, w, h, rotated));
+                }
+            }
+        }
+        let (_, i, w, h, rotated) = best?;
+        let slot = self.free.swap_remove(i);
+
+        // Cut along the shorter leftover side, so the bigger piece stays whole
+        let (right, below) = if slot.w - w < slot.h - h {
+            // Horizontal cut: the piece below spans the slot's full width
+            (Slot { x: slot.x + w, y: slot.y, w: slot.w - w, h }, Slot { x: slot.x, y: slot.y + h, w: slot.w, h: slot.h - h })
+        } else {
+            // Vertical cut: the piece to the right spans the full height
+            (Slot { x: slot.x + w, y: slot.y, w: slot.w - w, h: slot.h }, Slot { x: slot.x, y: slot.y + h, w, h: slot.h - h })
+        };
+        self.free.extend([right, below].into_iter().filter(|s| s.area() > 0));
+        Some((Slot { x: slot.x, y: slot.y, w, h }, rotated))
+    }
+}
+
+// ---------------------------------------------------------------------
+// MaxRects
+// ---------------------------------------------------------------------
+
+// Free space is the list of ALL maximal empty rectangles. They overlap each
+// other, which is what lets MaxRects find spots the guillotine cuts lose.
+struct MaxRectsPacker {
+    free: Vec<Slot>,
+    reach: Reach,
+}
+
+impl Packer for MaxRectsPacker {
+    fn insert(&mut self, w: u32, h: u32, allow_rotation: bool) -> Option<(Slot, bool)> {
+        // Best short side fit: the smallest leftover along either side,
+        // then along the other side as a tie-breaker
+        let mut best: Option<((u32, u32), Slot, bool)> = None;
+        for slot in &self.free {
+            for (w, h, rotated) in orientations(w, h, allow_rotation) {
+                if !slot.fits(w, h) || !slot.starts_within(self.reach) {
+                    continue;
+                }
+                let (dw, dh) = (slot.w - w, slot.h - h);
+                let score = (dw.min(dh), dw.max(dh));
+                if best.is_none_or(|b| score < b.0) {
+                    best = Some((score, Slot { x: slot.x, y: slot.y, w, h }, rotated));
+                }
+            }
+        }
+        let (_, placed, rotated) = best?;
+
+        // Every free rectangle the item overlaps shrinks to the (up to four)
+        // parts left, above, right and below the item
+        let mut next = Vec::with_capacity(self.free.len() + 4);
+        for f in &self.free {
+            if !f.overlaps(&placed) {
+                next.push(*f);
+                continue;
+            }
+            if placed.x > f.x {
+                next.push(Slot { w: placed.x - f.x, ..*f });
+            }
+            if placed.x + placed.w < f.x + f.w {
+                next.push(Slot { x: placed.x + placed.w, w: f.x + f.w - (placed.x + placed.w), ..*f });
+            }
+            if placed.y > f.y {
+                next.push(Slot { h: placed.y - f.y, ..*f });
+            }
+            if placed.y + placed.h < f.y + f.h {
+                next.push(Slot { y: placed.y + placed.h, h: f.y + f.h - (placed.y + placed.h), ..*f });
+            }
+        }
+
+        // Drop rectangles that sit inside another one (keeping one of equals)
+        let mut maximal: Vec<Slot> = Vec::with_capacity(next.len());
+        for (i, a) in next.iter().enumerate() {
+            let redundant = next
+                .iter()
+                .enumerate()
+                .any(|(j, b)| i != j && b.contains(a) && (a != b || j < i));
+            if !redundant {
+                maximal.push(*a);
+            }
+        }
+        self.free = maximal;
+        Some((placed, rotated))
+    }
+}
+
+// ---------------------------------------------------------------------
+// Packing and reports
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Algorithm {
+    Shelf,
+    Guillotine,
+    MaxRects,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Options {
+    algorithm: Algorithm,
+    allow_rotation: bool,
+}
+
+// Where item number `item` went
+#[derive(Debug, Clone, PartialEq)]
+struct Placement {
+    item: usize,
+    rect: Rectangle,
+    rotated: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum UnplacedReason {
+    // Zero width or height: nothing to place
+    Empty,
+    // Would not fit even in the empty container
+    TooLarge,
+    // Fits on its own, but not in the space left
+    NoSpace,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Unplaced {
+    item: usize,
+    reason: UnplacedReason,
+}
+
+#[derive(Debug)]
+struct PackingReport {
+    container: Rectangle,
+    placements: Vec<Placement>, // sorted by item number
+    unplaced: Vec<Unplaced>,    // sorted by item number
+}
+
+// Only the sizes of `items` matter; their positions are ignored
+fn pack(container: &Rectangle, items: &[Rectangle], options: Options) -> PackingReport {
+    let (width, height) = (container.width, container.height);
+    let whole = Slot { x: 0, y: 0, w: width, h: height };
+    let reach = Reach::of(container);
+    let mut packer: Box<dyn Packer> = match options.algorithm {
+        Algorithm::Shelf => Box::new(ShelfPacker { width, height, reach, shelves: Vec::new() }),
+        Algorithm::Guillotine => Box::new(GuillotinePacker { free: vec![whole], reach }),
+        Algorithm::MaxRects => Box::new(MaxRectsPacker { free: vec![whole], reach }),
+    };
+
+    // Big items first: they are the hardest to fit later. Shelves care
+    // about height (after laying the item flat), the others about area.
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by_key(|&i| {
+        let (w, h) = (items[i].width, items[i].height);
+        let key = match options.algorithm {
+            Algorithm::Shelf if options.allow_rotation => w.min(h) as u64,
+            Algorithm::Shelf => h as u64,
+            _ => items[i].checked_area(),
+        };
+        std::cmp::Reverse(key)
+    });
+
+    let mut placements = Vec::new();
+    let mut unplaced = Vec::new();
+    for i in order {
+        let (w, h) = (items[i].width, items[i].height);
+        if w == 0 || h == 0 {
+            unplaced.push(Unplaced { item: i, reason: UnplacedReason::Empty });
+            continue;
+        }
+        if !orientations(w, h, options.allow_rotation).iter().any(|&(w, h, _)| whole.fits(w, h)) {
+            unplaced.push(Unplaced { item: i, reason: UnplacedReason::TooLarge });
+            continue;
+        }
+        // The packers respect `reach`, so the absolute position fits in an i32
+        let placed = packer.insert(w, h, options.allow_rotation).map(|(slot, rotated)| {
+            let x = container.x.checked_add_unsigned(slot.x).expect("slot within reach");
+            let y = container.y.checked_add_unsigned(slot.y).expect("slot within reach");
+            Placement { item: i, rect: Rectangle::at(x, y, slot.w, slot.h), rotated }
+        });
+        match placed {
+            Some(placement) => placements.push(placement),
+            None => unplaced.push(Unplaced { item: i, reason: UnplacedReason::NoSpace }),
+        }
+    }
+    placements.sort_by_key(|p| p.item);
+    unplaced.sort_by_key(|u| u.item);
+    PackingReport { container: *container, placements, unplaced }
+}
+
+impl PackingReport {
+    fn used_area(&self) -> u64 {
+        self.placements.iter().map(|p| p.rect.checked_area()).sum()
+    }
+
+    // Fraction of the container covered, 0.0 to 1.0
+    fn utilization(&self) -> f64 {
+        match self.container.checked_area() {
+            0 => 0.0,
+            total => self.used_area() as f64 / total as f64,
+        }
+    }
+}
+
+impl fmt::Display for PackingReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} placed, {} unplaced, {:.1}% used",
+            self.placements.len(),
+            self.unplaced.len(),
+            self.utilization() * 100.0
+        )
+    }
+}
+
+const ALGORITHMS: [Algorithm; 3] = [Algorithm::Shelf, Algorithm::Guillotine, Algorithm::MaxRects];
+
+// Labels for a 100x60 sheet, including two that can never be placed
+const LABELS: [Rectangle; 11] = [
+    Rectangle::new(40, 20),
+    Rectangle::new(30, 30),
+    Rectangle::new(20, 50),
+    Rectangle::new(25, 15),
+    Rectangle::new(60, 10),
+    Rectangle::new(15, 15),
+    Rectangle::new(35, 20),
+    Rectangle::new(10, 45),
+    Rectangle::new(0, 10),  // empty
+    Rectangle::new(120, 5), // wider than the sheet
+    Rectangle::new(20, 20),
+];
+
+fn main() {
+    println!("=== Labels on a sheet ===");
+    let sheet = Rectangle::at(10, 10, 100, 60);
+    println!("sheet {:?}, {} labels", sheet, LABELS.len());
+    for algorithm in ALGORITHMS {
+        for allow_rotation in [false, true] {
+            let report = pack(&sheet, &LABELS, Options { algorithm, allow_rotation });
+            println!(
+                "  {:<10} rotation {:<5}: {}",
+                format!("{:?}", algorithm),
+                allow_rotation,
+                report
+            );
+        }
+    }
+
+    let best = pack(&sheet, &LABELS, Options { algorithm: Algorithm::MaxRects, allow_rotation: true });
+    println!("\nMaxRects with rotation:");
+    for p in &best.placements {
+        let turned = if p.rotated { " (rotated)" } else { "" };
+        println!("  label {:>2} at ({}, {}) {}x{}{}", p.item, p.rect.x, p.rect.y, p.rect.width, p.rect.height, turned);
+    }
+    for u in &best.unplaced {
+        println!("  label {:>2} not placed: {:?}", u.item, u.reason);
+    }
+
+    println!("\n=== Edge cases ===");
+    // A perfect fit: four squares fill the container exactly
+    let square = Rectangle::at(-50, -50, 100, 100);
+    let quarters = [Rectangle::new(50, 50); 4];
+    for algorithm in ALGORITHMS {
+        let report = pack(&square, &quarters, Options { algorithm, allow_rotation: false });
+        println!("  four quarters, {:?}: {}", algorithm, report);
+    }
+
+    // A tall thin container only takes a wide item when rotation is allowed
+    let column = Rectangle::new(10, 100);
+    let plank = [Rectangle::new(100, 10)];
+    for allow_rotation in [false, true] {
+        let report = pack(&column, &plank, Options { algorithm: Algorithm::MaxRects, allow_rotation });
+        println!("  100x10 plank in a 10x100 column, rotation {:<5}: {}", allow_rotation, report);
+    }
+
+    // Near the edge of i32: positions past i32::MAX cannot be represented
+    let far = Rectangle::at(i32::MAX - 5, 0, 20, 10);
+    let far_items = [Rectangle::new(5, 10), Rectangle::new(5, 10), Rectangle::new(5, 10)];
+    let report = pack(&far, &far_items, Options { algorithm: Algorithm::Shelf, allow_rotation: false });
+    println!("  container at x = i32::MAX - 5: {}", report);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tiny deterministic random number generator (xorshift), no crates needed
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn range(&mut self, lo: u32, hi: u32) -> u32 {
+            lo + (self.next() % (hi - lo + 1) as u64) as u32
+        }
+    }
+
+    impl PackingReport {
+        // Check the report against the items it was made from
+        fn verify(&self, items: &[Rectangle]) -> Result<(), String> {
+            let mut seen = vec![false; items.len()];
+            let accounted = self.placements.iter().map(|p| p.item).chain(self.unplaced.iter().map(|u| u.item));
+            for item in accounted {
+                if std::mem::replace(&mut seen[item], true) {
+                    return Err(format!("item {} reported twice", item));
+                }
+            }
+            if let Some(missing) = seen.iter().position(|s| !s) {
+                return Err(format!("item {} is missing from the report", missing));
+            }
+
+            for (n, p) in self.placements.iter().enumerate() {
+                let item = items[p.item];
+                let size = if p.rotated { (item.height, item.width) } else { (item.width, item.height) };
+                if (p.rect.width, p.rect.height) != size {
+                    return Err(format!("item {} changed size: {:?}", p.item, p.rect));
+                }
+                if !self.container.contains_rect(&p.rect) {
+                    return Err(format!("item {} is out of bounds: {:?}", p.item, p.rect));
+                }
+                for q in &self.placements[n + 1..] {
+                    if let Some(overlap) = p.rect.intersection(&q.rect) {
+                        return Err(format!("items {} and {} overlap at {:?}", p.item, q.item, overlap));
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn every_labels_report_verifies() {
+        let sheet = Rectangle::at(10, 10, 100, 60);
+        for algorithm in ALGORITHMS {
+            for allow_rotation in [false, true] {
+                pack(&sheet, &LABELS, Options { algorithm, allow_rotation }).verify(&LABELS).unwrap();
+            }
+        }
+        let best = pack(&sheet, &LABELS, Options { algorithm: Algorithm::MaxRects, allow_rotation: true });
+        assert!(best.unplaced.contains(&Unplaced { item: 8, reason: UnplacedReason::Empty }));
+        assert!(best.unplaced.contains(&Unplaced { item: 9, reason: UnplacedReason::TooLarge }));
+    }
+
+    #[test]
+    fn four_quarters_fill_the_container() {
+        let square = Rectangle::at(-50, -50, 100, 100);
+        let quarters = [Rectangle::new(50, 50); 4];
+        for algorithm in ALGORITHMS {
+            let report = pack(&square, &quarters, Options { algorithm, allow_rotation: false });
+            report.verify(&quarters).unwrap();
+            assert_eq!(report.utilization(), 1.0);
+        }
+    }
+
+    #[test]
+    fn a_plank_fits_a_column_only_when_rotated() {
+        let column = Rectangle::new(10, 100);
+        let plank = [Rectangle::new(100, 10)];
+        for algorithm in ALGORITHMS {
+            let fixed = pack(&column, &plank, Options { algorithm, allow_rotation: false });
+            let turned = pack(&column, &plank, Options { algorithm, allow_rotation: true });
+            assert_eq!(fixed.unplaced[0].reason, UnplacedReason::TooLarge);
+            assert!(turned.placements[0].rotated);
+            turned.verify(&plank).unwrap();
+        }
+    }
+
+    #[test]
+    fn running_out_of_room_is_no_space() {
+        let full = pack(&Rectangle::new(10, 10), &[Rectangle::new(10, 10), Rectangle::new(5, 5)], Options {
+            algorithm: Algorithm::Guillotine,
+            allow_rotation: true,
+        });
+        assert_eq!(full.unplaced, vec![Unplaced { item: 1, reason: UnplacedReason::NoSpace }]);
+    }
+
+    #[test]
+    fn empty_containers_and_no_items() {
+        let none = pack(&Rectangle::new(0, 0), &[Rectangle::new(1, 1)], Options {
+            algorithm: Algorithm::MaxRects,
+            allow_rotation: false,
+        });
+        assert_eq!(none.utilization(), 0.0);
+        let sheet = Rectangle::at(10, 10, 100, 60);
+        assert!(pack(&sheet, &[], Options { algorithm: Algorithm::Shelf, allow_rotation: true }).placements.is_empty());
+    }
+
+    #[test]
+    fn positions_past_i32_max_are_never_used() {
+        let far = Rectangle::at(i32::MAX - 5, 0, 20, 10);
+        let far_items = [Rectangle::new(5, 10), Rectangle::new(5, 10), Rectangle::new(5, 10)];
+        let report = pack(&far, &far_items, Options { algorithm: Algorithm::Shelf, allow_rotation: false });
+        report.verify(&far_items).unwrap();
+        assert_eq!(report.unplaced.len(), 1);
+        // The unreachable spot on the right must not use up room that IS
+        // reachable: the second item goes below the first instead
+        let far = Rectangle::at(i32::MAX - 5, 0, 20, 20);
+        let far_items = [Rectangle::new(12, 10), Rectangle::new(8, 10), Rectangle::new(8, 10)];
+        for algorithm in ALGORITHMS {
+            let report = pack(&far, &far_items, Options { algorithm, allow_rotation: false });
+            report.verify(&far_items).unwrap();
+            assert_eq!(report.unplaced, vec![Unplaced { item: 2, reason: UnplacedReason::NoSpace }], "{:?}", algorithm);
+            assert_eq!(report.placements[1].rect, Rectangle::at(i32::MAX - 5, 10, 8, 10));
+        }
+    }
+
+    #[test]
+    fn random_layouts_never_overlap_or_leave_the_container() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for _ in 0..300 {
+            let container = Rectangle::at(rng.range(0, 50) as i32 - 25, 0, rng.range(1, 200), rng.range(1, 200));
+            let items: Vec<Rectangle> = (0..rng.range(0, 40))
+                .map(|_| Rectangle::new(rng.range(0, 80), rng.range(0, 80)))
+                .collect();
+            for algorithm in ALGORITHMS {
+                for allow_rotation in [false, true] {
+                    let report = pack(&container, &items, Options { algorithm, allow_rotation });
+                    if let Err(e) = report.verify(&items) {
+                        panic!("{:?} (rotation {}) on {:?}: {}", algorithm, allow_rotation, container, e);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,630 @@
+// cargo run --example m5_methods_svg
+// cargo run --example m5_methods_svg > shapes.svg   (then open it in a browser)
+//
+// Demonstrates exporting shapes to an SVG document.
+//
+//   Rectangle   (m5_methods)        -> <rect>
+//   Point<f64>  (m5_generics)       -> <circle>
+//   Color       (m5_tuple_structs)  -> fill="#ff0000" / stroke="..."
+//   labels                          -> <text>
+//
+// The viewBox is computed from the shapes' bounds, so the picture always
+// fits. Text is XML-escaped: a label like `Tom & Jerry <3` would otherwise
+// break the document. To check the output, a small XML reader parses it
+// back and we look at the elements it finds.
+//
+// cargo test --example m5_methods_svg parses the output back and checks it.
+
+use std::fmt::{self, Write};
+
+// ---------------------------------------------------------------------
+// Shapes and colors from earlier examples
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rectangle {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl Rectangle {
+    fn at(x: i32, y: i32, width: u32, height: u32) -> Rectangle {
+        Rectangle { x, y, width, height }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Point<T> {
+    x: T,
+    y: T,
+}
+
+// RGB color from m5_tuple_structs
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Color(i32, i32, i32);
+
+impl Color {
+    // "#rrggbb"; the fields are i32, so out-of-range values are clamped
+    fn to_hex(self) -> String {
+        let channel = |c: i32| c.clamp(0, 255);
+        format!("#{:02x}{:02x}{:02x}", channel(self.0), channel(self.1), channel(self.2))
+    }
+}
+
+// ---------------------------------------------------------------------
+// Styles and the document
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+struct Style {
+    fill: Option<Color>, // None: transparent inside
+    stroke: Option<Color>,
+    stroke_width: f64,
+}
+
+impl Style {
+    fn filled(color: Color) -> Style {
+        Style { fill: Some(color), stroke: None, stroke_width: 0.0 }
+    }
+
+    fn outlined(color: Color, width: f64) -> Style {
+        Style { fill: None, stroke: Some(color), stroke_width: width }
+    }
+
+    fn stroke(mut self, color: Color, width: f64) -> Style {
+        self.stroke = Some(color);
+        self.stroke_width = width;
+        self
+    }
+
+    // The width actually drawn: NaN, infinite or negative widths become 0,
+    // so they can neither reach the output nor stretch the viewBox
+    fn drawn_stroke_width(&self) -> f64 {
+        if self.stroke_width.is_finite() && self.stroke_width > 0.0 {
+            self.stroke_width
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Shape {
+    Rect(Rectangle),
+    Point(Point<f64>),
+    Text(Point<f64>),
+}
+
+#[derive(Debug, Clone)]
+struct Item {
+    shape: Shape,
+    style: Style,
+    label: Option<String>,
+}
+
+struct SvgDocument {
+    title: String,
+    padding: f64,
+    point_radius: f64,
+    items: Vec<Item>,
+}
+
+// Bounds as (min x, min y, max x, max y)
+type Bounds = (f64, f64, f64, f64);
+
+// Replace the five characters XML gives special meaning. Control
+// characters other than tab, LF and CR are not allowed in XML at all, not
+// even as &#1; references, so they become U+FFFD (the replacement character).
+// The noncharacters U+FFFE and U+FFFF are outside XML's character range
+// too and are replaced the same way.
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            '\u{0}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => out.push(char::REPLACEMENT_CHARACTER),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+impl SvgDocument {
+    fn new(title: &str) -> Self {
+        SvgDocument { title: title.to_string(), padding: 10.0, point_radius: 3.0, items: Vec::new() }
+    }
+
+    fn add(&mut self, shape: Shape, style: Style, label: Option<&str>) -> &mut Self {
+        self.items.push(Item { shape, style, label: label.map(str::to_string) });
+        self
+    }
+
+    fn rect(&mut self, rect: Rectangle, style: Style) -> &mut Self {
+        self.add(Shape::Rect(rect), style, None)
+    }
+
+    fn labelled_rect(&mut self, rect: Rectangle, style: Style, label: &str) -> &mut Self {
+        self.add(Shape::Rect(rect), style, Some(label))
+    }
+
+    // Points with a NaN or infinite coordinate cannot be drawn and are skipped
+    fn point(&mut self, p: Point<f64>, style: Style, label: Option<&str>) -> &mut Self {
+        if p.x.is_finite() && p.y.is_finite() {
+            self.add(Shape::Point(p), style, label);
+        }
+        self
+    }
+
+    // Like points, text at a non-finite position is skipped
+    fn text(&mut self, at: Point<f64>, text: &str, color: Color) -> &mut Self {
+        if at.x.is_finite() && at.y.is_finite() {
+            self.add(Shape::Text(at), Style::filled(color), Some(text));
+        }
+        self
+    }
+
+    // The area covered by one item, including half its stroke
+    fn item_bounds(&self, item: &Item) -> Bounds {
+        let half_stroke = if item.style.stroke.is_some() { item.style.drawn_stroke_width() / 2.0 } else { 0.0 };
+        let (x0, y0, x1, y1) = match item.shape {
+            Shape::Rect(r) => {
+                (r.x as f64, r.y as f64, r.x as f64 + r.width as f64, r.y as f64 + r.height as f64)
+            }
+            Shape::Point(p) => {
+                let r = self.point_radius;
+                (p.x - r, p.y - r, p.x + r, p.y + r)
+            }
+            // Text size depends on the font; just keep its anchor in view
+            Shape::Text(p) => (p.x, p.y, p.x, p.y),
+        };
+        (x0 - half_stroke, y0 - half_stroke, x1 + half_stroke, y1 + half_stroke)
+    }
+
+    // "min-x min-y width height" around everything, plus padding.
+    // Finite shapes near f64::MAX can still overflow to infinity once
+    // strokes, padding or the width are added, so every number is clamped
+    // to the finite range.
+    fn view_box(&self) -> (f64, f64, f64, f64) {
+        let bounds = self.items.iter().map(|item| self.item_bounds(item)).reduce(|a, b| {
+            (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))
+        });
+        match bounds {
+            Some((x0, y0, x1, y1)) => {
+                let finite = |v: f64| v.clamp(-f64::MAX, f64::MAX);
+                let (x0, y0, x1, y1) = (finite(x0), finite(y0), finite(x1), finite(y1));
+                let p = self.padding;
+                (finite(x0 - p), finite(y0 - p), finite(x1 - x0 + 2.0 * p), finite(y1 - y0 + 2.0 * p))
+            }
+            None => (0.0, 0.0, 1.0, 1.0), // an empty picture still needs a size
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::new();
+        // Writing to a String cannot fail, hence the unwrap
+        self.write_to(&mut out).unwrap();
+        out
+    }
+
+    fn write_to(&self, out: &mut impl Write) -> fmt::Result {
+        let (vx, vy, vw, vh) = self.view_box();
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(out, r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#, vx, vy, vw, vh)?;
+        writeln!(out, "  <title>{}</title>", escape_xml(&self.title))?;
+
+        for item in &self.items {
+            let style = &item.style;
+            let mut paint = format!(r#"fill="{}""#, style.fill.map_or(String::from("none"), Color::to_hex));
+            if let Some(stroke) = style.stroke {
+                write!(paint, r#" stroke="{}" stroke-width="{}""#, stroke.to_hex(), style.drawn_stroke_width())?;
+            }
+
+            // Where the label goes: centered on rectangles, beside points
+            let label_at = match item.shape {
+                Shape::Rect(r) => {
+                    writeln!(
+                        out,
+                        r#"  <rect x="{}" y="{}" width="{}" height="{}" {}/>"#,
+                        r.x, r.y, r.width, r.height, paint
+                    )?;
+                    (r.x as f64 + r.width as f64 / 2.0, r.y as f64 + r.height as f64 / 2.0, "middle")
+                }
+                Shape::Point(p) => {
+                    writeln!(out, r#"  <circle cx="{}" cy="{}" r="{}" {}/>"#, p.x, p.y, self.point_radius, paint)?;
+                    (p.x + self.point_radius * 2.0, p.y, "start")
+                }
+                Shape::Text(p) => (p.x, p.y, "start"),
+            };
+
+            if let Some(label) = &item.label {
+                // Free text uses its own fill color; labels on shapes are black
+                let color = match item.shape {
+                    Shape::Text(_) => style.fill.unwrap_or(Color(0, 0, 0)),
+                    _ => Color(0, 0, 0),
+                };
+                writeln!(
+                    out,
+                    r#"  <text x="{}" y="{}" text-anchor="{}" dominant-baseline="middle" fill="{}">{}</text>"#,
+                    label_at.0,
+                    label_at.1,
+                    label_at.2,
+                    color.to_hex(),
+                    escape_xml(label)
+                )?;
+            }
+        }
+        writeln!(out, "</svg>")
+    }
+}
+
+// ---------------------------------------------------------------------
+// A small XML reader, enough to check our own output
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+impl Element {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    fn elements<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter_map(move |child| match child {
+            Node::Element(e) if e.name == name => Some(e),
+            _ => None,
+        })
+    }
+
+    fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|child| match child {
+                Node::Text(t) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+// What went wrong and the byte offset where it happened
+#[derive(Debug, PartialEq)]
+struct XmlError {
+    offset: usize,
+    message: String,
+}
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "at byte {}: {}", self.offset, self.message)
+    }
+}
+
+impl std::error::Error for XmlError {}
+
+struct XmlReader<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> XmlReader<'a> {
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, XmlError> {
+        Err(XmlError { offset: self.pos, message: message.into() })
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.pos = self.input.len() - trimmed.len();
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), XmlError> {
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            Ok(())
+        } else {
+            self.error(format!("expected {:?}", token))
+        }
+    }
+
+    fn name(&mut self) -> Result<String, XmlError> {
+        let len = self
+            .rest()
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '.')))
+            .unwrap_or(self.rest().len());
+        if len == 0 {
+            return self.error("expected a name");
+        }
+        let name = self.rest()[..len].to_string();
+        self.pos += len;
+        Ok(name)
+    }
+
+    // Turn &amp; &lt; &#65; ... back into characters
+    fn unescape(&self, raw: &str, start: usize) -> Result<String, XmlError> {
+        let mut out = String::with_capacity(raw.len());
+        let mut rest = raw;
+        while let Some(amp) = rest.find('&') {
+            out.push_str(&rest[..amp]);
+            let offset = start + (raw.len() - rest.len()) + amp;
+            let Some(semi) = rest[amp..].find(';') else {
+                return Err(XmlError { offset, message: String::from("unterminated entity") });
+            };
+            let entity = &rest[amp + 1..amp + semi];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => entity
+                    .strip_prefix("#x")
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(str::parse))
+                    .and_then(Result::ok)
+                    .and_then(char::from_u32),
+            };
+            match c {
+                Some(c) => out.push(c),
+                None => {
+                    return Err(XmlError { offset, message: format!("unknown entity &{};", entity) });
+                }
+            }
+            rest = &rest[amp + semi + 1..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    // An element, starting at its '<'
+    fn element(&mut self) -> Result<Element, XmlError> {
+        self.expect("<")?;
+        let name = self.name()?;
+        let mut attributes: Vec<(String, String)> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+                return Ok(Element { name, attributes, children: Vec::new() });
+            }
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let attr = self.name()?;
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            self.expect("\"")?;
+            let Some(len) = self.rest().find('"') else {
+                return self.error("unterminated attribute value");
+            };
+            let raw = &self.rest()[..len];
+            if raw.contains('<') {
+                return self.error("'<' is not allowed in an attribute value");
+            }
+            let value = self.unescape(raw, self.pos)?;
+            self.pos += len + 1;
+            if attributes.iter().any(|(n, _)| *n == attr) {
+                return self.error(format!("duplicate attribute '{}'", attr));
+            }
+            attributes.push((attr, value));
+        }
+
+        let mut children = Vec::new();
+        loop {
+            if self.rest().starts_with("</") {
+                self.pos += 2;
+                let close = self.name()?;
+                if close != name {
+                    return self.error(format!("</{}> does not close <{}>", close, name));
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+                return Ok(Element { name, attributes, children });
+            }
+            if self.rest().starts_with('<') {
+                children.push(Node::Element(self.element()?));
+                continue;
+            }
+            let Some(len) = self.rest().find('<') else {
+                return self.error(format!("<{}> is never closed", name));
+            };
+            let raw = &self.rest()[..len];
+            if raw.contains('>') {
+                return self.error("unescaped '>' in text");
+            }
+            let text = self.unescape(raw, self.pos)?;
+            self.pos += len;
+            // Indentation between elements is not content
+            if !text.trim().is_empty() {
+                children.push(Node::Text(text));
+            }
+        }
+    }
+}
+
+// Parse a whole document: optional declaration, then one root element
+fn parse_xml(input: &str) -> Result<Element, XmlError> {
+    let mut reader = XmlReader { input, pos: 0 };
+    reader.skip_whitespace();
+    if reader.rest().starts_with("<?xml") {
+        match reader.rest().find("?>") {
+            Some(end) => reader.pos += end + 2,
+            None => return reader.error("unterminated XML declaration"),
+        }
+    }
+    reader.skip_whitespace();
+    let root = reader.element()?;
+    reader.skip_whitespace();
+    if !reader.rest().is_empty() {
+        return reader.error("content after the root element");
+    }
+    Ok(root)
+}
+
+// The picture main prints and the tests parse back
+fn sample_document() -> SvgDocument {
+    let red = Color(255, 0, 0);
+    let navy = Color(0, 0, 128);
+    let gray = Color(200, 200, 200);
+
+    let mut doc = SvgDocument::new("Module 5 shapes: rectangles & points");
+    doc.labelled_rect(Rectangle::at(0, 0, 30, 50), Style::filled(gray).stroke(navy, 2.0), "30 x 50")
+        .rect(Rectangle::at(40, 10, 20, 20), Style::filled(red))
+        .labelled_rect(Rectangle::at(-20, 60, 40, 10), Style::outlined(navy, 1.0), "Tom & Jerry <3")
+        .point(Point { x: 70.0, y: 40.5 }, Style::filled(navy), Some("\"origin\" isn't here"))
+        .point(Point { x: f64::NAN, y: 0.0 }, Style::filled(navy), None) // skipped
+        .text(Point { x: 45.0, y: 0.0 }, "<title> & friends", Color(300, -5, 128));
+    doc
+}
+
+// Broken documents the reader rejects, which is why escaping matters
+const BROKEN: [&str; 6] = [
+    r#"<svg><text>Tom & Jerry</text></svg>"#,
+    r#"<svg><text>a < b</text></svg>"#,
+    r#"<svg><rect x="1" x="2"/></svg>"#,
+    r#"<svg><g></svg>"#,
+    r#"<svg>"#,
+    r#"<svg/><svg/>"#,
+];
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let svg = sample_document().render();
+    println!("{}", svg);
+
+    // ---- Parse it back and see what we find ----
+    let root = parse_xml(&svg)?;
+    let labels: Vec<String> = root.elements("text").map(Element::text).collect();
+    println!(
+        "Parsed back: {} rects, {} circles, labels {:?}, viewBox {:?}",
+        root.elements("rect").count(),
+        root.elements("circle").count(),
+        labels,
+        root.attr("viewBox").unwrap_or("")
+    );
+
+    println!("\nRejected by the reader:");
+    for input in BROKEN {
+        match parse_xml(input) {
+            Ok(_) => println!("  {:<36} (accepted?)", input),
+            Err(err) => println!("  {:<36} {}", input, err),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_sample_parses_back() {
+        let root = parse_xml(&sample_document().render()).unwrap();
+        assert_eq!(root.name, "svg");
+        assert_eq!(root.attr("xmlns"), Some("http://www.w3.org/2000/svg"));
+        assert_eq!(root.elements("title").next().unwrap().text(), "Module 5 shapes: rectangles & points");
+
+        // viewBox bounds, before 10 units of padding on each side:
+        //   left   -20.5  the outlined rect at x = -20, minus half its 1px stroke
+        //   top     -1    the first rect at y = 0, minus half its 2px stroke
+        //   right   73    the circle at x = 70 plus its radius of 3
+        //   bottom  70.5  the outlined rect's bottom edge at 70, plus half its stroke
+        let view_box: Vec<f64> = root.attr("viewBox").unwrap().split(' ').map(|v| v.parse().unwrap()).collect();
+        assert_eq!(view_box, vec![-20.5 - 10.0, -1.0 - 10.0, 73.0 + 20.5 + 20.0, 70.5 + 1.0 + 20.0]);
+
+        let rects: Vec<&Element> = root.elements("rect").collect();
+        assert_eq!(rects.len(), 3);
+        assert_eq!(rects[0].attr("fill"), Some("#c8c8c8"));
+        assert_eq!(rects[0].attr("stroke"), Some("#000080"));
+        assert_eq!(rects[1].attr("fill"), Some("#ff0000"));
+        assert_eq!(rects[1].attr("stroke"), None);
+        assert_eq!(rects[2].attr("fill"), Some("none"));
+        assert_eq!(rects[2].attr("x"), Some("-20"));
+
+        let circles: Vec<&Element> = root.elements("circle").collect();
+        assert_eq!(circles.len(), 1, "the NaN point is skipped");
+        assert_eq!(circles[0].attr("cy"), Some("40.5"));
+
+        // Labels survive escaping and come back exactly
+        let labels: Vec<String> = root.elements("text").map(Element::text).collect();
+        assert_eq!(labels, ["30 x 50", "Tom & Jerry <3", "\"origin\" isn't here", "<title> & friends"]);
+        let free_text = root.elements("text").last().unwrap();
+        assert_eq!(free_text.attr("fill"), Some("#ff0080")); // 300 and -5 clamped
+    }
+
+    #[test]
+    fn an_empty_document_is_still_valid() {
+        let empty = parse_xml(&SvgDocument::new("").render()).unwrap();
+        assert_eq!(empty.attr("viewBox"), Some("0 0 1 1"));
+    }
+
+    #[test]
+    fn the_reader_rejects_broken_xml() {
+        for input in BROKEN {
+            assert!(parse_xml(input).is_err(), "{}", input);
+        }
+        // Numeric character references are understood too
+        assert_eq!(parse_xml("<t>&#65;&#x42;&lt;</t>").unwrap().text(), "AB<");
+    }
+
+    #[test]
+    fn input_the_document_cannot_represent_as_is() {
+        let navy = Color(0, 0, 128);
+        let mut odd = SvgDocument::new("bell\u{7} tab\t");
+        odd.text(Point { x: f64::INFINITY, y: 0.0 }, "lost", navy) // skipped
+            .rect(Rectangle::at(0, 0, 10, 10), Style::outlined(navy, f64::NAN))
+            .rect(Rectangle::at(0, 0, 10, 10), Style::outlined(navy, -4.0));
+        let odd = parse_xml(&odd.render()).unwrap();
+        assert_eq!(odd.elements("title").next().unwrap().text(), "bell\u{fffd} tab\t");
+        assert_eq!(odd.elements("text").count(), 0);
+        let widths: Vec<&str> = odd.elements("rect").filter_map(|r| r.attr("stroke-width")).collect();
+        assert_eq!(widths, ["0", "0"]);
+        assert_eq!(odd.attr("viewBox"), Some("-10 -10 30 30"));
+    }
+
+    #[test]
+    fn noncharacters_are_replaced_like_control_characters() {
+        assert_eq!(escape_xml("a\u{fffe}b\u{ffff}c\u{1}"), "a\u{fffd}b\u{fffd}c\u{fffd}");
+        // Their neighbours are ordinary characters and stay
+        assert_eq!(escape_xml("\u{fffd}\u{fffc}\u{10000}"), "\u{fffd}\u{fffc}\u{10000}");
+        let mut doc = SvgDocument::new("title");
+        doc.text(Point { x: 0.0, y: 0.0 }, "x\u{ffff}", Color(0, 0, 0));
+        let root = parse_xml(&doc.render()).unwrap();
+        assert_eq!(root.elements("text").next().unwrap().text(), "x\u{fffd}");
+    }
+
+    #[test]
+    fn huge_finite_shapes_keep_a_finite_view_box() {
+        let navy = Color(0, 0, 128);
+        let mut doc = SvgDocument::new("far apart");
+        doc.point(Point { x: -f64::MAX, y: -f64::MAX }, Style::filled(navy).stroke(navy, f64::MAX), None)
+            .point(Point { x: f64::MAX, y: f64::MAX }, Style::filled(navy), None);
+        let (x, y, w, h) = doc.view_box();
+        assert!([x, y, w, h].iter().all(|v| v.is_finite()), "{:?}", (x, y, w, h));
+        assert_eq!((x, w), (-f64::MAX, f64::MAX));
+        // ...and the document still parses
+        let root = parse_xml(&doc.render()).unwrap();
+        assert_eq!(root.elements("circle").count(), 2);
+    }
+}
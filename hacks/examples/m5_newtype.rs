@@ -3,12 +3,24 @@
 // Demonstrates the Newtype Pattern - wrapping a type to give it new meaning.
 // This provides type safety by making the compiler distinguish between
 // values that would otherwise have the same underlying type.
+//
+// This file stays the simple version on purpose: plain wrapper structs
+// with hand-written methods, and no Quantity types. Those come later:
+// m5_newtype_quantity has units the compiler can combine (meters / seconds
+// = a velocity, meters x meters = an area), and m5_newtype_units has mass,
+// volume, angle, data size and temperature (where only differences can be
+// added).
+//
+// The mistakes commented out below really do not compile:
+// cargo test --test newtype checks each one with trybuild.
 
 // Newtype: wrap f64 to represent specific units
 // These are distinct types even though they both wrap f64
 struct Meters(f64);
 struct Feet(f64);
 struct Seconds(f64);
+// Meters times meters is an area, not more meters
+struct SquareMeters(f64);
 
 impl Meters {
     fn new(value: f64) -> Self {
@@ -48,9 +60,16 @@ impl Seconds {
     }
 }
 
+impl SquareMeters {
+    fn value(&self) -> f64 {
+        self.0
+    }
+}
+
 // Function that only accepts Meters - type safe!
-fn calculate_area_meters(length: &Meters, width: &Meters) -> f64 {
-    length.value() * width.value()
+// The result is typed too, so an area can't be passed on as a length.
+fn calculate_area_meters(length: &Meters, width: &Meters) -> SquareMeters {
+    SquareMeters(length.value() * width.value())
 }
 
 // This prevents accidents like passing Feet where Meters expected
@@ -79,7 +98,9 @@ fn main() {
     let length = Meters::new(10.0);
     let width = Meters::new(5.0);
     let area = calculate_area_meters(&length, &width);
-    println!("  {}m x {}m = {} square meters", length.value(), width.value(), area);
+    println!("  {}m x {}m = {} square meters", length.value(), width.value(), area.value());
+    assert_eq!(area.value(), 50.0);
+    // calculate_area_meters(&area, &width);  // Won't compile! An area is not Meters.
 
     // Can't mix types accidentally
     let time = Seconds::new(60.0);
@@ -0,0 +1,498 @@
+// cargo run --example m5_newtype_parse
+//
+// Demonstrates PARSING and FORMATTING the m5_newtype unit types:
+//
+//   "10 ft".parse::<Feet>()        -> Feet(10.0)
+//   "1.5 km".parse::<Meters>()     -> Meters(1500.0)   (converted)
+//   "2h".parse::<Seconds>()        -> Seconds(7200.0)
+//   "90s".parse::<Meters>()        -> Err(IncompatibleUnit: s is a time)
+//
+// A UnitRegistry knows every unit's symbol, its spelled-out names
+// ("meters", "metre", "secs"...), its kind (length or time) and its size
+// in base units. SI units also accept prefixes: km, mm, µs, kilometers.
+//
+// Formatting is the reverse, with an option to pick the most readable
+// unit automatically: Meters(1500.0) -> "1.5 km".
+
+use std::fmt::{self, Write};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+// The newtypes from m5_newtype
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+struct Meters(f64);
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+struct Feet(f64);
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+struct Seconds(f64);
+
+// What a unit measures. Only units of the same kind convert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Length,
+    Time,
+}
+
+// ---------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum UnitError {
+    Empty,
+    InvalidNumber(String),
+    OutOfRange(String), // finite, but not once converted ("1e308 km" in meters)
+    MissingUnit,
+    UnknownUnit(String),
+    IncompatibleUnit { unit: String, expected: Kind, found: Kind },
+}
+
+impl fmt::Display for UnitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnitError::Empty => write!(f, "empty input"),
+            UnitError::InvalidNumber(text) => write!(f, "{:?} is not a number", text),
+            UnitError::OutOfRange(text) => write!(f, "{:?} is too large to convert", text),
+            UnitError::MissingUnit => write!(f, "missing unit"),
+            UnitError::UnknownUnit(unit) => write!(f, "unknown unit {:?}", unit),
+            UnitError::IncompatibleUnit { unit, expected, found } => {
+                write!(f, "{:?} measures {:?}, expected {:?}", unit, found, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for UnitError {}
+
+// ---------------------------------------------------------------------
+// The registry
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+struct UnitDef {
+    symbol: String,
+    names: Vec<String>, // spelled-out names and other aliases
+    kind: Kind,
+    to_base: f64, // size in base units (meters or seconds)
+    prefixable: bool,
+}
+
+// A unit after prefixes are applied, e.g. "km" = 1000 m
+#[derive(Debug, Clone, PartialEq)]
+struct Resolved {
+    symbol: String,
+    kind: Kind,
+    to_base: f64,
+}
+
+struct Prefix {
+    symbols: &'static [&'static str], // "u" is an ASCII stand-in for "µ"
+    name: &'static str,
+    factor: f64,
+}
+
+const PREFIXES: &[Prefix] = &[
+    Prefix { symbols: &["n"], name: "nano", factor: 1e-9 },
+    Prefix { symbols: &["µ", "μ", "u"], name: "micro", factor: 1e-6 },
+    Prefix { symbols: &["m"], name: "milli", factor: 1e-3 },
+    Prefix { symbols: &["c"], name: "centi", factor: 1e-2 },
+    Prefix { symbols: &["k"], name: "kilo", factor: 1e3 },
+    Prefix { symbols: &["M"], name: "mega", factor: 1e6 },
+];
+
+struct UnitRegistry {
+    units: Vec<UnitDef>,
+}
+
+impl UnitRegistry {
+    fn new() -> Self {
+        UnitRegistry { units: Vec::new() }
+    }
+
+    // Add a unit; `names` are matched case-insensitively, symbols exactly
+    fn register(&mut self, symbol: &str, names: &[&str], kind: Kind, to_base: f64, prefixable: bool) -> &mut Self {
+        self.units.push(UnitDef {
+            symbol: symbol.to_string(),
+            names: names.iter().map(|n| n.to_lowercase()).collect(),
+            kind,
+            to_base,
+            prefixable,
+        });
+        self
+    }
+
+    fn standard() -> Self {
+        let mut registry = UnitRegistry::new();
+        registry
+            .register("m", &["meter", "meters", "metre", "metres"], Kind::Length, 1.0, true)
+            .register("in", &["inch", "inches", "\""], Kind::Length, 0.0254, false)
+            .register("ft", &["foot", "feet", "'"], Kind::Length, 0.3048, false)
+            .register("yd", &["yard", "yards"], Kind::Length, 0.9144, false)
+            .register("mi", &["mile", "miles"], Kind::Length, 1609.344, false)
+            .register("s", &["sec", "secs", "second", "seconds"], Kind::Time, 1.0, true)
+            .register("min", &["minute", "minutes", "mins"], Kind::Time, 60.0, false)
+            .register("h", &["hr", "hrs", "hour", "hours"], Kind::Time, 3600.0, false)
+            .register("d", &["day", "days"], Kind::Time, 86400.0, false);
+        registry
+    }
+
+    // Find a unit by symbol, name, or prefix + either of those.
+    // Exact matches win, so "min" is minutes and "m" is meters, not milli-.
+    fn lookup(&self, text: &str) -> Option<Resolved> {
+        let lower = text.to_lowercase();
+        let exact = |def: &UnitDef| def.symbol == text || def.names.contains(&lower);
+        if let Some(def) = self.units.iter().find(|d| exact(d)) {
+            return Some(Resolved { symbol: def.symbol.clone(), kind: def.kind, to_base: def.to_base });
+        }
+
+        for prefix in PREFIXES {
+            for def in self.units.iter().filter(|d| d.prefixable) {
+                // Symbol form: "km", "µs" - case matters ("Mm" is not "mm")
+                for p in prefix.symbols {
+                    if text.strip_prefix(p) == Some(def.symbol.as_str()) {
+                        return Some(Resolved {
+                            symbol: format!("{}{}", prefix.symbols[0], def.symbol),
+                            kind: def.kind,
+                            to_base: prefix.factor * def.to_base,
+                        });
+                    }
+                }
+                // Name form: "kilometers", "Milliseconds"
+                if let Some(rest) = lower.strip_prefix(prefix.name) {
+                    if def.names.iter().any(|n| n == rest) {
+                        return Some(Resolved {
+                            symbol: format!("{}{}", prefix.symbols[0], def.symbol),
+                            kind: def.kind,
+                            to_base: prefix.factor * def.to_base,
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // "3.2m", "10 ft", " 1.5e3  mm " -> (number, unit)
+    // Numbers too large for an f64 ("1e999") are rejected, not read as inf
+    fn parse(&self, text: &str) -> Result<(f64, Resolved), UnitError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(UnitError::Empty);
+        }
+        let split = number_len(text);
+        let (number, unit) = (&text[..split], text[split..].trim_start());
+        let value = number
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite())
+            .ok_or_else(|| UnitError::InvalidNumber(number.to_string()))?;
+        if unit.is_empty() {
+            return Err(UnitError::MissingUnit);
+        }
+        let resolved = self.lookup(unit).ok_or_else(|| UnitError::UnknownUnit(unit.to_string()))?;
+        Ok((value, resolved))
+    }
+
+    // Parse and convert into one of the newtypes
+    fn parse_as<T: UnitValue>(&self, text: &str) -> Result<T, UnitError> {
+        let (value, unit) = self.parse(text)?;
+        // A registry built by hand may lack the newtype's own unit
+        let target = self.lookup(T::SYMBOL).ok_or_else(|| UnitError::UnknownUnit(T::SYMBOL.to_string()))?;
+        if unit.kind != target.kind {
+            return Err(UnitError::IncompatibleUnit { unit: unit.symbol, expected: target.kind, found: unit.kind });
+        }
+        let converted = value * unit.to_base / target.to_base;
+        if !converted.is_finite() {
+            return Err(UnitError::OutOfRange(text.trim().to_string()));
+        }
+        Ok(T::from_value(converted))
+    }
+
+    // Write a value in a chosen unit, or the most readable one
+    fn format<T: UnitValue>(&self, quantity: &T, options: &FormatOptions) -> Result<String, UnitError> {
+        let own = self.lookup(T::SYMBOL).ok_or_else(|| UnitError::UnknownUnit(T::SYMBOL.to_string()))?;
+        let base = quantity.value() * own.to_base;
+
+        let target = match &options.unit {
+            UnitChoice::Own => own,
+            UnitChoice::Fixed(symbol) => {
+                let unit = self.lookup(symbol).ok_or_else(|| UnitError::UnknownUnit(symbol.clone()))?;
+                if unit.kind != own.kind {
+                    return Err(UnitError::IncompatibleUnit { unit: unit.symbol, expected: own.kind, found: unit.kind });
+                }
+                unit
+            }
+            // The largest unit on the ladder in which the value is at least 1.
+            // NaN and infinity have no readable unit and keep their own.
+            UnitChoice::Auto if !base.is_finite() => own,
+            UnitChoice::Auto => {
+                let ladder: Vec<Resolved> = T::LADDER.iter().filter_map(|s| self.lookup(s)).collect();
+                let fits = ladder.iter().rev().find(|u| base.abs() >= u.to_base);
+                match (base, fits) {
+                    (0.0, _) => own,
+                    (_, Some(unit)) => unit.clone(),
+                    (_, None) => ladder.first().cloned().unwrap_or(own), // smaller than everything
+                }
+            }
+        };
+        let value = base / target.to_base;
+        Ok(match options.precision {
+            Some(p) => format!("{:.*} {}", p, value, target.symbol),
+            None => format!("{} {}", tidy(value), target.symbol),
+        })
+    }
+}
+
+// Unit conversion leaves noise like 4.199999999999999; 12 significant
+// digits is plenty for display and hides it
+fn tidy(value: f64) -> f64 {
+    format!("{:.11e}", value).parse().unwrap_or(value)
+}
+
+// Length of the leading number: sign, digits, '.', and an exponent
+// like "e-3" (only when digits follow, so "2 em" stays "2" + "em")
+fn number_len(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    if matches!(bytes.first(), Some(b'+' | b'-')) {
+        i += 1;
+    }
+    while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+        i += 1;
+    }
+    if i < bytes.len() && matches!(bytes[i], b'e' | b'E') {
+        let mut j = i + 1;
+        if matches!(bytes.get(j), Some(b'+' | b'-')) {
+            j += 1;
+        }
+        if bytes.get(j).is_some_and(u8::is_ascii_digit) {
+            i = j;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+        }
+    }
+    i
+}
+
+// ---------------------------------------------------------------------
+// Formatting options
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+enum UnitChoice {
+    Own,           // the newtype's own unit
+    Fixed(String), // any unit of the same kind
+    Auto,          // the most readable unit
+}
+
+#[derive(Debug, Clone)]
+struct FormatOptions {
+    unit: UnitChoice,
+    precision: Option<usize>,
+}
+
+impl FormatOptions {
+    fn auto() -> Self {
+        FormatOptions { unit: UnitChoice::Auto, precision: None }
+    }
+
+    fn unit(symbol: &str) -> Self {
+        FormatOptions { unit: UnitChoice::Fixed(symbol.to_string()), precision: None }
+    }
+
+    fn precision(mut self, digits: usize) -> Self {
+        self.precision = Some(digits);
+        self
+    }
+}
+
+// ---------------------------------------------------------------------
+// Connecting the newtypes
+// ---------------------------------------------------------------------
+
+// What parse_as and format need to know about a newtype
+trait UnitValue: Sized {
+    const SYMBOL: &'static str;
+    // Candidates for UnitChoice::Auto, smallest first
+    const LADDER: &'static [&'static str];
+    fn from_value(value: f64) -> Self;
+    fn value(&self) -> f64;
+}
+
+// Width, fill and alignment for an already formatted value. f.pad does
+// this too, but it would also cut the text down to the precision.
+fn pad(f: &mut fmt::Formatter, text: &str) -> fmt::Result {
+    let gap = f.width().unwrap_or(0).saturating_sub(text.chars().count());
+    let (before, after) = match f.align() {
+        Some(fmt::Alignment::Right) => (gap, 0),
+        Some(fmt::Alignment::Center) => (gap / 2, gap - gap / 2),
+        Some(fmt::Alignment::Left) | None => (0, gap),
+    };
+    let fill = f.fill();
+    for _ in 0..before {
+        f.write_char(fill)?;
+    }
+    f.write_str(text)?;
+    for _ in 0..after {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+fn registry() -> &'static UnitRegistry {
+    static REGISTRY: OnceLock<UnitRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(UnitRegistry::standard)
+}
+
+macro_rules! unit_value {
+    ($Type:ident, $symbol:expr, [$($ladder:expr),*]) => {
+        impl UnitValue for $Type {
+            const SYMBOL: &'static str = $symbol;
+            const LADDER: &'static [&'static str] = &[$($ladder),*];
+            fn from_value(value: f64) -> Self {
+                $Type(value)
+            }
+            fn value(&self) -> f64 {
+                self.0
+            }
+        }
+
+        // "1.5 km".parse::<Meters>()
+        impl FromStr for $Type {
+            type Err = UnitError;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                registry().parse_as(s)
+            }
+        }
+
+        // "{}" uses the own unit; "{:.2}" passes the precision through,
+        // and "{:>10}" or "{:*<8}" pad the whole "1.5 m"
+        impl fmt::Display for $Type {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let options = FormatOptions { unit: UnitChoice::Own, precision: f.precision() };
+                // Formatting in the own unit cannot fail
+                pad(f, &registry().format(self, &options).unwrap())
+            }
+        }
+    };
+}
+
+// Metric lengths scale through metric units, feet through imperial ones
+unit_value!(Meters, "m", ["nm", "µm", "mm", "m", "km"]);
+unit_value!(Feet, "ft", ["in", "ft", "mi"]);
+unit_value!(Seconds, "s", ["ns", "µs", "ms", "s", "min", "h", "d"]);
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-9 * a.abs().max(b.abs()).max(1.0)
+}
+
+fn main() -> Result<(), UnitError> {
+    println!("=== Parsing ===");
+    let inputs = [
+        "10 ft", "3.2m", "1.5 km", "90s", "2h", "  7.5   meters ", "12 inches", "1e3 mm",
+        "250 ms", "3 µs", "3 us", "2 Kilometres", "1\u{a0}mi", "-4 yd", "45 min", "1.5 days",
+    ];
+    for input in inputs {
+        let (value, unit) = registry().parse(input)?;
+        let converted = match unit.kind {
+            Kind::Length => input.parse::<Meters>()?.to_string(),
+            Kind::Time => input.parse::<Seconds>()?.to_string(),
+        };
+        println!("  {:<18} -> {:<10} = {}", format!("{:?}", input), format!("{} {}", value, unit.symbol), converted);
+    }
+    assert_eq!("10 ft".parse::<Feet>()?, Feet(10.0));
+    assert_eq!("1.5 km".parse::<Meters>()?, Meters(1500.0));
+    assert_eq!("90s".parse::<Seconds>()?, Seconds(90.0));
+    assert_eq!("2h".parse::<Seconds>()?, Seconds(7200.0));
+    assert!(close("3.2m".parse::<Feet>()?.0, 3.2 / 0.3048));
+    assert!(close("12 inches".parse::<Feet>()?.0, 1.0));
+    assert_eq!("1e3 mm".parse::<Meters>()?, Meters(1.0));
+    // "m" alone is meters and "min" is minutes: exact matches beat prefixes
+    assert_eq!("1 min".parse::<Seconds>()?, Seconds(60.0));
+    assert_eq!("1 ms".parse::<Seconds>()?, Seconds(0.001));
+    assert_eq!("5 Mm".parse::<Meters>()?, Meters(5e6)); // mega, not milli
+
+    println!("\n=== Errors ===");
+    let bad = ["", "   ", "ft", "1.2.3 m", "10", "10 furlongs", "5 kft", "1e999 m", "10 m"];
+    for input in bad {
+        let result = if input == "10 m" { input.parse::<Seconds>().map(|_| ()) } else { input.parse::<Meters>().map(|_| ()) };
+        match result {
+            Ok(()) => println!("  {:<18} -> ok", format!("{:?}", input)),
+            Err(e) => println!("  {:<18} -> {}", format!("{:?}", input), e),
+        }
+    }
+    assert_eq!("".parse::<Meters>(), Err(UnitError::Empty));
+    assert_eq!("ft".parse::<Meters>(), Err(UnitError::InvalidNumber(String::new())));
+    assert_eq!("10".parse::<Meters>(), Err(UnitError::MissingUnit));
+    assert_eq!("10 furlongs".parse::<Meters>(), Err(UnitError::UnknownUnit(String::from("furlongs"))));
+    assert_eq!("5 kft".parse::<Meters>(), Err(UnitError::UnknownUnit(String::from("kft")))); // ft takes no prefix
+    assert_eq!("1e999 m".parse::<Meters>(), Err(UnitError::InvalidNumber(String::from("1e999"))));
+    assert_eq!("1e308 km".parse::<Meters>(), Err(UnitError::OutOfRange(String::from("1e308 km"))));
+    assert_eq!(
+        "90s".parse::<Meters>(),
+        Err(UnitError::IncompatibleUnit { unit: String::from("s"), expected: Kind::Length, found: Kind::Time })
+    );
+    // A registry without the newtype's own unit is an error, not a panic
+    let mut time_only = UnitRegistry::new();
+    time_only.register("s", &["seconds"], Kind::Time, 1.0, true);
+    assert_eq!(time_only.parse_as::<Meters>("3 s"), Err(UnitError::UnknownUnit(String::from("m"))));
+    assert_eq!(time_only.format(&Meters(3.0), &FormatOptions::auto()), Err(UnitError::UnknownUnit(String::from("m"))));
+
+    println!("\n=== Formatting ===");
+    let r = registry();
+    let cases: [(&str, String); 9] = [
+        ("Meters(1500.0), auto", r.format(&Meters(1500.0), &FormatOptions::auto())?),
+        ("Meters(0.0042), auto", r.format(&Meters(0.0042), &FormatOptions::auto())?),
+        ("Meters(3e-10), auto", r.format(&Meters(3e-10), &FormatOptions::auto())?),
+        ("Meters(-2500.0), auto", r.format(&Meters(-2500.0), &FormatOptions::auto())?),
+        ("Feet(7920.0), auto", r.format(&Feet(7920.0), &FormatOptions::auto())?),
+        ("Feet(0.5), auto", r.format(&Feet(0.5), &FormatOptions::auto())?),
+        ("Seconds(5400.0), auto, .2", r.format(&Seconds(5400.0), &FormatOptions::auto().precision(2))?),
+        ("Meters(100.0) in ft, .1", r.format(&Meters(100.0), &FormatOptions::unit("ft").precision(1))?),
+        ("Seconds(1.0 / 3.0), {:.3}", format!("{:.3}", Seconds(1.0 / 3.0))),
+    ];
+    for (what, text) in &cases {
+        println!("  {:<28} -> {}", what, text);
+    }
+    assert_eq!(cases[0].1, "1.5 km");
+    assert_eq!(cases[1].1, "4.2 mm");
+    assert_eq!(cases[3].1, "-2.5 km");
+    assert_eq!(cases[4].1, "1.5 mi");
+    assert_eq!(cases[5].1, "6 in");
+    assert_eq!(cases[6].1, "1.50 h");
+    assert_eq!(cases[7].1, "328.1 ft");
+    assert_eq!(cases[8].1, "0.333 s");
+    assert_eq!(r.format(&Meters(0.0), &FormatOptions::auto())?, "0 m");
+    assert_eq!(r.format(&Meters(f64::NAN), &FormatOptions::auto())?, "NaN m");
+    assert_eq!(r.format(&Seconds(f64::INFINITY), &FormatOptions::auto())?, "inf s");
+    // Width and fill apply to the number and unit together
+    assert_eq!(format!("[{:>8}]", Meters(1.5)), "[   1.5 m]");
+    assert_eq!(format!("[{:*<8.1}]", Seconds(2.0)), "[2.0 s***]");
+    assert_eq!(format!("[{:^9}]", Feet(10.0)), "[  10 ft  ]");
+    assert_eq!(
+        r.format(&Meters(1.0), &FormatOptions::unit("h")),
+        Err(UnitError::IncompatibleUnit { unit: String::from("h"), expected: Kind::Length, found: Kind::Time })
+    );
+
+    println!("\n=== Round trips: format, then parse back ===");
+    let lengths = [Meters(1500.0), Meters(0.0042), Meters(123.456), Meters(-7.0), Meters(9.9e6)];
+    for m in lengths {
+        let text = r.format(&m, &FormatOptions::auto())?;
+        let back: Meters = text.parse()?;
+        println!("  {:?} -> {:?} -> {:?}", m, text, back);
+        assert!(close(back.0, m.0));
+    }
+    for s in [Seconds(0.000_25), Seconds(59.0), Seconds(3600.0 * 30.0)] {
+        let back: Seconds = r.format(&s, &FormatOptions::auto())?.parse()?;
+        assert!(close(back.0, s.0));
+    }
+
+    // The registry is open: add a unit and it parses and formats like the rest
+    let mut custom = UnitRegistry::standard();
+    custom.register("ftm", &["fathom", "fathoms"], Kind::Length, 1.8288, false);
+    let depth: Meters = custom.parse_as("10 fathoms")?;
+    println!("\n  custom unit: \"10 fathoms\" = {:.3}", depth);
+    assert!(close(depth.0, 18.288));
+    Ok(())
+}
@@ -0,0 +1,499 @@
+// cargo run --example m5_newtype_quantity
+//
+// Demonstrates DIMENSIONAL ANALYSIS in the type system.
+//
+// m5_newtype has three unrelated wrappers: Meters, Feet and Seconds.
+// Nothing connects them, so "meters / seconds = speed" cannot be written.
+// Here every value is a Quantity<D, U>:
+//
+//   D - the DIMENSION, as exponents of (length, time, mass):
+//       length = (1, 0, 0), area = (2, 0, 0), velocity = (1, -1, 0)
+//   U - the UNIT the value is stored in: Meter, Foot, Second, ...
+//
+// Multiplying adds the exponents and dividing subtracts them, all at
+// compile time. Adding a length to a time does not compile, because
+// their D types differ. At runtime a Quantity is just one f64.
+//
+// The exponents are TYPE-LEVEL integers: Zero, Succ<N> (N + 1) and
+// Pred<N> (N - 1), with traits that do the arithmetic on types.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// ---------------------------------------------------------------------
+// Type-level integers
+// ---------------------------------------------------------------------
+
+struct Zero;
+struct Succ<N>(PhantomData<N>);
+struct Pred<N>(PhantomData<N>);
+
+// Only Zero, Succ<Succ<..Zero>> and Pred<Pred<..Zero>> are ever built:
+// a Succ never wraps a Pred, so every number has exactly one type
+type Z0 = Zero;
+type P1 = Succ<Zero>;
+type P2 = Succ<P1>;
+type N1 = Pred<Zero>;
+
+// Read the number back at runtime, for printing
+trait Integer {
+    const VALUE: i32;
+}
+
+impl Integer for Zero {
+    const VALUE: i32 = 0;
+}
+
+impl<N: Integer> Integer for Succ<N> {
+    const VALUE: i32 = N::VALUE + 1;
+}
+
+impl<N: Integer> Integer for Pred<N> {
+    const VALUE: i32 = N::VALUE - 1;
+}
+
+// N + 1 and N - 1. Incrementing a negative number unwraps a Pred
+// instead of adding a Succ, which keeps the form unique.
+trait Inc {
+    type Output;
+}
+
+trait Dec {
+    type Output;
+}
+
+impl Inc for Zero {
+    type Output = Succ<Zero>;
+}
+
+impl<N> Inc for Succ<N> {
+    type Output = Succ<Succ<N>>;
+}
+
+impl<N> Inc for Pred<N> {
+    type Output = N;
+}
+
+impl Dec for Zero {
+    type Output = Pred<Zero>;
+}
+
+impl<N> Dec for Succ<N> {
+    type Output = N;
+}
+
+impl<N> Dec for Pred<N> {
+    type Output = Pred<Pred<N>>;
+}
+
+// A + B, by peeling one step off A at a time:
+//   0 + B = B,  (A + 1) + B = (A + B) + 1,  (A - 1) + B = (A + B) - 1
+trait IntAdd<B> {
+    type Output;
+}
+
+impl<B> IntAdd<B> for Zero {
+    type Output = B;
+}
+
+impl<A: IntAdd<B>, B> IntAdd<B> for Succ<A>
+where
+    A::Output: Inc,
+{
+    type Output = <A::Output as Inc>::Output;
+}
+
+impl<A: IntAdd<B>, B> IntAdd<B> for Pred<A>
+where
+    A::Output: Dec,
+{
+    type Output = <A::Output as Dec>::Output;
+}
+
+// -A, then A - B = A + (-B)
+trait IntNeg {
+    type Output;
+}
+
+impl IntNeg for Zero {
+    type Output = Zero;
+}
+
+impl<N: IntNeg> IntNeg for Succ<N> {
+    type Output = Pred<N::Output>;
+}
+
+impl<N: IntNeg> IntNeg for Pred<N> {
+    type Output = Succ<N::Output>;
+}
+
+trait IntSub<B> {
+    type Output;
+}
+
+impl<A, B: IntNeg> IntSub<B> for A
+where
+    A: IntAdd<B::Output>,
+{
+    type Output = <A as IntAdd<B::Output>>::Output;
+}
+
+// ---------------------------------------------------------------------
+// Dimensions
+// ---------------------------------------------------------------------
+
+// Exponents of length, time and mass
+struct Dim<L, T, M>(PhantomData<(L, T, M)>);
+
+mod dim {
+    use super::{Dim, N1, P1, P2, Z0};
+
+    pub type Dimensionless = Dim<Z0, Z0, Z0>;
+    pub type Length = Dim<P1, Z0, Z0>;
+    pub type Time = Dim<Z0, P1, Z0>;
+    pub type Mass = Dim<Z0, Z0, P1>;
+    pub type Area = Dim<P2, Z0, Z0>;
+    pub type Velocity = Dim<P1, N1, Z0>;
+    pub type Acceleration = Dim<P1, super::Pred<N1>, Z0>;
+}
+
+trait Dimension {
+    fn exponents() -> [i32; 3];
+}
+
+impl<L: Integer, T: Integer, M: Integer> Dimension for Dim<L, T, M> {
+    fn exponents() -> [i32; 3] {
+        [L::VALUE, T::VALUE, M::VALUE]
+    }
+}
+
+// Multiplying quantities adds exponents...
+trait DimMul<Rhs> {
+    type Output;
+}
+
+impl<L1, T1, M1, L2, T2, M2> DimMul<Dim<L2, T2, M2>> for Dim<L1, T1, M1>
+where
+    L1: IntAdd<L2>,
+    T1: IntAdd<T2>,
+    M1: IntAdd<M2>,
+{
+    type Output = Dim<L1::Output, T1::Output, M1::Output>;
+}
+
+// ...and dividing subtracts them
+trait DimDiv<Rhs> {
+    type Output;
+}
+
+impl<L1, T1, M1, L2, T2, M2> DimDiv<Dim<L2, T2, M2>> for Dim<L1, T1, M1>
+where
+    L1: IntSub<L2>,
+    T1: IntSub<T2>,
+    M1: IntSub<M2>,
+{
+    type Output = Dim<L1::Output, T1::Output, M1::Output>;
+}
+
+// ---------------------------------------------------------------------
+// Units
+// ---------------------------------------------------------------------
+
+// A unit of dimension D: how many SI base units one of it is
+trait Unit<D> {
+    const TO_SI: f64;
+    fn symbol() -> String;
+}
+
+// The SI unit of ANY dimension; its symbol is built from the exponents,
+// e.g. "m^2" or "kg·m·s^-2". Products and quotients come out in Si.
+struct Si;
+
+impl<D: Dimension> Unit<D> for Si {
+    const TO_SI: f64 = 1.0;
+    fn symbol() -> String {
+        let [length, time, mass] = D::exponents();
+        let parts: Vec<String> = [("kg", mass), ("m", length), ("s", time)]
+            .into_iter()
+            .filter(|(_, exp)| *exp != 0)
+            .map(|(name, exp)| if exp == 1 { name.to_string() } else { format!("{}^{}", name, exp) })
+            .collect();
+        parts.join("·")
+    }
+}
+
+macro_rules! unit {
+    ($name:ident, $dim:ty, $to_si:expr, $symbol:expr) => {
+        struct $name;
+
+        impl Unit<$dim> for $name {
+            const TO_SI: f64 = $to_si;
+            fn symbol() -> String {
+                String::from($symbol)
+            }
+        }
+    };
+}
+
+// Exactly 0.3048 m by definition; m5_newtype used 1 / 3.28084
+unit!(Meter, dim::Length, 1.0, "m");
+unit!(Kilometer, dim::Length, 1000.0, "km");
+unit!(Foot, dim::Length, 0.3048, "ft");
+unit!(Second, dim::Time, 1.0, "s");
+unit!(Hour, dim::Time, 3600.0, "h");
+unit!(Kilogram, dim::Mass, 1.0, "kg");
+unit!(SquareFoot, dim::Area, 0.3048 * 0.3048, "ft^2");
+unit!(KilometersPerHour, dim::Velocity, 1000.0 / 3600.0, "km/h");
+
+// ---------------------------------------------------------------------
+// Quantity
+// ---------------------------------------------------------------------
+
+struct Quantity<D, U> {
+    value: f64, // in unit U
+    _marker: PhantomData<(D, U)>,
+}
+
+// The names from m5_newtype, now all one type with different parameters
+type Meters = Quantity<dim::Length, Meter>;
+type Feet = Quantity<dim::Length, Foot>;
+type Seconds = Quantity<dim::Time, Second>;
+type Area = Quantity<dim::Area, Si>;
+type Velocity = Quantity<dim::Velocity, Si>;
+
+impl<D, U: Unit<D>> Quantity<D, U> {
+    fn new(value: f64) -> Self {
+        Quantity { value, _marker: PhantomData }
+    }
+
+    // The number, in this quantity's own unit
+    fn value(&self) -> f64 {
+        self.value
+    }
+
+    fn to_si(self) -> Quantity<D, Si>
+    where
+        Si: Unit<D>,
+    {
+        Quantity::new(self.value * U::TO_SI)
+    }
+
+    // Same dimension, different unit: Meters -> Feet compiles,
+    // Meters -> Seconds does not
+    fn convert<V: Unit<D>>(self) -> Quantity<D, V> {
+        Quantity::new(self.value * U::TO_SI / V::TO_SI)
+    }
+}
+
+impl Meters {
+    fn to_feet(self) -> Feet {
+        self.convert()
+    }
+}
+
+impl Feet {
+    fn to_meters(self) -> Meters {
+        self.convert()
+    }
+}
+
+// Derives would demand D: Clone etc., so these are written by hand
+impl<D, U> Clone for Quantity<D, U> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<D, U> Copy for Quantity<D, U> {}
+
+impl<D, U: Unit<D>> fmt::Debug for Quantity<D, U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Quantity({} {})", self.value, U::symbol())
+    }
+}
+
+impl<D, U: Unit<D>> fmt::Display for Quantity<D, U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Pass precision through: format!("{:.2}", q)
+        match f.precision() {
+            Some(p) => write!(f, "{:.*}", p, self.value)?,
+            None => write!(f, "{}", self.value)?,
+        }
+        // A dimensionless ratio has no symbol
+        let symbol = U::symbol();
+        if symbol.is_empty() { Ok(()) } else { write!(f, " {}", symbol) }
+    }
+}
+
+// Equal and ordered by physical size, whatever the units
+impl<D, U: Unit<D>, V: Unit<D>> PartialEq<Quantity<D, V>> for Quantity<D, U> {
+    fn eq(&self, other: &Quantity<D, V>) -> bool {
+        self.value * U::TO_SI == other.value * V::TO_SI
+    }
+}
+
+impl<D, U: Unit<D>, V: Unit<D>> PartialOrd<Quantity<D, V>> for Quantity<D, U> {
+    fn partial_cmp(&self, other: &Quantity<D, V>) -> Option<std::cmp::Ordering> {
+        (self.value * U::TO_SI).partial_cmp(&(other.value * V::TO_SI))
+    }
+}
+
+// Adding needs the SAME dimension; the units may differ.
+// The result keeps the left-hand unit: 1 m + 1 ft = 1.3048 m
+impl<D, U: Unit<D>, V: Unit<D>> Add<Quantity<D, V>> for Quantity<D, U> {
+    type Output = Quantity<D, U>;
+    fn add(self, rhs: Quantity<D, V>) -> Self::Output {
+        Quantity::new(self.value + rhs.convert::<U>().value)
+    }
+}
+
+impl<D, U: Unit<D>, V: Unit<D>> Sub<Quantity<D, V>> for Quantity<D, U> {
+    type Output = Quantity<D, U>;
+    fn sub(self, rhs: Quantity<D, V>) -> Self::Output {
+        Quantity::new(self.value - rhs.convert::<U>().value)
+    }
+}
+
+impl<D, U: Unit<D>> Neg for Quantity<D, U> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Quantity::new(-self.value)
+    }
+}
+
+// Scaling by a plain number keeps the dimension
+impl<D, U: Unit<D>> Mul<f64> for Quantity<D, U> {
+    type Output = Self;
+    fn mul(self, rhs: f64) -> Self {
+        Quantity::new(self.value * rhs)
+    }
+}
+
+impl<D, U: Unit<D>> Div<f64> for Quantity<D, U> {
+    type Output = Self;
+    fn div(self, rhs: f64) -> Self {
+        Quantity::new(self.value / rhs)
+    }
+}
+
+// Multiplying two quantities combines their dimensions; the result is in SI
+impl<D1, U1, D2, U2> Mul<Quantity<D2, U2>> for Quantity<D1, U1>
+where
+    D1: DimMul<D2>,
+    U1: Unit<D1>,
+    U2: Unit<D2>,
+    Si: Unit<D1::Output>,
+{
+    type Output = Quantity<D1::Output, Si>;
+    fn mul(self, rhs: Quantity<D2, U2>) -> Self::Output {
+        Quantity::new(self.value * U1::TO_SI * rhs.value * U2::TO_SI)
+    }
+}
+
+impl<D1, U1, D2, U2> Div<Quantity<D2, U2>> for Quantity<D1, U1>
+where
+    D1: DimDiv<D2>,
+    U1: Unit<D1>,
+    U2: Unit<D2>,
+    Si: Unit<D1::Output>,
+{
+    type Output = Quantity<D1::Output, Si>;
+    fn div(self, rhs: Quantity<D2, U2>) -> Self::Output {
+        Quantity::new(self.value * U1::TO_SI / (rhs.value * U2::TO_SI))
+    }
+}
+
+// ---------------------------------------------------------------------
+// The m5_newtype functions, with typed results
+// ---------------------------------------------------------------------
+
+// Returns an Area, not a bare f64: the caller cannot mistake it for a length
+fn calculate_area_meters(length: &Meters, width: &Meters) -> Area {
+    *length * *width
+}
+
+// Any length over any time: the units are converted automatically
+fn average_speed<U: Unit<dim::Length>, V: Unit<dim::Time>>(
+    distance: Quantity<dim::Length, U>,
+    time: Quantity<dim::Time, V>,
+) -> Velocity {
+    distance / time
+}
+
+// These do not compile - uncomment one to see the error:
+// fn bad_examples() {
+//     let length = Meters::new(10.0);
+//     let time = Seconds::new(5.0);
+//     let _ = length + time;                           // ERROR: mismatched types (length vs time)
+//     let _: Area = length / time;                     // ERROR: mismatched types (a velocity is not an area)
+//     let _ = calculate_area_meters(&length, &time);   // ERROR: mismatched types (expected Meters)
+//     let _ = length.convert::<Second>();              // ERROR: `Second: Unit<Length>` is not satisfied
+// }
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9 * a.abs().max(1.0)
+}
+
+fn main() {
+    println!("=== The m5_newtype examples, dimension-checked ===\n");
+    let distance_m = Meters::new(100.0);
+    let distance_ft = distance_m.to_feet();
+    println!("  {:.1} = {:.2}", distance_m, distance_ft);
+    let height_ft = Feet::new(6.0);
+    println!("  {:.1} = {:.2}", height_ft, height_ft.to_meters());
+    assert!(close(distance_ft.to_meters().value(), 100.0));
+
+    let length = Meters::new(10.0);
+    let width = Meters::new(5.0);
+    let area = calculate_area_meters(&length, &width);
+    println!("  {} x {} = {}", length, width, area);
+    assert_eq!(area.value(), 50.0);
+    println!("           = {:.1}", area.convert::<SquareFoot>());
+
+    println!("\n=== Derived dimensions ===\n");
+    let lap = Meters::new(400.0);
+    let time = Seconds::new(50.0);
+    let speed = lap / time;
+    println!("  {} / {} = {}", lap, time, speed);
+    println!("           = {:.1}", speed.convert::<KilometersPerHour>());
+    assert_eq!(speed.value(), 8.0);
+    assert!(close(speed.convert::<KilometersPerHour>().value(), 28.8));
+
+    // Mixed units convert on the way in
+    let drive = average_speed(Quantity::<dim::Length, Kilometer>::new(150.0), Quantity::<dim::Time, Hour>::new(2.0));
+    println!("  150 km in 2 h = {:.3} = {:.1}", drive, drive.convert::<KilometersPerHour>());
+    assert!(close(drive.convert::<KilometersPerHour>().value(), 75.0));
+
+    // Velocity / time = acceleration; acceleration x mass = force (kg·m·s^-2)
+    let accel: Quantity<dim::Acceleration, Si> = speed / time;
+    let force = accel * Quantity::<dim::Mass, Kilogram>::new(70.0);
+    println!("  {} / {} = {}", speed, time, accel);
+    println!("  x 70 kg = {:.2}", force);
+    assert!(close(force.value(), 11.2));
+
+    // Dividing a length by a length leaves a plain ratio
+    let ratio: Quantity<dim::Dimensionless, Si> = Meters::new(3.0) / Feet::new(1.0);
+    println!("  3 m / 1 ft = {:.4} (no unit)", ratio);
+    // Area / length is a length again
+    let side: Meters = (area / width).convert();
+    assert_eq!(side, length);
+
+    println!("\n=== Same dimension, different units ===\n");
+    let total = Meters::new(1.0) + Feet::new(1.0);
+    println!("  1 m + 1 ft = {}", total);
+    assert!(close(total.value(), 1.3048));
+    println!("  1 ft + 1 m = {:.4}", Feet::new(1.0) + Meters::new(1.0));
+    // to_si works for any unit of any dimension
+    println!("  {:.2} in SI = {:.2}", distance_ft, distance_ft.to_si());
+    assert!(close(distance_ft.to_si().value(), 100.0));
+    assert!(Feet::new(3.0) < Meters::new(1.0));
+    assert_eq!(Meters::new(0.3048), Feet::new(1.0));
+    assert_eq!(-(Meters::new(2.0) * 3.0 - Meters::new(1.0)) / 5.0, Meters::new(-1.0));
+
+    println!("\n  Exponents are checked by the compiler, for example:");
+    println!("    dim::Velocity = {:?}", <dim::Velocity as Dimension>::exponents());
+    println!("    Area / Time   = {:?}", <<dim::Area as DimDiv<dim::Time>>::Output as Dimension>::exponents());
+    assert_eq!(<dim::Acceleration as Dimension>::exponents(), [1, -2, 0]);
+    assert_eq!(Seconds::new(1.0).value(), 1.0);
+}
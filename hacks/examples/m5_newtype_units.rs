@@ -0,0 +1,640 @@
+// cargo run --example m5_newtype_units
+//
+// Demonstrates more UNIT FAMILIES built with the newtype pattern.
+// m5_newtype only had length (with a hard-coded 3.28084) and time.
+//
+//   Mass, Volume, Angle  - LINEAR: value_in_base = value * factor
+//   Temperature          - AFFINE: °F = °C * 9/5 + 32. The zero point
+//                          moves, so "20 °C + 10 °C" has no meaning.
+//                          A TemperatureDelta (a difference) is a separate
+//                          type: point - point = delta, point.checked_add(
+//                          delta) = point (it can fail below absolute
+//                          zero), and point + point does not compile.
+//   DataSize             - whole bytes in a u64, with decimal (kB = 1000)
+//                          and binary (KiB = 1024) prefixes
+//
+// Every family has a conversion table, and main checks that converting
+// any value to any unit and back gives the same value.
+// `cargo test --example m5_newtype_units` runs the same tables as tests.
+
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+// ---------------------------------------------------------------------
+// Linear families
+// ---------------------------------------------------------------------
+
+// One macro writes a family: the unit enum with its factors, and a
+// newtype that stores the value in the base unit
+macro_rules! linear_family {
+    (
+        $Quantity:ident, $Unit:ident, base = $Base:ident,
+        { $($Variant:ident => ($symbol:expr, $factor:expr)),+ $(,)? }
+    ) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        enum $Unit {
+            $($Variant),+
+        }
+
+        impl $Unit {
+            const ALL: &'static [$Unit] = &[$($Unit::$Variant),+];
+
+            // How many base units one of this unit is
+            fn factor(self) -> f64 {
+                match self {
+                    $($Unit::$Variant => $factor),+
+                }
+            }
+
+            fn symbol(self) -> &'static str {
+                match self {
+                    $($Unit::$Variant => $symbol),+
+                }
+            }
+        }
+
+        // Stored in the base unit, so comparing and adding never converts
+        #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+        struct $Quantity(f64);
+
+        impl $Quantity {
+            fn new(value: f64, unit: $Unit) -> Self {
+                $Quantity(value * unit.factor())
+            }
+
+            fn get(self, unit: $Unit) -> f64 {
+                self.0 / unit.factor()
+            }
+
+            // Display helper: "2.5 lb"
+            fn show(self, unit: $Unit) -> String {
+                format!("{} {}", tidy(self.get(unit)), unit.symbol())
+            }
+        }
+
+        impl Add for $Quantity {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                $Quantity(self.0 + rhs.0)
+            }
+        }
+
+        impl Sub for $Quantity {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                $Quantity(self.0 - rhs.0)
+            }
+        }
+
+        impl Mul<f64> for $Quantity {
+            type Output = Self;
+            fn mul(self, rhs: f64) -> Self {
+                $Quantity(self.0 * rhs)
+            }
+        }
+
+        impl fmt::Display for $Quantity {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.show($Unit::$Base))
+            }
+        }
+    };
+}
+
+// Exact factors from the international definitions
+linear_family!(Mass, MassUnit, base = Kilogram, {
+    Milligram => ("mg", 1e-6),
+    Gram => ("g", 1e-3),
+    Kilogram => ("kg", 1.0),
+    Tonne => ("t", 1000.0),
+    Ounce => ("oz", 0.028_349_523_125),
+    Pound => ("lb", 0.453_592_37),
+    Stone => ("st", 6.350_293_18),
+});
+
+// Base: liters. US customary units are defined from the US gallon
+// (231 cubic inches); the imperial gallon is exactly 4.54609 L.
+linear_family!(Volume, VolumeUnit, base = Liter, {
+    Milliliter => ("mL", 1e-3),
+    Liter => ("L", 1.0),
+    CubicMeter => ("m³", 1000.0),
+    CubicCentimeter => ("cm³", 1e-3),
+    Teaspoon => ("tsp", 3.785_411_784 / 768.0),
+    Tablespoon => ("tbsp", 3.785_411_784 / 256.0),
+    FluidOunce => ("fl oz", 3.785_411_784 / 128.0),
+    Cup => ("cup", 3.785_411_784 / 16.0),
+    Pint => ("pt", 3.785_411_784 / 8.0),
+    Quart => ("qt", 3.785_411_784 / 4.0),
+    Gallon => ("gal", 3.785_411_784),
+    ImperialGallon => ("imp gal", 4.546_09),
+});
+
+linear_family!(Angle, AngleUnit, base = Radian, {
+    Radian => ("rad", 1.0),
+    Degree => ("°", std::f64::consts::PI / 180.0),
+    Gradian => ("gon", std::f64::consts::PI / 200.0),
+    Turn => ("turn", std::f64::consts::TAU),
+    ArcMinute => ("′", std::f64::consts::PI / (180.0 * 60.0)),
+    ArcSecond => ("″", std::f64::consts::PI / (180.0 * 3600.0)),
+});
+
+impl Angle {
+    // The same direction, as an angle in [0, 1 turn)
+    fn normalized(self) -> Angle {
+        Angle(self.0.rem_euclid(std::f64::consts::TAU))
+    }
+}
+
+// ---------------------------------------------------------------------
+// Temperature (affine)
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TemperatureUnit {
+    Kelvin,
+    Celsius,
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    const ALL: &'static [TemperatureUnit] =
+        &[TemperatureUnit::Kelvin, TemperatureUnit::Celsius, TemperatureUnit::Fahrenheit];
+
+    // kelvin = (value + offset) * scale
+    fn offset_and_scale(self) -> (f64, f64) {
+        match self {
+            TemperatureUnit::Kelvin => (0.0, 1.0),
+            TemperatureUnit::Celsius => (273.15, 1.0),
+            TemperatureUnit::Fahrenheit => (459.67, 5.0 / 9.0),
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            TemperatureUnit::Kelvin => "K",
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TemperatureError {
+    BelowAbsoluteZero(f64), // in kelvin
+    NotANumber,
+}
+
+impl fmt::Display for TemperatureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TemperatureError::BelowAbsoluteZero(k) => write!(f, "{} K is below absolute zero", tidy(*k)),
+            TemperatureError::NotANumber => write!(f, "temperature is NaN"),
+        }
+    }
+}
+
+impl std::error::Error for TemperatureError {}
+
+// A point on the temperature scale, stored in kelvin
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+struct Temperature(f64);
+
+// A difference between two temperatures, stored in kelvin (= °C steps)
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+struct TemperatureDelta(f64);
+
+impl Temperature {
+    fn new(value: f64, unit: TemperatureUnit) -> Result<Self, TemperatureError> {
+        let (offset, scale) = unit.offset_and_scale();
+        Temperature::from_kelvin((value + offset) * scale)
+    }
+
+    fn from_kelvin(kelvin: f64) -> Result<Self, TemperatureError> {
+        if kelvin.is_nan() {
+            Err(TemperatureError::NotANumber)
+        } else if kelvin < 0.0 {
+            Err(TemperatureError::BelowAbsoluteZero(kelvin))
+        } else {
+            Ok(Temperature(kelvin))
+        }
+    }
+
+    fn get(self, unit: TemperatureUnit) -> f64 {
+        let (offset, scale) = unit.offset_and_scale();
+        self.0 / scale - offset
+    }
+
+    fn show(self, unit: TemperatureUnit) -> String {
+        format!("{} {}", tidy(self.get(unit)), unit.symbol())
+    }
+
+    // point + delta = point, refusing to go below absolute zero. There is
+    // no `+` for this: it could not report the error.
+    fn checked_add(self, delta: TemperatureDelta) -> Result<Self, TemperatureError> {
+        Temperature::from_kelvin(self.0 + delta.0)
+    }
+}
+
+impl TemperatureDelta {
+    // Only the scale matters for a difference: 18 °F of change = 10 K
+    fn new(value: f64, unit: TemperatureUnit) -> Self {
+        TemperatureDelta(value * unit.offset_and_scale().1)
+    }
+
+    fn get(self, unit: TemperatureUnit) -> f64 {
+        self.0 / unit.offset_and_scale().1
+    }
+}
+
+// point - point = delta
+impl Sub for Temperature {
+    type Output = TemperatureDelta;
+    fn sub(self, rhs: Temperature) -> TemperatureDelta {
+        TemperatureDelta(self.0 - rhs.0)
+    }
+}
+
+impl Add for TemperatureDelta {
+    type Output = TemperatureDelta;
+    fn add(self, rhs: TemperatureDelta) -> TemperatureDelta {
+        TemperatureDelta(self.0 + rhs.0)
+    }
+}
+
+// There is deliberately NO `impl Add for Temperature`:
+//     let _ = boiling + freezing;   // ERROR: cannot add `Temperature` to `Temperature`
+
+// ---------------------------------------------------------------------
+// Data size
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DataUnit {
+    Byte,
+    Kilobyte,
+    Megabyte,
+    Gigabyte,
+    Terabyte,
+    Petabyte,
+    Kibibyte,
+    Mebibyte,
+    Gibibyte,
+    Tebibyte,
+    Pebibyte,
+}
+
+impl DataUnit {
+    const ALL: &'static [DataUnit] = &[
+        DataUnit::Byte,
+        DataUnit::Kilobyte,
+        DataUnit::Megabyte,
+        DataUnit::Gigabyte,
+        DataUnit::Terabyte,
+        DataUnit::Petabyte,
+        DataUnit::Kibibyte,
+        DataUnit::Mebibyte,
+        DataUnit::Gibibyte,
+        DataUnit::Tebibyte,
+        DataUnit::Pebibyte,
+    ];
+    const DECIMAL: &'static [DataUnit] =
+        &[DataUnit::Byte, DataUnit::Kilobyte, DataUnit::Megabyte, DataUnit::Gigabyte, DataUnit::Terabyte, DataUnit::Petabyte];
+    const BINARY: &'static [DataUnit] =
+        &[DataUnit::Byte, DataUnit::Kibibyte, DataUnit::Mebibyte, DataUnit::Gibibyte, DataUnit::Tebibyte, DataUnit::Pebibyte];
+
+    // Exact, as an integer
+    fn bytes(self) -> u64 {
+        match self {
+            DataUnit::Byte => 1,
+            DataUnit::Kilobyte => 1000,
+            DataUnit::Megabyte => 1000u64.pow(2),
+            DataUnit::Gigabyte => 1000u64.pow(3),
+            DataUnit::Terabyte => 1000u64.pow(4),
+            DataUnit::Petabyte => 1000u64.pow(5),
+            DataUnit::Kibibyte => 1 << 10,
+            DataUnit::Mebibyte => 1 << 20,
+            DataUnit::Gibibyte => 1 << 30,
+            DataUnit::Tebibyte => 1 << 40,
+            DataUnit::Pebibyte => 1 << 50,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            DataUnit::Byte => "B",
+            DataUnit::Kilobyte => "kB",
+            DataUnit::Megabyte => "MB",
+            DataUnit::Gigabyte => "GB",
+            DataUnit::Terabyte => "TB",
+            DataUnit::Petabyte => "PB",
+            DataUnit::Kibibyte => "KiB",
+            DataUnit::Mebibyte => "MiB",
+            DataUnit::Gibibyte => "GiB",
+            DataUnit::Tebibyte => "TiB",
+            DataUnit::Pebibyte => "PiB",
+        }
+    }
+}
+
+// A whole number of bytes. u64 keeps sizes exact, where f64 would start
+// rounding above 2^53 bytes (8 PiB).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct DataSize(u64);
+
+impl DataSize {
+    // None if the result does not fit in a u64
+    fn new(value: u64, unit: DataUnit) -> Option<Self> {
+        value.checked_mul(unit.bytes()).map(DataSize)
+    }
+
+    // Whole units and the bytes left over: 1500 B = (1 kB, 500 B)
+    fn whole(self, unit: DataUnit) -> (u64, u64) {
+        (self.0 / unit.bytes(), self.0 % unit.bytes())
+    }
+
+    fn get(self, unit: DataUnit) -> f64 {
+        self.0 as f64 / unit.bytes() as f64
+    }
+
+    // The largest unit of the chosen system in which the size is at least 1
+    fn show(self, units: &[DataUnit]) -> String {
+        let unit = units.iter().rev().find(|u| self.0 >= u.bytes()).copied().unwrap_or(DataUnit::Byte);
+        if unit == DataUnit::Byte {
+            format!("{} B", self.0)
+        } else {
+            format!("{:.2} {}", self.get(unit), unit.symbol())
+        }
+    }
+}
+
+// ---------------------------------------------------------------------
+// Checks
+// ---------------------------------------------------------------------
+
+// Round away last-digit noise for display
+fn tidy(value: f64) -> f64 {
+    format!("{:.11e}", value).parse().unwrap_or(value)
+}
+
+// Relative error allowed after a conversion there and back: a few ulps
+const ROUND_TRIP_TOLERANCE: f64 = 8.0 * f64::EPSILON;
+
+fn assert_round_trip(family: &str, from: &str, to: &str, original: f64, back: f64) {
+    let error = if original == 0.0 { back.abs() } else { ((back - original) / original).abs() };
+    assert!(
+        error <= ROUND_TRIP_TOLERANCE,
+        "{}: {} {} -> {} -> {} (relative error {:e})",
+        family,
+        original,
+        from,
+        to,
+        back,
+        error
+    );
+}
+
+const SAMPLES: [f64; 8] = [0.0, 1.0, -1.0, 0.1, 2.5, 1234.5678, 1e-9, 6.02e23];
+
+// Print the table (1 unit = x base) and check every pair of units
+macro_rules! check_linear {
+    ($Quantity:ident, $Unit:ident, $base:expr) => {{
+        println!("{}:", stringify!($Quantity));
+        for &unit in $Unit::ALL {
+            println!("  1 {:<7} = {} {}", unit.symbol(), tidy(unit.factor()), $base.symbol());
+        }
+        for &from in $Unit::ALL {
+            for &to in $Unit::ALL {
+                for value in SAMPLES {
+                    let there = $Quantity::new(value, from).get(to);
+                    let back = $Quantity::new(there, to).get(from);
+                    assert_round_trip(stringify!($Quantity), from.symbol(), to.symbol(), value, back);
+                }
+            }
+        }
+        let pairs = $Unit::ALL.len() * $Unit::ALL.len();
+        println!("  {} unit pairs x {} values round-trip\n", pairs, SAMPLES.len());
+    }};
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-12 * a.abs().max(b.abs()).max(1.0)
+}
+
+fn main() -> Result<(), TemperatureError> {
+    println!("=== Conversion tables ===\n");
+    check_linear!(Mass, MassUnit, MassUnit::Kilogram);
+    check_linear!(Volume, VolumeUnit, VolumeUnit::Liter);
+    check_linear!(Angle, AngleUnit, AngleUnit::Radian);
+
+    // Known equalities
+    assert!(close(Mass::new(1.0, MassUnit::Pound).get(MassUnit::Ounce), 16.0));
+    assert!(close(Mass::new(1.0, MassUnit::Stone).get(MassUnit::Pound), 14.0));
+    assert!(close(Volume::new(1.0, VolumeUnit::Gallon).get(VolumeUnit::FluidOunce), 128.0));
+    assert!(close(Volume::new(1.0, VolumeUnit::Tablespoon).get(VolumeUnit::Teaspoon), 3.0));
+    assert!(close(Volume::new(1.0, VolumeUnit::CubicMeter).get(VolumeUnit::Liter), 1000.0));
+    assert!(close(Angle::new(1.0, AngleUnit::Turn).get(AngleUnit::Degree), 360.0));
+    assert!(close(Angle::new(1.0, AngleUnit::Degree).get(AngleUnit::ArcSecond), 3600.0));
+
+    let flour = Mass::new(2.5, MassUnit::Pound) + Mass::new(200.0, MassUnit::Gram);
+    println!("2.5 lb + 200 g = {} = {}", flour, flour.show(MassUnit::Ounce));
+    let milk = Volume::new(1.0, VolumeUnit::ImperialGallon) - Volume::new(1.0, VolumeUnit::Gallon);
+    println!("imperial gallon - US gallon = {}", milk.show(VolumeUnit::Milliliter));
+    let heading = (Angle::new(350.0, AngleUnit::Degree) + Angle::new(30.0, AngleUnit::Degree)).normalized();
+    println!("350° + 30°, normalized = {}", heading.show(AngleUnit::Degree));
+    assert!(close(heading.get(AngleUnit::Degree), 20.0));
+    assert!(close((Angle::new(-90.0, AngleUnit::Degree)).normalized().get(AngleUnit::Degree), 270.0));
+
+    println!("\n=== Temperature ===\n");
+    use TemperatureUnit::{Celsius, Fahrenheit, Kelvin};
+    for (value, unit) in [(100.0, Celsius), (0.0, Celsius), (-40.0, Celsius), (98.6, Fahrenheit), (0.0, Kelvin)] {
+        let t = Temperature::new(value, unit)?;
+        println!("  {:<9} = {:<10} = {:<10} = {}", t.show(unit), t.show(Celsius), t.show(Fahrenheit), t.show(Kelvin));
+    }
+    assert!(close(Temperature::new(212.0, Fahrenheit)?.get(Celsius), 100.0));
+    assert!(close(Temperature::new(-40.0, Celsius)?.get(Fahrenheit), -40.0));
+    assert!(close(Temperature::new(0.0, Kelvin)?.get(Celsius), -273.15));
+
+    // Differences scale but do not shift: a 10 °C rise is an 18 °F rise
+    let morning = Temperature::new(12.0, Celsius)?;
+    let noon = Temperature::new(22.0, Celsius)?;
+    let rise = noon - morning;
+    println!("\n  rise from {} to {}: {} °C = {} °F", morning.show(Celsius), noon.show(Celsius), tidy(rise.get(Celsius)), tidy(rise.get(Fahrenheit)));
+    assert!(close(rise.get(Fahrenheit), 18.0));
+    let evening = noon.checked_add(TemperatureDelta::new(-9.0, Fahrenheit))?;
+    println!("  noon + (-9 °F change) = {}", evening.show(Celsius));
+    assert!(close(evening.get(Celsius), 17.0));
+    assert!(close((rise + rise).get(Kelvin), 20.0));
+
+    // Absolute zero is a floor
+    let errors = [
+        Temperature::new(-300.0, Celsius).unwrap_err(),
+        Temperature::new(f64::NAN, Kelvin).unwrap_err(),
+        Temperature::new(1.0, Kelvin)?.checked_add(TemperatureDelta::new(-5.0, Celsius)).unwrap_err(),
+    ];
+    for e in errors {
+        println!("  error: {}", e);
+    }
+    assert!(matches!(errors[0], TemperatureError::BelowAbsoluteZero(k) if close(k, -26.85)));
+    assert_eq!(errors[1], TemperatureError::NotANumber);
+    assert_eq!(errors[2], TemperatureError::BelowAbsoluteZero(-4.0));
+
+    print!("\n  Temperature round trips: ");
+    let mut checked = 0;
+    for &from in TemperatureUnit::ALL {
+        for &to in TemperatureUnit::ALL {
+            for value in [0.0, 1.0, 25.0, 100.0, 1234.5678, 1e6] {
+                let there = Temperature::new(value, from)?.get(to);
+                let back = Temperature::new(there, to)?.get(from);
+                // Offsets add absolute rounding error, so compare against
+                // the largest number seen along the way
+                let scale = value.abs().max(there.abs()).max(Temperature::new(value, from)?.0);
+                assert!((back - value).abs() <= 16.0 * f64::EPSILON * scale, "{} {:?} -> {:?}: {}", value, from, to, back);
+                checked += 1;
+            }
+        }
+    }
+    println!("{} conversions ok", checked);
+
+    println!("\n=== Data size ===\n");
+    for &unit in DataUnit::ALL {
+        println!("  1 {:<3} = {} B", unit.symbol(), unit.bytes());
+    }
+    let sizes = [DataSize(999), DataSize(1_500), DataSize(1_048_576), DataSize(5_000_000_000), DataSize(u64::MAX)];
+    for size in sizes {
+        println!("  {:>20} B = {:<10} = {}", size.0, size.show(DataUnit::DECIMAL), size.show(DataUnit::BINARY));
+    }
+    assert_eq!(DataSize(1_048_576).show(DataUnit::BINARY), "1.00 MiB");
+    assert_eq!(DataSize(1_048_576).show(DataUnit::DECIMAL), "1.05 MB");
+    assert_eq!(DataSize(999).show(DataUnit::DECIMAL), "999 B");
+    assert_eq!(DataSize(1_500).whole(DataUnit::Kilobyte), (1, 500));
+    assert_eq!(DataSize::new(2, DataUnit::Kibibyte), Some(DataSize(2048)));
+    assert_eq!(DataSize::new(u64::MAX, DataUnit::Kilobyte), None); // overflow is reported
+
+    // Integer round trips are exact, right up to the largest size that fits
+    let mut checked = 0;
+    for &unit in DataUnit::ALL {
+        let max = u64::MAX / unit.bytes();
+        for value in [0, 1, 7, 1000, 1024, max / 2, max] {
+            let size = DataSize::new(value, unit).unwrap();
+            assert_eq!(size.whole(unit), (value, 0));
+            for &other in DataUnit::ALL {
+                let (whole, rest) = size.whole(other);
+                assert_eq!(whole as u128 * other.bytes() as u128 + rest as u128, size.0 as u128);
+            }
+            checked += 1;
+        }
+    }
+    println!("  {} sizes split and rebuilt exactly in all {} units", checked, DataUnit::ALL.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Each row: value in `from` = expected in `to`, checked both ways
+    #[test]
+    fn linear_known_answers_round_trip() {
+        let masses = [
+            (1.0, MassUnit::Pound, MassUnit::Ounce, 16.0),
+            (1.0, MassUnit::Stone, MassUnit::Pound, 14.0),
+            (1.0, MassUnit::Tonne, MassUnit::Milligram, 1e9),
+            (1.0, MassUnit::Pound, MassUnit::Gram, 453.59237),
+        ];
+        for (value, from, to, expected) in masses {
+            let there = Mass::new(value, from).get(to);
+            assert!(close(there, expected), "{} {} -> {} {}", value, from.symbol(), there, to.symbol());
+            assert_round_trip("Mass", to.symbol(), from.symbol(), value, Mass::new(there, to).get(from));
+        }
+
+        let volumes = [
+            (1.0, VolumeUnit::Gallon, VolumeUnit::FluidOunce, 128.0),
+            (1.0, VolumeUnit::Cup, VolumeUnit::Tablespoon, 16.0),
+            (1.0, VolumeUnit::Liter, VolumeUnit::CubicCentimeter, 1000.0),
+            (1.0, VolumeUnit::ImperialGallon, VolumeUnit::Liter, 4.54609),
+        ];
+        for (value, from, to, expected) in volumes {
+            let there = Volume::new(value, from).get(to);
+            assert!(close(there, expected), "{} {} -> {} {}", value, from.symbol(), there, to.symbol());
+            assert_round_trip("Volume", to.symbol(), from.symbol(), value, Volume::new(there, to).get(from));
+        }
+
+        let angles = [
+            (1.0, AngleUnit::Turn, AngleUnit::Degree, 360.0),
+            (90.0, AngleUnit::Degree, AngleUnit::Gradian, 100.0),
+            (1.0, AngleUnit::Degree, AngleUnit::ArcSecond, 3600.0),
+            (std::f64::consts::PI, AngleUnit::Radian, AngleUnit::Turn, 0.5),
+        ];
+        for (value, from, to, expected) in angles {
+            let there = Angle::new(value, from).get(to);
+            assert!(close(there, expected), "{} {} -> {} {}", value, from.symbol(), there, to.symbol());
+            assert_round_trip("Angle", to.symbol(), from.symbol(), value, Angle::new(there, to).get(from));
+        }
+    }
+
+    #[test]
+    fn linear_every_pair_round_trips() {
+        check_linear!(Mass, MassUnit, MassUnit::Kilogram);
+        check_linear!(Volume, VolumeUnit, VolumeUnit::Liter);
+        check_linear!(Angle, AngleUnit, AngleUnit::Radian);
+    }
+
+    #[test]
+    fn temperature_known_answers_round_trip() {
+        use TemperatureUnit::{Celsius, Fahrenheit, Kelvin};
+        let table = [
+            (100.0, Celsius, Fahrenheit, 212.0),
+            (0.0, Celsius, Kelvin, 273.15),
+            (-40.0, Celsius, Fahrenheit, -40.0),
+            (98.6, Fahrenheit, Celsius, 37.0),
+            (0.0, Kelvin, Fahrenheit, -459.67),
+            (0.0, Fahrenheit, Celsius, -160.0 / 9.0),
+        ];
+        for (value, from, to, expected) in table {
+            let there = Temperature::new(value, from).unwrap().get(to);
+            assert!(close(there, expected), "{} {} -> {} {}", value, from.symbol(), there, to.symbol());
+            let back = Temperature::new(there, to).unwrap().get(from);
+            assert!(close(back, value), "{} {} -> {} -> {}", value, from.symbol(), to.symbol(), back);
+        }
+    }
+
+    #[test]
+    fn temperature_deltas_scale_without_offset() {
+        use TemperatureUnit::{Celsius, Fahrenheit, Kelvin};
+        let table = [(10.0, Celsius, Fahrenheit, 18.0), (9.0, Fahrenheit, Kelvin, 5.0), (1.0, Kelvin, Celsius, 1.0)];
+        for (value, from, to, expected) in table {
+            let there = TemperatureDelta::new(value, from).get(to);
+            assert!(close(there, expected));
+            assert!(close(TemperatureDelta::new(there, to).get(from), value));
+        }
+    }
+
+    #[test]
+    fn checked_add_stops_at_absolute_zero() {
+        use TemperatureUnit::{Celsius, Kelvin};
+        let one_kelvin = Temperature::new(1.0, Kelvin).unwrap();
+        assert_eq!(one_kelvin.checked_add(TemperatureDelta::new(-1.0, Celsius)), Ok(Temperature(0.0)));
+        assert_eq!(
+            one_kelvin.checked_add(TemperatureDelta::new(-5.0, Celsius)),
+            Err(TemperatureError::BelowAbsoluteZero(-4.0))
+        );
+        assert_eq!(one_kelvin.checked_add(TemperatureDelta(f64::NAN)), Err(TemperatureError::NotANumber));
+    }
+
+    #[test]
+    fn data_size_known_answers_round_trip() {
+        let table = [
+            (1, DataUnit::Kilobyte, 1_000),
+            (1, DataUnit::Kibibyte, 1_024),
+            (3, DataUnit::Mebibyte, 3 << 20),
+            (2, DataUnit::Petabyte, 2_000_000_000_000_000),
+            (16_383, DataUnit::Pebibyte, 16_383 << 50),
+        ];
+        for (value, unit, bytes) in table {
+            let size = DataSize::new(value, unit).unwrap();
+            assert_eq!(size, DataSize(bytes));
+            assert_eq!(size.whole(unit), (value, 0));
+        }
+        assert_eq!(DataSize::new(16_384, DataUnit::Pebibyte), None);
+    }
+}
@@ -0,0 +1,461 @@
+// cargo run --example m5_serde_csv
+// cargo run --example m5_serde_csv --features serde
+//
+// Demonstrates saving and loading the example data types.
+//
+// CSV: a small hand-written reader/writer. Each struct maps to one row;
+// the first row is a header that must match the struct's field names.
+// Errors say which row (line) was wrong and why.
+//
+// JSON: with the optional `serde` cargo feature, the same structs also
+// derive Serialize/Deserialize. cfg_attr only adds the derive when the
+// feature is on, so the default build has no extra dependencies:
+//
+//   #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+//
+// The types are the shared ones from examples/common, so what is saved is
+// exactly what m5_derive, m5_methods, m5_structs and m5_builder use. Their
+// checks apply on the way back in: a username or email must parse again,
+// and a row only becomes a Server if it passes Server::validate. The CSV
+// has no column for a User's password credential (m5_structs_password);
+// users read from it have none.
+//
+// cargo test --example m5_serde_csv runs the round-trip tests.
+
+use std::fmt;
+use std::str::FromStr;
+
+// The data types from the Module 5 examples
+#[path = "common/shapes.rs"]
+mod shapes;
+use shapes::{Point, Rectangle, SimplePoint};
+
+#[path = "common/users.rs"]
+mod users;
+use users::User;
+
+#[path = "common/server.rs"]
+mod server;
+use server::Server;
+
+// ---------------------------------------------------------------------
+// CSV errors
+// ---------------------------------------------------------------------
+
+#[derive(Debug, PartialEq)]
+enum CsvErrorKind {
+    MissingHeader,
+    HeaderMismatch { expected: Vec<String>, found: Vec<String> },
+    FieldCount { expected: usize, found: usize },
+    InvalidValue { column: &'static str, value: String },
+    UnterminatedQuote,
+    TextAfterQuote,
+    Invalid(String),
+}
+
+// An error plus the 1-based line where its row starts
+#[derive(Debug, PartialEq)]
+struct CsvError {
+    line: usize,
+    kind: CsvErrorKind,
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            CsvErrorKind::MissingHeader => write!(f, "missing header row"),
+            CsvErrorKind::HeaderMismatch { expected, found } => {
+                write!(f, "header should be {:?}, found {:?}", expected, found)
+            }
+            CsvErrorKind::FieldCount { expected, found } => {
+                write!(f, "expected {} fields, found {}", expected, found)
+            }
+            CsvErrorKind::InvalidValue { column, value } => {
+                write!(f, "invalid value {:?} in column '{}'", value, column)
+            }
+            CsvErrorKind::UnterminatedQuote => write!(f, "unterminated quoted field"),
+            CsvErrorKind::TextAfterQuote => write!(f, "text after a closing quote"),
+            CsvErrorKind::Invalid(reason) => write!(f, "invalid row: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+// ---------------------------------------------------------------------
+// Mapping structs to rows
+// ---------------------------------------------------------------------
+
+// A type that can be one CSV row
+trait CsvRecord: Sized {
+    const HEADERS: &'static [&'static str];
+    fn to_row(&self) -> Vec<String>;
+    fn from_row(row: &[String], line: usize) -> Result<Self, CsvError>;
+}
+
+// Parse one cell, naming the column in the error
+fn cell<T: FromStr>(value: &str, column: &'static str, line: usize) -> Result<T, CsvError> {
+    value.parse().map_err(|_| CsvError {
+        line,
+        kind: CsvErrorKind::InvalidValue { column, value: value.to_string() },
+    })
+}
+
+// Every field is Display + FromStr, so one macro covers all five types.
+// Fields with no column follow the `;` with their value, and
+// `validate = path` checks the finished value, as a builder would.
+macro_rules! csv_record {
+    (
+        $Type:ident { $($field:ident),+ $(; $($fixed:ident: $value:expr),+)? }
+        $(validate = $validate:path)?
+    ) => {
+        impl CsvRecord for $Type {
+            const HEADERS: &'static [&'static str] = &[$(stringify!($field)),+];
+
+            fn to_row(&self) -> Vec<String> {
+                vec![$(self.$field.to_string()),+]
+            }
+
+            // The caller has checked that the row has one cell per field
+            fn from_row(row: &[String], line: usize) -> Result<Self, CsvError> {
+                let mut cells = row.iter();
+                let value = $Type {
+                    $($field: cell(cells.next().unwrap(), stringify!($field), line)?,)+
+                    $($($fixed: $value,)+)?
+                };
+                $($validate(&value).map_err(|reason| CsvError { line, kind: CsvErrorKind::Invalid(reason) })?;)?
+                Ok(value)
+            }
+        }
+    };
+}
+
+csv_record!(Point { x, y });
+csv_record!(SimplePoint { x, y });
+csv_record!(Rectangle { x, y, width, height });
+csv_record!(User { username, email, active; credential: None });
+csv_record!(Server { host, port, max_connections, timeout_seconds } validate = Server::validate);
+
+// ---------------------------------------------------------------------
+// Reading and writing
+// ---------------------------------------------------------------------
+
+// Quote a field only when needed: commas, quotes or line breaks
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn write_csv<T: CsvRecord>(records: &[T]) -> String {
+    let mut out = T::HEADERS.join(",");
+    out.push('\n');
+    for record in records {
+        let row: Vec<String> = record.to_row().iter().map(|f| escape(f)).collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+// Split text into rows of fields (RFC 4180: "" inside quotes is a quote,
+// and quoted fields may span lines). Returns (starting line, fields).
+// A closing quote must end the field: `"ab"c` is an error.
+fn split_rows(text: &str) -> Result<Vec<(usize, Vec<String>)>, CsvError> {
+    let mut rows = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut after_quote = false;
+    let mut line = 1;
+    let mut row_start = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if after_quote {
+            after_quote = false;
+            if !matches!(c, ',' | '\n' | '\r') {
+                return Err(CsvError { line, kind: CsvErrorKind::TextAfterQuote });
+            }
+        }
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => {
+                in_quotes = false;
+                after_quote = true;
+            }
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            ('\r', false) if chars.peek() == Some(&'\n') => {}
+            ('\n', false) => {
+                fields.push(std::mem::take(&mut field));
+                // Skip blank lines
+                if !(fields.len() == 1 && fields[0].is_empty()) {
+                    rows.push((row_start, std::mem::take(&mut fields)));
+                }
+                fields.clear();
+                line += 1;
+                row_start = line;
+            }
+            _ => {
+                if c == '\n' {
+                    line += 1; // newline inside a quoted field
+                }
+                field.push(c);
+            }
+        }
+    }
+    if in_quotes {
+        return Err(CsvError { line: row_start, kind: CsvErrorKind::UnterminatedQuote });
+    }
+    // Last row without a trailing newline
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        rows.push((row_start, fields));
+    }
+    Ok(rows)
+}
+
+fn read_csv<T: CsvRecord>(text: &str) -> Result<Vec<T>, CsvError> {
+    let mut rows = split_rows(text)?.into_iter();
+
+    let (line, header) = rows
+        .next()
+        .ok_or(CsvError { line: 1, kind: CsvErrorKind::MissingHeader })?;
+    let header: Vec<String> = header.iter().map(|h| h.trim().to_string()).collect();
+    if header != T::HEADERS {
+        return Err(CsvError {
+            line,
+            kind: CsvErrorKind::HeaderMismatch {
+                expected: T::HEADERS.iter().map(|h| h.to_string()).collect(),
+                found: header,
+            },
+        });
+    }
+
+    rows.map(|(line, row)| {
+        if row.len() != T::HEADERS.len() {
+            return Err(CsvError {
+                line,
+                kind: CsvErrorKind::FieldCount { expected: T::HEADERS.len(), found: row.len() },
+            });
+        }
+        T::from_row(&row, line)
+    })
+    .collect()
+}
+
+// Write, read back, and check nothing changed
+fn csv_round_trip<T: CsvRecord + PartialEq + fmt::Debug>(name: &str, records: &[T]) {
+    let text = write_csv(records);
+    let back: Vec<T> = read_csv(&text).expect("round trip");
+    assert_eq!(back, records);
+    println!("{} ({} rows) round-trips:\n{}", name, records.len(), text);
+}
+
+#[cfg(feature = "serde")]
+fn json_round_trip<T>(name: &str, records: &[T])
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + fmt::Debug,
+{
+    let json = serde_json::to_string(records).expect("serialize");
+    let back: Vec<T> = serde_json::from_str(&json).expect("deserialize");
+    assert_eq!(back, records);
+    println!("  {:<12} {}", name, json);
+}
+
+fn main() {
+    let points = vec![Point { x: 1, y: 2 }, Point { x: -3, y: 4 }];
+    let simple = vec![SimplePoint { x: 10, y: 20 }];
+    let rects = vec![
+        Rectangle { x: 0, y: 0, width: 30, height: 50 },
+        Rectangle { x: -5, y: 12, width: 10, height: 10 },
+    ];
+    let mut pat = User::new("pat_obrien", "o'brien@example.com").expect("valid user");
+    pat.active = false;
+    let users = vec![User::new("someuser", "user@example.com").expect("valid user"), pat];
+    let servers = vec![
+        Server::builder().host("0.0.0.0").port(3000).max_connections(1000).timeout(60).build().expect("valid"),
+        // Commas, quotes and newlines survive thanks to quoting
+        Server::builder().host("rack \"b\", row 2\nslot 7").build().expect("valid"),
+    ];
+
+    println!("=== CSV ===\n");
+    csv_round_trip("Point", &points);
+    csv_round_trip("SimplePoint", &simple);
+    csv_round_trip("Rectangle", &rects);
+    csv_round_trip("User", &users);
+    csv_round_trip("Server", &servers);
+
+    println!("=== CSV errors ===");
+    let bad_inputs = [
+        ("", "empty file"),
+        ("x,z\n1,2\n", "wrong header"),
+        ("x,y\n1,2\n3\n", "missing field"),
+        ("x,y\n1,2\n\n5,abc\n", "not a number (after a blank line)"),
+        ("x,y\n\"1,2\n", "unterminated quote"),
+        ("x,y\n\"1\"2,3\n", "text after a closing quote"),
+    ];
+    for (input, what) in bad_inputs {
+        let err = read_csv::<Point>(input).unwrap_err();
+        println!("  {:<34} -> {}", what, err);
+    }
+    assert_eq!(read_csv::<Point>("x,y\n1,2\n\n5,abc\n").unwrap_err().line, 4);
+    let err = read_csv::<Server>("host,port,max_connections,timeout_seconds\nlocalhost,70000,1,1\n").unwrap_err();
+    println!("  {:<34} -> {}", "port out of range", err);
+    let err = read_csv::<Server>("host,port,max_connections,timeout_seconds\nlocalhost,80,0,1\n").unwrap_err();
+    println!("  {:<34} -> {}", "fails Server::validate", err);
+    let err = read_csv::<User>("username,email,active\n9 lives,cat@example.com,true\n").unwrap_err();
+    println!("  {:<34} -> {}", "not a valid Username", err);
+
+    #[cfg(feature = "serde")]
+    {
+        println!("\n=== JSON (serde feature) ===");
+        json_round_trip("Point", &points);
+        json_round_trip("SimplePoint", &simple);
+        json_round_trip("Rectangle", &rects);
+        json_round_trip("User", &users);
+        json_round_trip("Server", &servers);
+    }
+    #[cfg(not(feature = "serde"))]
+    println!("\nJSON: run with --features serde to enable Serialize/Deserialize");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T: CsvRecord + PartialEq + fmt::Debug>(records: &[T]) {
+        let text = write_csv(records);
+        assert_eq!(read_csv::<T>(&text).as_deref(), Ok(records), "{}", text);
+    }
+
+    fn user(username: &str, email: &str, active: bool) -> User {
+        User { active, ..User::new(username, email).unwrap() }
+    }
+
+    fn server(host: &str) -> Server {
+        Server::builder().host(host).build().unwrap()
+    }
+
+    fn error_kind<T: CsvRecord + fmt::Debug>(text: &str) -> (usize, CsvErrorKind) {
+        let err = read_csv::<T>(text).unwrap_err();
+        (err.line, err.kind)
+    }
+
+    #[test]
+    fn every_type_round_trips() {
+        round_trip(&[Point { x: i32::MIN, y: i32::MAX }, Point { x: 0, y: -1 }]);
+        round_trip(&[SimplePoint { x: 7, y: -7 }]);
+        round_trip(&[Rectangle { x: -3, y: 4, width: u32::MAX, height: 0 }]);
+        round_trip(&[user("alice", "a@example.com", true), user("bob", "b@example.com", false)]);
+        round_trip(&[Server { host: String::from("::1"), port: u16::MAX, max_connections: 1, timeout_seconds: 30 }]);
+        round_trip::<Point>(&[]);
+    }
+
+    #[test]
+    fn awkward_strings_round_trip() {
+        let names = [
+            "comma, here",
+            "\"quoted\"",
+            "\"",
+            "\"\"",
+            "line\nbreak",
+            "crlf\r\nbreak",
+            " padded ",
+            "",
+            "trailing quote\"",
+            "unicode: żółw 🐢",
+        ];
+        let servers: Vec<Server> = names.iter().map(|n| server(n)).collect();
+        round_trip(&servers);
+    }
+
+    #[test]
+    fn crlf_files_and_blank_lines_are_accepted() {
+        let points = read_csv::<Point>("x,y\r\n1,2\r\n\r\n3,4").unwrap();
+        assert_eq!(points, [Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]);
+    }
+
+    #[test]
+    fn errors_name_the_line_of_the_row() {
+        assert_eq!(error_kind::<Point>(""), (1, CsvErrorKind::MissingHeader));
+        assert_eq!(
+            error_kind::<Point>("x,y\n1,2\n3\n"),
+            (3, CsvErrorKind::FieldCount { expected: 2, found: 1 })
+        );
+        // A quoted field spanning lines: the row starts on line 2
+        assert_eq!(
+            error_kind::<Server>("host,port,max_connections,timeout_seconds\n\"multi\nline\",1,2,maybe\n"),
+            (2, CsvErrorKind::InvalidValue { column: "timeout_seconds", value: String::from("maybe") })
+        );
+        assert_eq!(error_kind::<Point>("x,y\n\"1,2\n"), (2, CsvErrorKind::UnterminatedQuote));
+    }
+
+    #[test]
+    fn text_after_a_closing_quote_is_rejected() {
+        for text in ["x,y\n\"1\"2,3\n", "x,y\n1,\"2\" \n", "x,y\n\"\"\"\"x,1\n", "x,y\n\"1\"\"2\"x,1\n"] {
+            assert_eq!(error_kind::<Point>(text), (2, CsvErrorKind::TextAfterQuote), "{:?}", text);
+        }
+        // ...but a quoted field may be followed by a comma or the end of the line
+        let text = "username,email,active\n\"alice\",\"a@example.com\",\"true\"\r\n\"carol\",c@example.com,false";
+        let users = read_csv::<User>(text).unwrap();
+        assert_eq!(users, [user("alice", "a@example.com", true), user("carol", "c@example.com", false)]);
+    }
+
+    #[test]
+    fn user_rows_go_through_the_newtypes() {
+        let header = "username,email,active\n";
+        let (line, kind) = error_kind::<User>(&format!("{}alice,a@example.com,true\n9 lives,x@example.com,true\n", header));
+        assert_eq!(line, 3);
+        assert_eq!(kind, CsvErrorKind::InvalidValue { column: "username", value: String::from("9 lives") });
+        let (_, kind) = error_kind::<User>(&format!("{}alice,not-an-email,true\n", header));
+        assert!(matches!(kind, CsvErrorKind::InvalidValue { column: "email", .. }));
+        // A user read back has no credential
+        let users = read_csv::<User>(&format!("{}alice,a@example.com,true\n", header)).unwrap();
+        assert_eq!(users[0].credential, None);
+        assert_eq!((users[0].username.as_ref(), users[0].email.as_ref()), ("alice", "a@example.com"));
+    }
+
+    #[test]
+    fn server_rows_are_validated() {
+        let text = "host,port,max_connections,timeout_seconds\nlocalhost,80,1,1\nlocalhost,80,0,1\n";
+        assert_eq!(
+            error_kind::<Server>(text),
+            (3, CsvErrorKind::Invalid(String::from("max_connections must be at least 1")))
+        );
+    }
+
+    #[test]
+    fn rectangle_header_includes_the_origin() {
+        assert_eq!(Rectangle::HEADERS, ["x", "y", "width", "height"]);
+        let (line, kind) = error_kind::<Rectangle>("width,height\n30,50\n");
+        assert_eq!(line, 1);
+        assert!(matches!(kind, CsvErrorKind::HeaderMismatch { .. }));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trips() {
+        fn check<T>(records: &[T])
+        where
+            T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + fmt::Debug,
+        {
+            let json = serde_json::to_string(records).unwrap();
+            assert_eq!(serde_json::from_str::<Vec<T>>(&json).unwrap(), records);
+        }
+        check(&[Point { x: 1, y: 2 }]);
+        check(&[SimplePoint { x: 1, y: 2 }]);
+        check(&[Rectangle { x: -1, y: 2, width: 3, height: 4 }]);
+        check(&[user("obrien", "o'brien@example.com", true)]);
+        check(&[server("h")]);
+        let json = serde_json::to_string(&Rectangle { x: 1, y: 2, width: 3, height: 4 }).unwrap();
+        assert_eq!(json, r#"{"x":1,"y":2,"width":3,"height":4}"#);
+    }
+}
@@ -2,21 +2,38 @@
 //
 // Demonstrates structs - custom data types that group related values.
 // Structs are similar to classes in other languages (but without inheritance).
+//
+// See m5_structs_validated for how Username and EmailAddress check their text.
+// See m5_structs_password for storing and checking passwords.
 
-// Define a struct with named fields
-struct User {
-    username: String,
-    email: String,
-    active: bool,
-}
+// Username and EmailAddress are validated newtypes: wrappers around a
+// String that can only be made by a constructor that checks the text.
+// They live in common/users.rs, shared with m5_structs_validated.
+#[path = "common/users.rs"]
+mod users;
+
+// The struct itself is defined in common/users.rs like this:
+//
+//     struct User {
+//         username: Username,
+//         email: EmailAddress,
+//         active: bool,
+//         credential: Option<Credential>, // see m5_structs_password
+//     }
+//
+// Plain Strings would accept any text, and passing the email where the
+// username goes would still compile. With --features serde it can also
+// be saved as JSON; reading it back checks both fields again.
+use users::{EmailAddress, User, UserError, Username};
 
-fn main() {
+fn main() -> Result<(), UserError> {
     // Create an instance of the struct
     // All fields must be initialized
     let user1 = User {
-        email: String::from("user@example.com"),
-        username: String::from("someuser"),
+        email: EmailAddress::parse("user@example.com")?,
+        username: Username::parse("someuser")?,
         active: true,
+        credential: None,
     };
 
     // Access fields with dot notation
@@ -26,8 +43,8 @@ fn main() {
     // Struct Update Syntax: create new struct reusing fields from another
     // The ..user1 copies remaining fields from user1
     let user2 = User {
-        email: String::from("other@example.com"),  // New email
-        ..user1  // Copy username and active from user1
+        email: EmailAddress::parse("other@example.com")?, // New email
+        ..user1 // Copy username and active from user1
     };
     // Note: user1.username was moved to user2, so user1 is partially invalid now
 
@@ -35,13 +52,38 @@ fn main() {
 
     // Mutable struct - the entire struct must be marked mut
     let mut user3 = User {
-        email: String::from("mutable@example.com"),
-        username: String::from("mutableuser"),
+        email: EmailAddress::parse("mutable@example.com")?,
+        username: Username::parse("mutableuser")?,
         active: false,
+        credential: None,
     };
 
     // Now we can modify fields
     user3.active = true;
-    user3.email = String::from("updated@example.com");
+    user3.email = EmailAddress::parse("updated@example.com")?;
     println!("\nUser3 (modified): {} - active: {}", user3.email, user3.active);
+
+    // Bad text is caught when the field is made, not later
+    let bad = Username::parse("9 lives");
+    println!("\nUsername \"9 lives\": {}", bad.as_ref().unwrap_err());
+    assert!(bad.is_err());
+
+    #[cfg(feature = "serde")]
+    json_round_trip(&user3);
+    Ok(())
+}
+
+// With --features serde: the newtypes are written as plain strings and
+// parsed again on the way back in
+#[cfg(feature = "serde")]
+fn json_round_trip(user: &User) {
+    let json = serde_json::to_string(user).expect("serialize");
+    println!("\nJSON: {}", json);
+    assert_eq!(json, r#"{"username":"mutableuser","email":"updated@example.com","active":true}"#);
+    let back: User = serde_json::from_str(&json).expect("deserialize");
+    assert_eq!(&back, user);
+
+    let invalid = r#"{"username":"x","email":"updated@example.com","active":true}"#;
+    let err = serde_json::from_str::<User>(invalid).unwrap_err();
+    println!("Rejected: {}", err);
 }
@@ -0,0 +1,695 @@
+// cargo run --example m5_structs_jsonl
+//
+// Demonstrates SAVING USERS TO A FILE as JSON Lines (one JSON object per
+// line), so the users from m5_structs outlive the process.
+//
+//   - Every change APPENDS a line: a "put" with the whole user, or a
+//     "delete" with just the id. Opening the file replays the lines in
+//     order, and the last line for an id wins.
+//   - Every line carries a schema version "v". Older lines are upgraded
+//     on load by a chain of migrations, one per version step.
+//   - compact() rewrites the file with one current-version line per
+//     live user. The new file is written next to the old one and then
+//     renamed over it, so a crash leaves one file or the other.
+//   - A line that cannot be read, or that gives a second user a taken
+//     username or email, stops the load with its line number, instead
+//     of silently dropping a user. The exception is a torn write: a
+//     last line without its newline that does not parse is what a crash
+//     mid-append leaves, and open() cuts it off.
+//
+// The store implements UserStore from m5_structs_repository, so the same
+// UserRepository (unique names, active/inactive rules) runs on top of it,
+// with the Username and EmailAddress newtypes from m5_structs_validated.
+// Both are shared through examples/common.
+// The JSON is written and read by hand, as in m5_serde_csv, so the
+// example needs no dependencies. Records are flat: strings, whole
+// numbers and booleans.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+#[path = "common/users.rs"]
+mod users;
+#[path = "common/repository.rs"]
+mod repository;
+
+use repository::{StorageError, UserId, UserRepository, UserStore, UserUpdate};
+use users::credential::Credential;
+use users::{EmailAddress, User, Username};
+
+// ---------------------------------------------------------------------
+// Flat JSON
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Str(String),
+    Int(u64),
+    Bool(bool),
+}
+
+type Record = HashMap<String, Value>;
+
+fn write_string(out: &mut String, text: &str) {
+    out.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+}
+
+// Fields are written in the order given, so lines are easy to read
+fn write_record(fields: &[(&str, Value)]) -> String {
+    let mut out = String::from("{");
+    for (i, (key, value)) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_string(&mut out, key);
+        out.push(':');
+        match value {
+            Value::Str(text) => write_string(&mut out, text),
+            Value::Int(n) => out.push_str(&n.to_string()),
+            Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        }
+    }
+    out.push('}');
+    out
+}
+
+// Reads one object of flat fields. Errors give the byte column.
+struct JsonReader<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl JsonReader<'_> {
+    fn parse(text: &str) -> Result<Record, String> {
+        let mut reader = JsonReader { text, pos: 0 };
+        let record = reader.object()?;
+        reader.skip_space();
+        if reader.pos < text.len() {
+            return Err(reader.error("unexpected text after the object"));
+        }
+        Ok(record)
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("{} (column {})", message, self.pos + 1)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn skip_space(&mut self) {
+        while let Some(ch) = self.peek().filter(|ch| ch.is_ascii_whitespace()) {
+            self.pos += ch.len_utf8();
+        }
+    }
+
+    fn expect(&mut self, wanted: char) -> Result<(), String> {
+        self.skip_space();
+        if self.peek() == Some(wanted) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", wanted)))
+        }
+    }
+
+    fn object(&mut self) -> Result<Record, String> {
+        self.expect('{')?;
+        let mut record = Record::new();
+        self.skip_space();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(record);
+        }
+        loop {
+            self.skip_space();
+            let key_at = self.pos;
+            let key = self.string()?;
+            self.expect(':')?;
+            let value = self.value()?;
+            if record.insert(key.clone(), value).is_some() {
+                self.pos = key_at;
+                return Err(self.error(&format!("field {:?} appears twice", key)));
+            }
+            self.skip_space();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(record);
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_space();
+        let rest = &self.text[self.pos..];
+        match self.peek() {
+            Some('"') => Ok(Value::Str(self.string()?)),
+            Some('0'..='9') => {
+                let len = rest.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(rest.len());
+                let number = rest[..len].parse().map_err(|_| self.error("number is too large"))?;
+                self.pos += len;
+                Ok(Value::Int(number))
+            }
+            _ if rest.starts_with("true") => {
+                self.pos += 4;
+                Ok(Value::Bool(true))
+            }
+            _ if rest.starts_with("false") => {
+                self.pos += 5;
+                Ok(Value::Bool(false))
+            }
+            None => Err(self.error("line ends in the middle of the object")),
+            _ => Err(self.error("expected a string, whole number or boolean")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if self.peek() != Some('"') {
+            return Err(self.error("expected '\"'"));
+        }
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            let ch = self.peek().ok_or_else(|| self.error("string is not closed"))?;
+            self.pos += ch.len_utf8();
+            match ch {
+                '"' => return Ok(out),
+                '\\' => {
+                    let escaped = self.peek().ok_or_else(|| self.error("string is not closed"))?;
+                    self.pos += 1;
+                    match escaped {
+                        '"' => out.push('"'),
+                        '\\' => out.push('\\'),
+                        '/' => out.push('/'),
+                        'n' => out.push('\n'),
+                        'r' => out.push('\r'),
+                        't' => out.push('\t'),
+                        'u' => {
+                            let ch = self.unicode_escape()?;
+                            out.push(ch);
+                        }
+                        _ => return Err(self.error("unknown escape")),
+                    }
+                }
+                _ => out.push(ch),
+            }
+        }
+    }
+
+    // The 4 hex digits after "\u". Characters outside the Basic
+    // Multilingual Plane are written as a UTF-16 surrogate pair,
+    // "\ud83d\ude00" for U+1F600, and only make sense together.
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let high = self.hex4()?;
+        let code = match high {
+            0xD800..=0xDBFF => {
+                if !self.text[self.pos..].starts_with("\\u") {
+                    return Err(self.error("\\u escape is half of a surrogate pair"));
+                }
+                self.pos += 2;
+                let low = self.hex4()?;
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err(self.error("\\u escape is half of a surrogate pair"));
+                }
+                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+            }
+            0xDC00..=0xDFFF => return Err(self.error("\\u escape is half of a surrogate pair")),
+            _ => high,
+        };
+        char::from_u32(code).ok_or_else(|| self.error("bad \\u escape"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let hex = self.text.get(self.pos..self.pos + 4).unwrap_or("");
+        if !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            return Err(self.error("bad \\u escape"));
+        }
+        let code = u32::from_str_radix(hex, 16).map_err(|_| self.error("bad \\u escape"))?;
+        self.pos += 4;
+        Ok(code)
+    }
+}
+
+// ---------------------------------------------------------------------
+// Schema versions
+// ---------------------------------------------------------------------
+//
+//   v1  {"v":1,"id":1,"username":"alice","email":"a@example.com"}
+//       The first files had no "v" at all; those are v1 too.
+//   v2  adds "active". Every v1 user was active.
+//   v3  adds "op": "put" or "delete". Before v3 users were never
+//       deleted, so every older line is a put.
+//   v4  adds an optional "credential": the password hash as a PHC
+//       string (see m5_structs_password). Left out when the user has
+//       no password, which is every user before v4.
+
+const CURRENT_VERSION: u64 = 4;
+
+// MIGRATIONS[n] upgrades a record from version n + 1 to n + 2
+const MIGRATIONS: [fn(&mut Record); (CURRENT_VERSION - 1) as usize] = [v1_to_v2, v2_to_v3, v3_to_v4];
+
+fn v1_to_v2(record: &mut Record) {
+    record.insert("active".to_string(), Value::Bool(true));
+}
+
+fn v2_to_v3(record: &mut Record) {
+    record.insert("op".to_string(), Value::Str("put".to_string()));
+}
+
+// A missing "credential" already means "no password"
+fn v3_to_v4(_record: &mut Record) {}
+
+// What one line asks for, once it is upgraded to the current version
+#[derive(Debug, PartialEq)]
+enum Entry {
+    Put(UserId, User),
+    Delete(UserId),
+}
+
+fn put_line(id: UserId, user: &User) -> String {
+    let mut fields = vec![
+        ("v", Value::Int(CURRENT_VERSION)),
+        ("op", Value::Str("put".to_string())),
+        ("id", Value::Int(id.0)),
+        ("username", Value::Str(user.username.to_string())),
+        ("email", Value::Str(user.email.to_string())),
+        ("active", Value::Bool(user.active)),
+    ];
+    if let Some(credential) = &user.credential {
+        fields.push(("credential", Value::Str(credential.to_phc())));
+    }
+    write_record(&fields)
+}
+
+fn delete_line(id: UserId) -> String {
+    write_record(&[
+        ("v", Value::Int(CURRENT_VERSION)),
+        ("op", Value::Str("delete".to_string())),
+        ("id", Value::Int(id.0)),
+    ])
+}
+
+// Parse, migrate and check one line. Returns the entry and the version
+// the line was written in.
+fn read_line(text: &str) -> Result<(Entry, u64), String> {
+    let mut record = JsonReader::parse(text)?;
+    let version = match record.remove("v") {
+        None => 1,
+        Some(Value::Int(v)) if (1..=CURRENT_VERSION).contains(&v) => v,
+        Some(Value::Int(v)) => return Err(format!("schema version {} is not supported (newest is {})", v, CURRENT_VERSION)),
+        Some(_) => return Err("field \"v\" should be a whole number".to_string()),
+    };
+    for migrate in &MIGRATIONS[version as usize - 1..] {
+        migrate(&mut record);
+    }
+
+    let id = UserId(match record.remove("id") {
+        Some(Value::Int(id)) => id,
+        _ => return Err("field \"id\" is missing or not a whole number".to_string()),
+    });
+    let mut text_field = |name: &str| match record.remove(name) {
+        Some(Value::Str(text)) => Ok(text),
+        _ => Err(format!("field {:?} is missing or not a string", name)),
+    };
+    let entry = match text_field("op")?.as_str() {
+        "delete" => Entry::Delete(id),
+        "put" => {
+            let username = Username::parse(&text_field("username")?).map_err(|e| e.to_string())?;
+            let email = EmailAddress::parse(&text_field("email")?).map_err(|e| e.to_string())?;
+            let active = match record.remove("active") {
+                Some(Value::Bool(active)) => active,
+                _ => return Err("field \"active\" is missing or not a boolean".to_string()),
+            };
+            let credential = match record.remove("credential") {
+                None => None,
+                Some(Value::Str(phc)) => Some(Credential::parse_phc(&phc).map_err(|e| format!("credential: {}", e))?),
+                Some(_) => return Err("field \"credential\" is not a string".to_string()),
+            };
+            Entry::Put(id, User { username, email, active, credential })
+        }
+        op => return Err(format!("unknown op {:?}", op)),
+    };
+    Ok((entry, version))
+}
+
+// ---------------------------------------------------------------------
+// File store
+// ---------------------------------------------------------------------
+
+// All users are kept in memory; the file is the log of how they got there
+struct JsonlStore {
+    path: PathBuf,
+    file: File, // opened for appending
+    users: BTreeMap<UserId, User>,
+    // Folded username / email -> id, as in MemoryStore, so neither lookups
+    // nor the clash check on open scan every user
+    by_username: HashMap<String, UserId>,
+    by_email: HashMap<String, UserId>,
+    lines: usize,
+    migrated: usize, // lines that were read in an older version
+}
+
+impl JsonlStore {
+    // A missing file is an empty store; it is created on open
+    fn open(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let path = path.as_ref().to_path_buf();
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let mut store = JsonlStore {
+            path,
+            file,
+            users: BTreeMap::new(),
+            by_username: HashMap::new(),
+            by_email: HashMap::new(),
+            lines: 0,
+            migrated: 0,
+        };
+        // split_inclusive keeps each line's '\n', so the one line that may
+        // lack it, the last, can be told apart
+        for (index, raw) in text.split_inclusive('\n').enumerate() {
+            let corrupt = |reason: String| StorageError::Corrupt { line: index + 1, reason };
+            let (line, terminated) = match raw.strip_suffix('\n') {
+                Some(line) => (line.strip_suffix('\r').unwrap_or(line), true),
+                None => (raw, false),
+            };
+            if line.trim().is_empty() {
+                store.lines += 1;
+                continue;
+            }
+            let (entry, version) = match read_line(line) {
+                Ok(read) => read,
+                // append() writes a line and its newline together, so an
+                // unterminated last line that does not parse is a write a
+                // crash cut short. Nothing after it was written: drop it.
+                Err(_) if !terminated => {
+                    store.file.set_len((text.len() - raw.len()) as u64)?;
+                    return Ok(store);
+                }
+                Err(reason) => return Err(corrupt(reason)),
+            };
+            store.lines += 1;
+            if version < CURRENT_VERSION {
+                store.migrated += 1;
+            }
+            match entry {
+                Entry::Put(id, user) => {
+                    // The repository never lets two users share a name or
+                    // an email, so a file where they do was edited by hand
+                    if let Some(other) = store.clash(id, &user) {
+                        return Err(corrupt(other));
+                    }
+                    store.insert(id, user);
+                }
+                Entry::Delete(id) => {
+                    if store.forget(id).is_none() {
+                        return Err(corrupt(format!("deletes user {}, who does not exist", id)));
+                    }
+                }
+            }
+        }
+        // The last line may also be whole but miss its newline (the crash
+        // came between the two). It parsed, so finish it before appending
+        // more.
+        if !text.is_empty() && !text.ends_with('\n') {
+            store.file.write_all(b"\n")?;
+        }
+        Ok(store)
+    }
+
+    fn append(&mut self, line: String) -> Result<(), StorageError> {
+        // One write per line, so a crash cuts at most the last line short;
+        // open() drops such a line
+        self.file.write_all(format!("{}\n", line).as_bytes())?;
+        self.file.sync_data()?;
+        self.lines += 1;
+        Ok(())
+    }
+
+    // Lines that no longer describe a live user (older puts, deletes)
+    fn stale_lines(&self) -> usize {
+        self.lines - self.users.len()
+    }
+
+    // Rewrite the file with one current-version line per user
+    fn compact(&mut self) -> Result<(), StorageError> {
+        let temp = self.path.with_extension("jsonl.tmp");
+        let mut out = File::create(&temp)?;
+        for (id, user) in &self.users {
+            writeln!(out, "{}", put_line(*id, user))?;
+        }
+        out.sync_all()?;
+        fs::rename(&temp, &self.path)?;
+        self.file = OpenOptions::new().append(true).open(&self.path)?;
+        self.lines = self.users.len();
+        self.migrated = 0;
+        Ok(())
+    }
+
+    // Why `user` cannot be stored as `id`, if another user already has
+    // its username or email
+    fn clash(&self, id: UserId, user: &User) -> Option<String> {
+        let taken = |index: &HashMap<String, UserId>, key: &str| index.get(key).copied().filter(|other| *other != id);
+        if let Some(other) = taken(&self.by_username, user.username.folded()) {
+            return Some(format!("username {:?} is already used by user {}", user.username.as_ref(), other));
+        }
+        taken(&self.by_email, user.email.folded())
+            .map(|other| format!("email {:?} is already used by user {}", user.email.as_ref(), other))
+    }
+
+    // Keep the indexes in step with `users`. The old keys go first, in
+    // case the username or email changed.
+    fn insert(&mut self, id: UserId, user: User) {
+        self.forget(id);
+        self.by_username.insert(user.username.folded().to_string(), id);
+        self.by_email.insert(user.email.folded().to_string(), id);
+        self.users.insert(id, user);
+    }
+
+    fn forget(&mut self, id: UserId) -> Option<User> {
+        let user = self.users.remove(&id)?;
+        self.by_username.remove(user.username.folded());
+        self.by_email.remove(user.email.folded());
+        Some(user)
+    }
+}
+
+impl UserStore for JsonlStore {
+    fn get(&self, id: UserId) -> Result<Option<User>, StorageError> {
+        Ok(self.users.get(&id).cloned())
+    }
+
+    fn id_for_username(&self, folded: &str) -> Result<Option<UserId>, StorageError> {
+        Ok(self.by_username.get(folded).copied())
+    }
+
+    fn id_for_email(&self, folded: &str) -> Result<Option<UserId>, StorageError> {
+        Ok(self.by_email.get(folded).copied())
+    }
+
+    // The file is written first: if that fails, memory is unchanged
+    fn put(&mut self, id: UserId, user: User) -> Result<(), StorageError> {
+        self.append(put_line(id, &user))?;
+        self.insert(id, user);
+        Ok(())
+    }
+
+    fn remove(&mut self, id: UserId) -> Result<Option<User>, StorageError> {
+        if !self.users.contains_key(&id) {
+            return Ok(None);
+        }
+        self.append(delete_line(id))?;
+        Ok(self.forget(id))
+    }
+
+    fn largest_id(&self) -> Result<Option<UserId>, StorageError> {
+        Ok(self.users.keys().next_back().copied())
+    }
+}
+
+// ---------------------------------------------------------------------
+// Demo
+// ---------------------------------------------------------------------
+
+type Error = Box<dyn std::error::Error>;
+
+fn name(text: &str) -> Result<Username, Error> {
+    Ok(Username::try_from(text)?)
+}
+
+fn email(text: &str) -> Result<EmailAddress, Error> {
+    Ok(EmailAddress::try_from(text)?)
+}
+
+fn show_file(path: &Path) -> Result<(), Error> {
+    for (index, line) in fs::read_to_string(path)?.lines().enumerate() {
+        println!("  {:>2} | {}", index + 1, line);
+    }
+    Ok(())
+}
+
+fn all_users(store: &JsonlStore) -> Vec<(UserId, User)> {
+    store.users.iter().map(|(id, user)| (*id, user.clone())).collect()
+}
+
+fn main() -> Result<(), Error> {
+    let dir = std::env::temp_dir().join(format!("m5_structs_jsonl_{}", std::process::id()));
+    fs::create_dir_all(&dir)?;
+    let path = dir.join("users.jsonl");
+
+    println!("=== Loading a file from older versions ===\n");
+    let legacy = [
+        r#"{"id":1,"username":"alice","email":"alice@example.com"}"#,
+        r#"{"v":1,"id":2,"username":"bob","email":"bob@example.com"}"#,
+        r#"{"v":2,"id":3,"username":"carol","email":"carol@example.com","active":false}"#,
+        r#"{"v":3,"op":"put","id":4,"username":"dave","email":"dave@example.com","active":true}"#,
+        // "password", hashed with the salt "salt" (a known answer in m5_structs_password)
+        concat!(
+            r#"{"v":4,"op":"put","id":5,"username":"frank","email":"frank@example.com","active":true,"#,
+            r#""credential":"$pbkdf2-sha256$i=4096$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o"}"#
+        ),
+    ];
+    fs::write(&path, legacy.join("\n") + "\n")?;
+    show_file(&path)?;
+
+    let store = JsonlStore::open(&path)?;
+    println!("\n  loaded {} users, {} lines migrated", store.users.len(), store.migrated);
+    assert_eq!(store.migrated, 4);
+    assert!(store.users[&UserId(1)].active); // v1 had no "active"
+    assert!(!store.users[&UserId(3)].active);
+    assert!(store.users[&UserId(4)].credential.is_none()); // nor did anything before v4
+    assert!(store.users[&UserId(5)].credential.as_ref().is_some_and(|c| c.verify("password")));
+
+    println!("\n=== Changes through the repository ===\n");
+    let mut repo = UserRepository::new(store)?;
+    let erin = repo.create(name("Erin")?, email("erin@example.com")?)?;
+    repo.update(UserId(2), UserUpdate { email: Some(email("robert@example.com")?), ..Default::default() })?;
+    repo.deactivate(UserId(4))?;
+    repo.delete(UserId(4))?;
+    // The rules still hold, and a refused change writes nothing
+    let lines_before = repo.store().lines;
+    let refused = repo.create(name("ALICE")?, email("new@example.com")?);
+    println!("  create ALICE -> {}", refused.unwrap_err());
+    assert_eq!(repo.store().lines, lines_before);
+    show_file(&path)?;
+    let expected = all_users(repo.store());
+    drop(repo);
+
+    println!("\n=== Reopening ===\n");
+    let store = JsonlStore::open(&path)?;
+    assert_eq!(all_users(&store), expected);
+    println!("  {} users, as before; {} of {} lines are stale", store.users.len(), store.stale_lines(), store.lines);
+    let mut repo = UserRepository::new(store)?;
+    assert_eq!(repo.next_id(), UserId(erin.0 + 1)); // numbering carries on
+    assert_eq!(repo.get_by_username(&name("ERIN")?)?.0, erin);
+    assert_eq!(repo.get_by_email(&email("Robert@Example.com")?)?.0, UserId(2));
+    repo.reactivate(UserId(3))?;
+    println!("  found Erin and robert again; carol is active again");
+    let expected = all_users(repo.store());
+
+    println!("\n=== Compacting ===\n");
+    let mut store = repo.into_store();
+    store.compact()?;
+    show_file(&path)?;
+    assert_eq!(store.stale_lines(), 0);
+    assert!(!dir.join("users.jsonl.tmp").exists());
+    drop(store);
+    let store = JsonlStore::open(&path)?;
+    assert_eq!(all_users(&store), expected);
+    assert_eq!(store.migrated, 0); // every line is now v4
+
+    println!("\n=== Corrupt files ===\n");
+    let good = r#"{"v":3,"op":"put","id":1,"username":"alice","email":"alice@example.com","active":true}"#;
+    let cases: [(&str, &[&str], usize); 11] = [
+        ("not JSON", &[good, "hello"], 2),
+        ("cut short", &[good, r#"{"v":3,"op":"put","id":2,"user"#], 2),
+        ("missing field", &[good, r#"{"v":3,"op":"put","id":2,"username":"bob","active":true}"#], 2),
+        ("newer version", &[r#"{"v":9,"id":1}"#, good], 1),
+        ("bad username", &[good, "", r#"{"v":2,"id":2,"username":"9bob","email":"b@example.com","active":true}"#], 3),
+        ("unknown delete", &[good, r#"{"v":3,"op":"delete","id":7}"#], 2),
+        ("duplicate field", &[r#"{"v":3,"v":3}"#], 1),
+        ("wrong type", &[r#"{"v":2,"id":"1","username":"alice","email":"alice@example.com","active":true}"#], 1),
+        ("same username", &[good, r#"{"v":2,"id":2,"username":"ALICE","email":"b@example.com","active":true}"#], 2),
+        ("same email", &[good, r#"{"v":2,"id":2,"username":"bob","email":"Alice@example.com","active":true}"#], 2),
+        ("bad credential", &[r#"{"v":4,"op":"put","id":1,"username":"alice","email":"a@example.com","active":true,"credential":"$md5$x"}"#], 1),
+    ];
+    for (label, lines, bad_line) in cases {
+        // Each bad line ends with its newline: a finished write, not a torn one
+        fs::write(&path, lines.join("\n") + "\n")?;
+        match JsonlStore::open(&path) {
+            Ok(_) => panic!("{}: file should not load", label),
+            Err(e) => {
+                println!("  {:<16} -> {}", label, e);
+                assert!(matches!(e, StorageError::Corrupt { line, .. } if line == bad_line), "{}: {:?}", label, e);
+            }
+        }
+    }
+
+    // A name is only taken while its holder has it: user 1 renamed, so
+    // user 2 may use "alice" afterwards
+    let renamed = r#"{"v":3,"op":"put","id":1,"username":"alicia","email":"alice@example.com","active":true}"#;
+    let reused = r#"{"v":3,"op":"put","id":2,"username":"alice","email":"a2@example.com","active":true}"#;
+    fs::write(&path, [good, renamed, reused].join("\n"))?;
+    assert_eq!(JsonlStore::open(&path)?.users.len(), 2);
+
+    // A last line that is whole but lacks its newline still loads, and
+    // the next append does not run into it
+    fs::write(&path, good)?;
+    let mut store = JsonlStore::open(&path)?;
+    store.put(UserId(2), User { username: name("bob")?, email: email("bob@example.com")?, active: true, credential: None })?;
+    assert_eq!(JsonlStore::open(&path)?.users.len(), 2);
+
+    // A last line cut short by a crash is a torn write: open drops it
+    // from the file, and the next append starts on a clean line
+    let torn = r#"{"v":3,"op":"put","id":3,"username":"carol","#;
+    let before = fs::read_to_string(&path)?;
+    fs::write(&path, before.clone() + torn)?;
+    let mut store = JsonlStore::open(&path)?;
+    assert_eq!((store.users.len(), store.lines), (2, 2));
+    assert_eq!(fs::read_to_string(&path)?, before);
+    println!("  {:<16} -> dropped from the file; {} users load", "torn last line", store.users.len());
+    store.put(UserId(3), User { username: name("carol")?, email: email("carol@example.com")?, active: true, credential: None })?;
+    assert_eq!(JsonlStore::open(&path)?.users.len(), 3);
+
+    // Escapes survive a round trip
+    let tricky = write_record(&[("text", Value::Str("quote \" slash \\ tab \t bell \u{7} é".to_string()))]);
+    let parsed = JsonReader::parse(&tricky)?;
+    assert_eq!(parsed["text"], Value::Str("quote \" slash \\ tab \t bell \u{7} é".to_string()));
+    println!("\n  {}", tricky);
+
+    // Outside the BMP, JSON uses a UTF-16 surrogate pair; half a pair is an error
+    let emoji = JsonReader::parse(r#"{"text":"smile \ud83d\ude00"}"#)?;
+    assert_eq!(emoji["text"], Value::Str("smile \u{1f600}".to_string()));
+    for half in [r#"{"text":"\ud83d"}"#, r#"{"text":"\ude00"}"#, r#"{"text":"\ud83d\u0041"}"#] {
+        let err = JsonReader::parse(half).unwrap_err();
+        println!("  {} -> {}", half, err);
+        assert!(err.contains("surrogate pair"), "{}", err);
+    }
+
+    fs::remove_dir_all(&dir)?;
+    println!("\nAll checks passed");
+    Ok(())
+}
@@ -0,0 +1,451 @@
+// cargo run --example m5_structs_password
+//
+// Demonstrates PASSWORD CREDENTIALS for the User struct from m5_structs.
+//
+// A password is never stored. What is stored is a slow, salted hash:
+//
+//   PBKDF2-HMAC-SHA256(password, salt, iterations) -> 32 bytes
+//
+//   - The SALT (16 random bytes per user) makes equal passwords hash
+//     differently, so one precomputed table cannot crack every user.
+//   - The ITERATIONS make each guess expensive. They can be raised
+//     later: a user whose hash used fewer is REHASHED at their next
+//     successful login, the only time the password is known.
+//
+// The hash is saved as one PHC-style string, which carries its own
+// parameters, so old and new hashes can sit side by side:
+//
+//   $pbkdf2-sha256$i=600000$<salt, base64>$<hash, base64>
+//
+// SHA-256, HMAC and PBKDF2 are written out in common/credential.rs so the
+// example has no dependencies, and main checks them against published
+// test vectors (FIPS 180-2, RFC 4231, RFC 7914). In real code, use a
+// maintained crate. `cargo test --example m5_structs_password` runs the
+// same known answers as tests.
+//
+// The credential is a field of the shared User from common/users.rs
+// (m5_structs), and m5_structs_jsonl saves it with the user.
+//
+// Salts come from /dev/urandom, so on systems without it (Windows)
+// set_password returns CredentialError::Random.
+
+use std::fmt;
+
+#[path = "common/users.rs"]
+mod users;
+
+use users::credential::{
+    base64_decode, base64_encode, constant_time_eq, pbkdf2_sha256, random_salt, Credential, HashPolicy, HmacSha256,
+    PhcError, Sha256,
+};
+use users::User;
+
+// ---------------------------------------------------------------------
+// User
+// ---------------------------------------------------------------------
+
+#[derive(Debug)]
+enum CredentialError {
+    TooShort { min: usize },
+    TooLong { max: usize },
+    Random(String),
+    NoPassword,
+    WrongPassword,
+    Inactive,
+}
+
+impl fmt::Display for CredentialError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CredentialError::TooShort { min } => write!(f, "password needs at least {} characters", min),
+            CredentialError::TooLong { max } => write!(f, "password may be at most {} bytes", max),
+            CredentialError::Random(e) => write!(f, "could not make a salt: {}", e),
+            CredentialError::NoPassword => write!(f, "user has no password set"),
+            CredentialError::WrongPassword => write!(f, "wrong password"),
+            CredentialError::Inactive => write!(f, "user is inactive"),
+        }
+    }
+}
+
+impl std::error::Error for CredentialError {}
+
+#[derive(Debug, PartialEq)]
+enum Login {
+    Ok,
+    Rehashed, // ok, and the stored hash was upgraded
+}
+
+// Password methods for the User from m5_structs
+impl User {
+    const MIN_PASSWORD_CHARS: usize = 8;
+    const MAX_PASSWORD_BYTES: usize = 1024;
+
+    fn set_password(&mut self, password: &str, policy: &HashPolicy) -> Result<(), CredentialError> {
+        if password.chars().count() < Self::MIN_PASSWORD_CHARS {
+            return Err(CredentialError::TooShort { min: Self::MIN_PASSWORD_CHARS });
+        }
+        if password.len() > Self::MAX_PASSWORD_BYTES {
+            return Err(CredentialError::TooLong { max: Self::MAX_PASSWORD_BYTES });
+        }
+        self.credential = Some(new_credential(password, policy)?);
+        Ok(())
+    }
+
+    fn verify_password(&self, password: &str) -> bool {
+        self.credential.as_ref().is_some_and(|credential| credential.verify(password))
+    }
+
+    // Check the password, and upgrade the hash while the password is known.
+    // The rehash skips set_password's length rules: a password set before
+    // they were tightened must not lock its owner out.
+    fn login(&mut self, password: &str, policy: &HashPolicy) -> Result<Login, CredentialError> {
+        let credential = self.credential.as_ref().ok_or(CredentialError::NoPassword)?;
+        if !credential.verify(password) {
+            return Err(CredentialError::WrongPassword);
+        }
+        if !self.active {
+            return Err(CredentialError::Inactive);
+        }
+        // If no salt can be made the old hash stays, and the next login
+        // tries again: the password was right either way
+        if credential.needs_rehash(policy) {
+            if let Ok(upgraded) = new_credential(password, policy) {
+                self.credential = Some(upgraded);
+                return Ok(Login::Rehashed);
+            }
+        }
+        Ok(Login::Ok)
+    }
+}
+
+// A fresh salt and hash under the current policy
+fn new_credential(password: &str, policy: &HashPolicy) -> Result<Credential, CredentialError> {
+    let salt = random_salt(policy.salt_len()).map_err(|e| CredentialError::Random(e.to_string()))?;
+    let credential = Credential::derive(password, salt, policy.iterations(), policy.hash_len());
+    Ok(credential.expect("HashPolicy::new checks the limits derive does"))
+}
+
+// ---------------------------------------------------------------------
+// Checks
+// ---------------------------------------------------------------------
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn pbkdf2_hex(password: &str, salt: &str, iterations: u32, len: usize) -> String {
+    let mut out = vec![0; len];
+    pbkdf2_sha256(password.as_bytes(), salt.as_bytes(), iterations, &mut out);
+    hex(&out)
+}
+
+// Each check_* function asserts one family of published vectors and
+// returns how many it checked. main prints them; the tests run them too.
+
+fn check_sha256() -> usize {
+    // FIPS 180-2 examples
+    let sha = [
+        ("", "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"),
+        ("abc", "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+        (
+            "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+        ),
+    ];
+    for (input, expected) in sha {
+        assert_eq!(hex(&Sha256::digest(input.as_bytes())), expected, "SHA-256({:?})", input);
+    }
+    // A million 'a's, fed in uneven pieces to exercise the buffering
+    let mut sha = Sha256::new();
+    for piece in [1, 63, 64, 65, 999_807] {
+        sha.update(&vec![b'a'; piece]);
+    }
+    assert_eq!(hex(&sha.finish()), "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0");
+    4
+}
+
+fn check_hmac() -> usize {
+    // RFC 4231 test cases 2 and 6 (a key longer than one block)
+    let hmac = [
+        (b"Jefe".to_vec(), "what do ya want for nothing?", "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"),
+        (
+            vec![0xaa; 131],
+            "Test Using Larger Than Block-Size Key - Hash Key First",
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+        ),
+    ];
+    for (key, message, expected) in hmac {
+        assert_eq!(hex(&HmacSha256::new(&key).mac(message.as_bytes())), expected, "HMAC {:?}", message);
+    }
+    2
+}
+
+fn check_pbkdf2() -> usize {
+    // RFC 7914 section 11, and the widely used RFC 6070-style vectors
+    // for SHA-256. The 40-byte one needs a second, partial block.
+    let pbkdf2 = [
+        ("password", "salt", 1, 32, "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"),
+        ("password", "salt", 2, 32, "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43"),
+        ("password", "salt", 4096, 32, "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a"),
+        (
+            "passwordPASSWORDpassword",
+            "saltSALTsaltSALTsaltSALTsaltSALTsalt",
+            4096,
+            40,
+            "348c89dbcbd32b2f32d814b8116e84cf2b17347ebc1800181c4e2a1fb8dd53e1c635518c7dac47e9",
+        ),
+        (
+            "passwd",
+            "salt",
+            1,
+            64,
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc\
+             49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783",
+        ),
+        (
+            "Password",
+            "NaCl",
+            80_000,
+            64,
+            "4ddcd8f60b98be21830cee5ef22701f9641a4418d04c0414aeff08876b34ab56\
+             a1d425a1225833549adb841b51c9b3176a272bdebba1d078478f62b397f33c8d",
+        ),
+    ];
+    for (password, salt, iterations, len, expected) in pbkdf2 {
+        assert_eq!(pbkdf2_hex(password, salt, iterations, len), expected, "PBKDF2({:?}, {:?}, {})", password, salt, iterations);
+    }
+    pbkdf2.len()
+}
+
+fn check_base64() -> usize {
+    // RFC 4648 section 10, without the padding
+    let base64 = [("", ""), ("f", "Zg"), ("fo", "Zm8"), ("foo", "Zm9v"), ("foob", "Zm9vYg"), ("fooba", "Zm9vYmE"), ("foobar", "Zm9vYmFy")];
+    for (input, expected) in base64 {
+        assert_eq!(base64_encode(input.as_bytes()), expected);
+        assert_eq!(base64_decode(expected).as_deref(), Some(input.as_bytes()));
+    }
+    assert_eq!(base64_decode("Zh"), None); // "Zg" with stray low bits
+    assert_eq!(base64_decode("Zm9vY"), None);
+    assert_eq!(base64_decode("Zm9v!"), None);
+    base64.len()
+}
+
+// A PHC string with a fixed salt is a known answer too
+fn check_phc() -> usize {
+    let phc = "$pbkdf2-sha256$i=4096$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o";
+    let credential = Credential::derive("password", b"salt".to_vec(), 4096, 32).unwrap();
+    assert_eq!(credential.to_phc(), phc);
+    assert_eq!(Credential::parse_phc(phc), Ok(credential));
+    1
+}
+
+fn known_answers() {
+    println!("  SHA-256: {} FIPS 180-2 vectors", check_sha256());
+    println!("  HMAC-SHA256: {} RFC 4231 vectors", check_hmac());
+    println!("  PBKDF2-HMAC-SHA256: {} vectors", check_pbkdf2());
+    println!("  base64: {} RFC 4648 vectors", check_base64());
+    println!("  PHC string: {} fixed-salt vector", check_phc());
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Known answers ===\n");
+    known_answers();
+
+    println!("\n=== Setting and checking a password ===\n");
+    let policy = HashPolicy::new(2_000, 16, 32)?;
+    // User::new leaves `credential` empty
+    let mut user = User::new("someuser", "user@example.com")?;
+    assert!(!user.verify_password("anything")); // no credential, no match
+    assert!(matches!(user.login("anything", &policy), Err(CredentialError::NoPassword)));
+
+    user.set_password("correct horse battery", &policy)?;
+    let stored = user.credential.as_ref().unwrap().to_phc();
+    println!("  {} ({}) stores {}", user.username, user.email, stored);
+    println!("  debug output: {:?}", user.credential);
+    assert!(user.verify_password("correct horse battery"));
+    assert!(!user.verify_password("correct horse battery "));
+    assert!(!user.verify_password("Correct horse battery"));
+
+    // Same password, new salt: a different hash
+    let first = user.credential.clone().unwrap();
+    user.set_password("correct horse battery", &policy)?;
+    assert_ne!(first.salt, user.credential.as_ref().unwrap().salt);
+    assert_ne!(first.hash, user.credential.as_ref().unwrap().hash);
+
+    // Stored and loaded back, it still verifies
+    let loaded = Credential::parse_phc(&stored)?;
+    assert_eq!(loaded, first);
+    assert!(loaded.verify("correct horse battery"));
+
+    for short in ["short", "seven77"] {
+        let e = user.set_password(short, &policy).unwrap_err();
+        println!("  set_password({:?}) -> {}", short, e);
+    }
+    assert!(matches!(user.set_password(&"x".repeat(2000), &policy), Err(CredentialError::TooLong { .. })));
+
+    println!("\n=== Rehash on login ===\n");
+    // The hash above used 2,000 iterations. Raise the policy.
+    let stronger = HashPolicy::new(4_000, policy.salt_len(), policy.hash_len())?;
+    let wrong = user.login("wrong password", &stronger);
+    println!("  login with a wrong password -> {}", wrong.unwrap_err());
+    assert_eq!(user.credential.as_ref().unwrap().iterations, 2_000); // nothing upgraded
+
+    assert_eq!(user.login("correct horse battery", &stronger)?, Login::Rehashed);
+    println!("  login -> rehashed: {}", user.credential.as_ref().unwrap().to_phc());
+    assert_eq!(user.credential.as_ref().unwrap().iterations, 4_000);
+    assert_eq!(user.login("correct horse battery", &stronger)?, Login::Ok);
+    // A weaker policy never downgrades a hash
+    assert_eq!(user.login("correct horse battery", &policy)?, Login::Ok);
+
+    // A password from before the 8-character rule still logs in, and is
+    // rehashed as it is: login does not apply set_password's rules
+    let mut old = User::new("olduser", "old@example.com")?;
+    old.credential = Some(Credential::derive("hunter2", b"old salt".to_vec(), 1_000, 32)?);
+    assert!(old.set_password("hunter2", &stronger).is_err());
+    assert_eq!(old.login("hunter2", &stronger)?, Login::Rehashed);
+    assert!(old.verify_password("hunter2"));
+    assert_eq!(old.credential.as_ref().unwrap().iterations, 4_000);
+    println!("  login with a 7-character legacy password -> rehashed");
+
+    user.active = false;
+    let e = user.login("correct horse battery", &stronger).unwrap_err();
+    println!("  login while inactive -> {}", e);
+    assert!(matches!(e, CredentialError::Inactive));
+
+    println!("\n=== Reading stored strings ===\n");
+    let bad = [
+        "pbkdf2-sha256$i=1000$c2FsdA$xeR4",
+        "$argon2id$v=19$m=65536,t=3,p=4$c2FsdA$xeR4",
+        "$pbkdf2-sha256$i=0$c2FsdA$xeR4",
+        "$pbkdf2-sha256$rounds=1000$c2FsdA$xeR4",
+        "$pbkdf2-sha256$i=1000$c2Fsd$xeR4",
+        "$pbkdf2-sha256$i=1000$c2FsdA$",
+        "$pbkdf2-sha256$i=4000000000$c2FsdA$xeR4",
+    ];
+    for text in bad {
+        let e = Credential::parse_phc(text).unwrap_err();
+        println!("  {:<44} -> {}", text, e);
+    }
+    // Lengths are capped before anything is decoded
+    let huge = format!("$pbkdf2-sha256$i=1000$c2FsdA${}", "A".repeat(1_000_000));
+    let e = Credential::parse_phc(&huge).unwrap_err();
+    println!("  {:<44} -> {}", "(a 1,000,000-character hash)", e);
+    assert_eq!(e, PhcError::TooLong { part: "hash", max: Credential::MAX_HASH_LEN });
+
+    // A policy is held to the same limits, so whatever set_password
+    // stores can be read back
+    for (i, salt_len, hash_len) in [(0, 16, 32), (20_000_000, 16, 32), (1_000, 65, 32), (1_000, 16, 0)] {
+        let e = HashPolicy::new(i, salt_len, hash_len).unwrap_err();
+        println!("  HashPolicy::new({}, {}, {}) -> {}", i, salt_len, hash_len, e);
+    }
+
+    // Equal-length inputs that differ anywhere compare unequal
+    assert!(constant_time_eq(b"abcd", b"abcd"));
+    assert!(!constant_time_eq(b"abcd", b"abce"));
+    assert!(!constant_time_eq(b"abcd", b"xbcd"));
+    assert!(!constant_time_eq(b"abcd", b"abc"));
+
+    println!("\nAll checks passed");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_known_answers() {
+        assert_eq!(check_sha256(), 4);
+    }
+
+    #[test]
+    fn hmac_known_answers() {
+        assert_eq!(check_hmac(), 2);
+    }
+
+    #[test]
+    fn pbkdf2_known_answers() {
+        assert_eq!(check_pbkdf2(), 6);
+    }
+
+    #[test]
+    fn base64_known_answers() {
+        assert_eq!(check_base64(), 7);
+    }
+
+    #[test]
+    fn phc_known_answer() {
+        assert_eq!(check_phc(), 1);
+        let stored = Credential::parse_phc("$pbkdf2-sha256$i=4096$c2FsdA$xeR41ZKIyEGqUw22hFxMjZYok6ABzk4RpJY4c6qYE0o").unwrap();
+        assert!(stored.verify("password"));
+        assert!(!stored.verify("Password"));
+    }
+
+    #[test]
+    fn parse_phc_caps_iterations_and_lengths() {
+        let at_cap = format!("$pbkdf2-sha256$i={}$c2FsdA$xeR4", Credential::MAX_ITERATIONS);
+        assert_eq!(Credential::parse_phc(&at_cap).map(|c| c.iterations), Ok(Credential::MAX_ITERATIONS));
+        let above = format!("$pbkdf2-sha256$i={}$c2FsdA$xeR4", Credential::MAX_ITERATIONS + 1);
+        assert!(matches!(Credential::parse_phc(&above), Err(PhcError::BadParameter(_))));
+
+        // 64 bytes is 86 base64 characters; one more character is too long
+        let max_hash = base64_encode(&[7; Credential::MAX_HASH_LEN]);
+        assert_eq!(max_hash.len(), 86);
+        let ok = format!("$pbkdf2-sha256$i=1$c2FsdA${}", max_hash);
+        assert_eq!(Credential::parse_phc(&ok).map(|c| c.hash.len()), Ok(Credential::MAX_HASH_LEN));
+        let long = format!("$pbkdf2-sha256$i=1$c2FsdA${}A", max_hash);
+        assert_eq!(Credential::parse_phc(&long), Err(PhcError::TooLong { part: "hash", max: Credential::MAX_HASH_LEN }));
+        let salt = base64_encode(&[1; Credential::MAX_SALT_LEN + 1]);
+        let long_salt = format!("$pbkdf2-sha256$i=1${}$xeR4", salt);
+        assert_eq!(
+            Credential::parse_phc(&long_salt),
+            Err(PhcError::TooLong { part: "salt", max: Credential::MAX_SALT_LEN })
+        );
+    }
+
+    #[test]
+    fn login_rehashes_a_password_set_password_would_refuse() {
+        let policy = HashPolicy::new(10, 16, 32).unwrap();
+        let mut user = User::new("olduser", "old@example.com").unwrap();
+        user.credential = Some(Credential::derive("abc", b"salt".to_vec(), 1, 32).unwrap());
+        assert!(matches!(user.set_password("abc", &policy), Err(CredentialError::TooShort { .. })));
+        assert_eq!(user.login("abc", &policy).unwrap(), Login::Rehashed);
+        let credential = user.credential.as_ref().unwrap();
+        assert_eq!((credential.iterations, credential.salt.len()), (10, policy.salt_len()));
+        assert!(user.verify_password("abc"));
+        assert_eq!(user.login("abc", &policy).unwrap(), Login::Ok);
+    }
+
+    #[test]
+    fn an_empty_hash_never_verifies() {
+        let empty = Credential { iterations: 1, salt: b"salt".to_vec(), hash: Vec::new() };
+        assert!(!empty.verify(""));
+        assert!(!empty.verify("anything"));
+        assert_eq!(Credential::parse_phc("$pbkdf2-sha256$i=1$c2FsdA$"), Err(PhcError::EmptyHash));
+    }
+
+    #[test]
+    fn derive_and_policy_keep_to_the_parse_limits() {
+        let max_i = Credential::MAX_ITERATIONS;
+        assert!(matches!(Credential::derive("pw", vec![1; 16], 0, 32), Err(PhcError::BadParameter(_))));
+        assert_eq!(Credential::derive("pw", vec![1; 16], 1, 0), Err(PhcError::EmptyHash));
+        assert!(matches!(HashPolicy::new(0, 16, 32), Err(PhcError::BadParameter(_))));
+        assert!(matches!(HashPolicy::new(max_i + 1, 16, 32), Err(PhcError::BadParameter(_))));
+        assert_eq!(
+            HashPolicy::new(1, Credential::MAX_SALT_LEN + 1, 32).unwrap_err(),
+            PhcError::TooLong { part: "salt", max: Credential::MAX_SALT_LEN }
+        );
+        assert_eq!(
+            HashPolicy::new(1, 16, Credential::MAX_HASH_LEN + 1).unwrap_err(),
+            PhcError::TooLong { part: "hash", max: Credential::MAX_HASH_LEN }
+        );
+        assert_eq!(HashPolicy::new(1, 16, 0).unwrap_err(), PhcError::EmptyHash);
+
+        // The largest policy still round-trips through its PHC string
+        let policy = HashPolicy::new(1, Credential::MAX_SALT_LEN, Credential::MAX_HASH_LEN).unwrap();
+        let mut user = User::new("someuser", "user@example.com").unwrap();
+        user.set_password("correct horse battery", &policy).unwrap();
+        let stored = user.credential.as_ref().unwrap().to_phc();
+        assert_eq!(Credential::parse_phc(&stored).as_ref(), Ok(user.credential.as_ref().unwrap()));
+    }
+}
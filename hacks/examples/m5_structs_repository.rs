@@ -0,0 +1,260 @@
+// cargo run --example m5_structs_repository
+//
+// Demonstrates a REPOSITORY for the User struct from m5_structs.
+//
+// m5_structs builds each User by hand, and `..user1` quietly gives two
+// users the same username. Here all changes go through a
+// UserRepository, which enforces the rules:
+//
+//   - usernames and emails are unique, ignoring case
+//   - new users start active
+//   - deactivate needs an active user, and reactivate an inactive one
+//   - an inactive user cannot be updated
+//   - only an inactive user can be deleted. Until then, their username
+//     and email stay reserved.
+//
+// Storing the users is a separate UserStore trait, so the repository
+// works the same over any backend. Two are shown: a HashMap with
+// indexes, and a plain Vec that is searched one entry at a time.
+//
+// Username, EmailAddress and User are the validated types from
+// m5_structs_validated. They and the repository itself live in
+// examples/common, so m5_structs_jsonl can put a file behind them.
+
+use std::collections::HashMap;
+use std::fmt;
+
+#[path = "common/users.rs"]
+mod users;
+#[path = "common/repository.rs"]
+mod repository;
+
+use repository::{Lookup, RepositoryError, StorageError, UserId, UserRepository, UserStore, UserUpdate};
+use users::{EmailAddress, User, Username};
+
+// ---------------------------------------------------------------------
+// Backends
+// ---------------------------------------------------------------------
+
+// HashMap backend: users by id, plus an index for each unique field
+#[derive(Default)]
+struct MemoryStore {
+    users: HashMap<UserId, User>,
+    by_username: HashMap<String, UserId>,
+    by_email: HashMap<String, UserId>,
+}
+
+impl MemoryStore {
+    fn unindex(&mut self, user: &User) {
+        self.by_username.remove(user.username.folded());
+        self.by_email.remove(user.email.folded());
+    }
+}
+
+impl UserStore for MemoryStore {
+    fn get(&self, id: UserId) -> Result<Option<User>, StorageError> {
+        Ok(self.users.get(&id).cloned())
+    }
+
+    fn id_for_username(&self, folded: &str) -> Result<Option<UserId>, StorageError> {
+        Ok(self.by_username.get(folded).copied())
+    }
+
+    fn id_for_email(&self, folded: &str) -> Result<Option<UserId>, StorageError> {
+        Ok(self.by_email.get(folded).copied())
+    }
+
+    fn put(&mut self, id: UserId, user: User) -> Result<(), StorageError> {
+        // The old keys go first, in case the username or email changed
+        if let Some(old) = self.users.get(&id).cloned() {
+            self.unindex(&old);
+        }
+        self.by_username.insert(user.username.folded().to_string(), id);
+        self.by_email.insert(user.email.folded().to_string(), id);
+        self.users.insert(id, user);
+        Ok(())
+    }
+
+    fn remove(&mut self, id: UserId) -> Result<Option<User>, StorageError> {
+        let removed = self.users.remove(&id);
+        if let Some(user) = &removed {
+            self.unindex(user);
+        }
+        Ok(removed)
+    }
+
+    fn largest_id(&self) -> Result<Option<UserId>, StorageError> {
+        Ok(self.users.keys().max().copied())
+    }
+}
+
+// Vec backend: no indexes, every lookup is a scan. Fine for a handful of
+// users, and a check that the repository does not depend on the indexes.
+#[derive(Default)]
+struct VecStore {
+    users: Vec<(UserId, User)>,
+}
+
+impl VecStore {
+    fn find(&self, matches: impl Fn(&User) -> bool) -> Option<UserId> {
+        self.users.iter().find(|(_, user)| matches(user)).map(|(id, _)| *id)
+    }
+}
+
+impl UserStore for VecStore {
+    fn get(&self, id: UserId) -> Result<Option<User>, StorageError> {
+        Ok(self.users.iter().find(|(i, _)| *i == id).map(|(_, user)| user.clone()))
+    }
+
+    fn id_for_username(&self, folded: &str) -> Result<Option<UserId>, StorageError> {
+        Ok(self.find(|user| user.username.folded() == folded))
+    }
+
+    fn id_for_email(&self, folded: &str) -> Result<Option<UserId>, StorageError> {
+        Ok(self.find(|user| user.email.folded() == folded))
+    }
+
+    fn put(&mut self, id: UserId, user: User) -> Result<(), StorageError> {
+        match self.users.iter_mut().find(|(i, _)| *i == id) {
+            Some(entry) => entry.1 = user,
+            None => self.users.push((id, user)),
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self, id: UserId) -> Result<Option<User>, StorageError> {
+        let index = self.users.iter().position(|(i, _)| *i == id);
+        Ok(index.map(|index| self.users.remove(index).1))
+    }
+
+    fn largest_id(&self) -> Result<Option<UserId>, StorageError> {
+        Ok(self.users.iter().map(|(id, _)| *id).max())
+    }
+}
+
+// ---------------------------------------------------------------------
+// Demo
+// ---------------------------------------------------------------------
+
+type Error = Box<dyn std::error::Error>;
+
+fn name(text: &str) -> Result<Username, Error> {
+    Ok(Username::try_from(text)?)
+}
+
+fn email(text: &str) -> Result<EmailAddress, Error> {
+    Ok(EmailAddress::try_from(text)?)
+}
+
+// Print the outcome of a step that is expected to fail, and check which
+// error it was
+fn expect_error<T: fmt::Debug>(label: &str, result: Result<T, RepositoryError>, expected: RepositoryError) {
+    match result {
+        Ok(value) => panic!("{}: expected {:?}, got Ok({:?})", label, expected, value),
+        Err(e) => {
+            println!("  {:<32} -> {}", label, e);
+            assert_eq!(e, expected, "{}", label);
+        }
+    }
+}
+
+// The same script against any backend
+fn exercise<S: UserStore>(backend: &str, store: S) -> Result<(), Error> {
+    println!("=== {} ===\n", backend);
+    let mut repo = UserRepository::new(store)?;
+
+    let alice = repo.create(name("alice")?, email("alice@example.com")?)?;
+    let bob = repo.create(name("Bob")?, email("bob@example.com")?)?;
+    println!("  created alice as {} and Bob as {}", alice, bob);
+
+    // Lookups ignore case
+    let (id, user) = repo.get_by_username(&name("BOB")?)?;
+    assert_eq!((id, user.username.as_ref()), (bob, "Bob"));
+    assert_eq!(repo.get_by_email(&email("Alice@Example.COM")?)?.0, alice);
+
+    // What `..user1` did silently in m5_structs is now an error
+    expect_error(
+        "create ALICE",
+        repo.create(name("ALICE")?, email("other@example.com")?),
+        RepositoryError::DuplicateUsername { username: name("ALICE")?, taken_by: alice },
+    );
+    expect_error(
+        "create carol with Bob's email",
+        repo.create(name("carol")?, email("BOB@example.com")?),
+        RepositoryError::DuplicateEmail { email: email("BOB@example.com")?, taken_by: bob },
+    );
+
+    // Updates: changing your own name's case is fine, taking Bob's is not
+    let updated = repo.update(alice, UserUpdate { username: Some(name("Alice")?), ..Default::default() })?;
+    println!("  renamed {} to {}", alice, updated.username);
+    expect_error(
+        "update alice's email to Bob's",
+        repo.update(alice, UserUpdate { email: Some(email("bob@example.com")?), ..Default::default() }),
+        RepositoryError::DuplicateEmail { email: email("bob@example.com")?, taken_by: bob },
+    );
+    assert_eq!(repo.get(alice)?.email.as_ref(), "alice@example.com"); // a failed update changes nothing
+
+    // Renaming frees the old name for someone else
+    repo.update(bob, UserUpdate { username: Some(name("robert")?), email: Some(email("robert@example.com")?) })?;
+    let bob2 = repo.create(name("bob")?, email("bob@example.com")?)?;
+    println!("  Bob became robert; a new bob is {}", bob2);
+
+    // Lifecycle
+    expect_error("reactivate alice", repo.reactivate(alice), RepositoryError::AlreadyActive(alice));
+    expect_error("delete alice while active", repo.delete(alice), RepositoryError::StillActive(alice));
+    repo.deactivate(alice)?;
+    expect_error("deactivate alice again", repo.deactivate(alice), RepositoryError::AlreadyInactive(alice));
+    expect_error(
+        "update inactive alice",
+        repo.update(alice, UserUpdate { email: Some(email("new@example.com")?), ..Default::default() }),
+        RepositoryError::Inactive(alice),
+    );
+    // An inactive user still holds their name
+    expect_error(
+        "create alice while inactive",
+        repo.create(name("alice")?, email("alice2@example.com")?),
+        RepositoryError::DuplicateUsername { username: name("alice")?, taken_by: alice },
+    );
+    repo.reactivate(alice)?;
+    repo.deactivate(alice)?;
+    let deleted = repo.delete(alice)?;
+    println!("  deleted {} ({})", alice, deleted.username);
+
+    expect_error("get deleted alice", repo.get(alice), RepositoryError::NotFound(Lookup::Id(alice)));
+    expect_error(
+        "find alice@example.com",
+        repo.get_by_email(&email("alice@example.com")?),
+        RepositoryError::NotFound(Lookup::Email(email("alice@example.com")?)),
+    );
+
+    // Deleting frees the name, but this repository does not hand out
+    // the id again
+    let alice2 = repo.create(name("alice")?, email("alice@example.com")?)?;
+    assert!(alice2 > bob2);
+    println!("  alice signed up again as {}\n", alice2);
+
+    // A repository over a store that already has users keeps counting
+    let repo = UserRepository::new(repo.into_store())?;
+    assert_eq!(repo.next_id(), UserId(alice2.0 + 1));
+
+    // Ids are u64, and running out is an error rather than an overflow
+    let user = repo.get(alice2)?;
+    let mut store = repo.into_store();
+    store.put(UserId(u64::MAX - 1), user.clone())?;
+    let mut repo = UserRepository::new(store)?;
+    let refused = repo.create(name("dave")?, email("dave@example.com")?);
+    assert_eq!(refused, Err(RepositoryError::IdsExhausted(UserId(u64::MAX))));
+    let mut store = repo.into_store();
+    store.put(UserId(u64::MAX), user)?;
+    let refused = UserRepository::new(store).err();
+    println!("  a store holding id {} -> {}", u64::MAX, refused.as_ref().unwrap());
+    assert_eq!(refused, Some(RepositoryError::IdsExhausted(UserId(u64::MAX))));
+    Ok(())
+}
+
+fn main() -> Result<(), Error> {
+    exercise("MemoryStore (HashMap + indexes)", MemoryStore::default())?;
+    exercise("VecStore (linear scan)", VecStore::default())?;
+    println!("All checks passed");
+    Ok(())
+}
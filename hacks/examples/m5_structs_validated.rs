@@ -0,0 +1,138 @@
+// cargo run --example m5_structs_validated
+//
+// Demonstrates VALIDATED NEWTYPES for the User struct from m5_structs.
+//
+// There, `username` and `email` are both plain Strings: any text goes in,
+// and passing them in the wrong order still compiles. Here each field has
+// its own type, and the only way to make one is a constructor that checks
+// the text first (a "parse, don't validate" design):
+//
+//   Username      3-32 ASCII letters, digits, '_', '-', '.'
+//                 starts with a letter, no separator at the end or twice
+//   EmailAddress  basic RFC 5322 addr-spec: dot-atom "@" domain,
+//                 at most 64 bytes before the '@' and 254 in total
+//
+// Both keep the text as typed for Display, and compare case-insensitively:
+// "Alice" and "alice" are the same user.
+
+// The types live in common/users.rs, shared with m5_structs
+#[path = "common/users.rs"]
+mod users;
+
+use users::{EmailAddress, EmailError, User, UserError, Username};
+
+fn main() -> Result<(), UserError> {
+    println!("=== Building a User ===\n");
+    let user1 = User::new("Some_User", "Some.User@Example.com")?;
+    println!("User: {} ({})", user1.username, user1.email);
+    println!("Active: {}", user1.active);
+    println!("Compared as: {} / {}", user1.username.folded(), user1.email.folded());
+    println!("Mail domain: {}", user1.email.domain());
+
+    // The fields can also be built first and moved in
+    let username = Username::try_from("mutableuser")?;
+    let email: EmailAddress = "mutable@example.com".try_into()?;
+    let mut user3 = User { username, email, active: false, credential: None };
+    user3.active = true;
+    user3.email = EmailAddress::try_from("updated@example.com")?;
+    println!("\nUser3 (modified): {} - active: {}", user3.email, user3.active);
+
+    // The fields can no longer be swapped by mistake:
+    //     User { username: email, email: username, active: true }
+    //     ERROR: expected `Username`, found `EmailAddress`
+
+    // Struct update syntax still copies the username, so two users can
+    // share one. Keeping names unique is the job of a repository
+    // (see m5_structs_repository).
+    let user2 = User { email: EmailAddress::try_from("other@example.com")?, ..user1.clone() };
+    assert_eq!(user2.username, user1.username);
+
+    println!("\n=== Case-insensitive comparison ===\n");
+    let pairs = [("Alice", "alice"), ("ALICE.B", "alice.b"), ("alice", "alicia")];
+    for (a, b) in pairs {
+        let same = Username::parse(a)? == Username::parse(b)?;
+        println!("  {:<8} == {:<8} -> {}", a, b, same);
+    }
+    assert_eq!(EmailAddress::parse("Bob@Example.COM")?, EmailAddress::parse("bob@example.com")?);
+    assert_eq!(EmailAddress::parse("Bob@Example.COM")?.as_ref(), "Bob@Example.COM"); // display keeps the case
+
+    // Equal values must hash equally, or HashSet would keep both
+    let names: std::collections::HashSet<Username> =
+        ["Alice", "alice", "ALICE", "bob"].into_iter().map(Username::parse).collect::<Result<_, _>>()?;
+    assert_eq!(names.len(), 2);
+
+    println!("\n=== Usernames ===\n");
+    let usernames = [
+        ("someuser", true),
+        ("a.b-c_d", true),
+        ("x9", false),
+        ("averyveryveryverylongusernamethatgoesonandon", false),
+        ("9lives", false),
+        ("_hidden", false),
+        ("double..dot", false),
+        ("trailing-", false),
+        ("has space", false),
+        ("émile", false),
+    ];
+    for (text, valid) in usernames {
+        match Username::parse(text) {
+            Ok(name) => println!("  ok    {:<24} -> {}", text, name),
+            Err(e) => println!("  error {:<24} -> {}", shorten(text, 24), e),
+        }
+        assert_eq!(Username::parse(text).is_ok(), valid, "{}", text);
+    }
+
+    println!("\n=== Email addresses ===\n");
+    let long_local = format!("{}@example.com", "a".repeat(65));
+    let long_label = format!("me@{}.com", "d".repeat(64));
+    let emails = [
+        ("user@example.com", true),
+        ("first.last+tag@mail.example.co.uk", true),
+        ("o'brien@example.ie", true),
+        ("x@a-b.io", true),
+        ("", false),
+        ("no-at-sign.example.com", false),
+        ("@example.com", false),
+        ("user@", false),
+        (".user@example.com", false),
+        ("us..er@example.com", false),
+        ("user.@example.com", false),
+        ("two@at@example.com", false),
+        ("a b@example.com", false),
+        ("user@localhost", false),
+        ("user@-example.com", false),
+        ("user@example..com", false),
+        ("user@exa_mple.com", false),
+        ("user@127.0.0.1", false),
+        (long_local.as_str(), false),
+        (long_label.as_str(), false),
+    ];
+    for (text, valid) in emails {
+        match EmailAddress::parse(text) {
+            Ok(email) => println!("  ok    {:<34} -> {}", text, email),
+            Err(e) => println!("  error {:<34} -> {}", shorten(text, 34), e),
+        }
+        assert_eq!(EmailAddress::parse(text).is_ok(), valid, "{}", text);
+    }
+    assert_eq!(EmailAddress::parse("two@at@example.com"), Err(EmailError::InvalidLocalChar { ch: '@', index: 3 }));
+    assert!(matches!(EmailAddress::parse(&"a@b.".repeat(100)), Err(EmailError::TooLong { len: 400 })));
+
+    println!("\n=== Errors from User::new ===\n");
+    for (username, email) in [("ok_name", "broken@"), ("1bad", "fine@example.com")] {
+        if let Err(e) = User::new(username, email) {
+            println!("  {}", e);
+        }
+    }
+
+    println!("\nAll checks passed");
+    Ok(())
+}
+
+// Keep long test inputs from pushing the table apart
+fn shorten(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        format!("{}...", text.chars().take(width - 3).collect::<String>())
+    }
+}
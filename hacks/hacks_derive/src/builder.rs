@@ -0,0 +1,254 @@
+// #[derive(Builder)]
+//
+// Generates the same code m5_builder used to write by hand:
+//
+//   struct ServerBuilder { ... }          one slot per field
+//   impl ServerBuilder {
+//       fn new() -> Self                  defaults filled in
+//       fn host(mut self, ..) -> Self     one chainable setter per field
+//       fn build(self) -> Result<Server, ServerBuilderError>
+//   }
+//
+// build() only returns a Result when it can fail: when some field is
+// required or the struct has a validator. Otherwise it returns the value
+// itself, exactly like the hand-written builder did.
+//
+// Attributes:
+//   #[builder(validate = path)]     on the struct: fn(&T) -> Result<(), String>
+//   #[builder(default)]             field starts as Default::default()
+//   #[builder(default = expr)]      field starts as `expr`
+//   #[builder(setter = name)]       setter gets a different name
+//   #[builder(into)]                setter takes `impl Into<T>`
+//
+// Fields without a default are REQUIRED: build() fails if they were never set.
+// `String` fields always get `impl Into<String>` setters.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Expr, Fields, Ident, Path, Type};
+
+// What we learned about one field from its type and #[builder(...)]
+struct FieldSpec {
+    name: Ident,
+    ty: Type,
+    setter: Ident,
+    default: Option<Expr>,
+    into: bool,
+}
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(named) => &named.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "#[derive(Builder)] needs a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "#[derive(Builder)] only works on structs",
+            ))
+        }
+    };
+
+    let validate = struct_validator(&input)?;
+    let specs = fields
+        .iter()
+        .map(|field| {
+            let name = field.ident.clone().expect("named field");
+            let mut spec = FieldSpec {
+                setter: name.clone(),
+                name,
+                ty: field.ty.clone(),
+                default: None,
+                into: is_string(&field.ty),
+            };
+            for attr in field.attrs.iter().filter(|a| a.path().is_ident("builder")) {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("default") {
+                        spec.default = Some(if meta.input.peek(syn::Token![=]) {
+                            meta.value()?.parse()?
+                        } else {
+                            syn::parse_quote!(::core::default::Default::default())
+                        });
+                        Ok(())
+                    } else if meta.path.is_ident("setter") {
+                        spec.setter = meta.value()?.parse()?;
+                        Ok(())
+                    } else if meta.path.is_ident("into") {
+                        spec.into = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("expected `default`, `setter` or `into`"))
+                    }
+                })?;
+            }
+            Ok(spec)
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let vis = &input.vis;
+    let target = &input.ident;
+    let builder = format_ident!("{}Builder", target);
+    let error = format_ident!("{}BuilderError", target);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // Builder slots: defaulted fields hold T, required fields hold Option<T>
+    let slots = specs.iter().map(|f| {
+        let (name, ty) = (&f.name, &f.ty);
+        match f.default {
+            Some(_) => quote! { #name: #ty },
+            None => quote! { #name: ::core::option::Option<#ty> },
+        }
+    });
+
+    let initial = specs.iter().map(|f| {
+        let name = &f.name;
+        match &f.default {
+            Some(expr) => quote! { #name: #expr },
+            None => quote! { #name: ::core::option::Option::None },
+        }
+    });
+
+    let setters = specs.iter().map(|f| {
+        let (name, ty, setter) = (&f.name, &f.ty, &f.setter);
+        let (param, value) = if f.into {
+            (quote! { value: impl ::core::convert::Into<#ty> }, quote! { value.into() })
+        } else {
+            (quote! { value: #ty }, quote! { value })
+        };
+        let store = match f.default {
+            Some(_) => value,
+            None => quote! { ::core::option::Option::Some(#value) },
+        };
+        quote! {
+            #vis fn #setter(mut self, #param) -> Self {
+                self.#name = #store;
+                self
+            }
+        }
+    });
+
+    let assemble = specs.iter().map(|f| {
+        let name = &f.name;
+        let label = name.to_string();
+        match f.default {
+            Some(_) => quote! { #name: self.#name },
+            None => quote! {
+                #name: self.#name.ok_or(#error::MissingField(#label))?
+            },
+        }
+    });
+
+    let fallible = validate.is_some() || specs.iter().any(|f| f.default.is_none());
+
+    let build = if fallible {
+        let check = validate.map(|path| {
+            quote! { #path(&value).map_err(#error::Invalid)?; }
+        });
+        quote! {
+            #vis fn build(self) -> ::core::result::Result<#target #ty_generics, #error> {
+                let value = #target { #(#assemble,)* };
+                #check
+                ::core::result::Result::Ok(value)
+            }
+        }
+    } else {
+        quote! {
+            #vis fn build(self) -> #target #ty_generics {
+                #target { #(#assemble,)* }
+            }
+        }
+    };
+
+    // The error type only exists when build() can fail
+    let error_type = fallible.then(|| {
+        let error_doc = format!("Why `{}::build()` failed.", builder);
+        quote! {
+            #[doc = #error_doc]
+            #[allow(dead_code)]
+            #[derive(Debug, Clone, PartialEq)]
+            #vis enum #error {
+                MissingField(&'static str),
+                Invalid(::std::string::String),
+            }
+
+            impl ::core::fmt::Display for #error {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    match self {
+                        #error::MissingField(field) => {
+                            ::core::write!(f, "missing required field `{}`", field)
+                        }
+                        #error::Invalid(reason) => ::core::write!(f, "invalid configuration: {}", reason),
+                    }
+                }
+            }
+
+            impl ::std::error::Error for #error {}
+        }
+    });
+
+    Ok(quote! {
+        #error_type
+
+        #[allow(dead_code)]
+        #vis struct #builder #impl_generics #where_clause {
+            #(#slots,)*
+        }
+
+        #[allow(dead_code)]
+        impl #impl_generics #builder #ty_generics #where_clause {
+            #vis fn new() -> Self {
+                #builder { #(#initial,)* }
+            }
+
+            #(#setters)*
+
+            #build
+        }
+
+        impl #impl_generics ::core::default::Default for #builder #ty_generics #where_clause {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #impl_generics #target #ty_generics #where_clause {
+            #vis fn builder() -> #builder #ty_generics {
+                #builder::new()
+            }
+        }
+    })
+}
+
+// Read `#[builder(validate = path)]` from the struct itself
+fn struct_validator(input: &DeriveInput) -> syn::Result<Option<Path>> {
+    let mut validate = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("builder")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("validate") {
+                validate = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `validate = path`"))
+            }
+        })?;
+    }
+    Ok(validate)
+}
+
+fn is_string(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "String" && s.arguments.is_empty()),
+        _ => false,
+    }
+}
@@ -0,0 +1,251 @@
+// #[derive(Describe)]
+//
+// Generates two functions for schema introspection:
+//
+//   impl Point {
+//       fn describe() -> StructSchema                       // names, types, docs
+//       fn field_values(&self) -> Vec<(&'static str, String)>
+//   }
+//
+// A proc-macro crate cannot export ordinary types, so the user's crate
+// defines `StructSchema`, `FieldSchema` and `StructKind` (see
+// examples/m5_derive_describe.rs). The generated code refers to them as
+// `crate::StructSchema` etc., so they must live at the crate root - or say
+// where they are:
+//
+//   #[derive(Describe)]
+//   #[describe(schema = crate::reflect)]
+//   struct Point { ... }
+//
+// Tuple struct fields are named "0", "1", ...; unit structs have no fields.
+// Values are rendered with {:?}, so field_values() (and only field_values())
+// requires every field type to be Debug.
+
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use quote::quote;
+use syn::{Attribute, Data, DeriveInput, Expr, Fields, Lit, Meta, Path};
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "#[derive(Describe)] only works on structs",
+            ))
+        }
+    };
+
+    let schema = schema_path(&input)?;
+    let kind = match fields {
+        Fields::Named(_) => quote!(#schema::StructKind::Named),
+        Fields::Unnamed(_) => quote!(#schema::StructKind::Tuple),
+        Fields::Unit => quote!(#schema::StructKind::Unit),
+    };
+
+    // Field names as strings and as accessors (`self.x` or `self.0`)
+    let names: Vec<String> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| match &f.ident {
+            Some(ident) => ident.to_string(),
+            None => i.to_string(),
+        })
+        .collect();
+    let members: Vec<syn::Member> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| match &f.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(i.into()),
+        })
+        .collect();
+
+    let schemas = fields.iter().zip(&names).map(|(field, name)| {
+        let ty = &field.ty;
+        let ty_name = type_name(quote!(#ty));
+        let doc = doc_comment(&field.attrs);
+        quote! {
+            #schema::FieldSchema { name: #name, ty: #ty_name, doc: #doc }
+        }
+    });
+
+    // field_values() needs Debug on every field type; describe() does not,
+    // so the bounds go on a second impl block
+    let mut debug_generics = input.generics.clone();
+    let debug_where = debug_generics.make_where_clause();
+    for field in fields {
+        let ty = &field.ty;
+        debug_where
+            .predicates
+            .push(syn::parse_quote!(#ty: ::core::fmt::Debug));
+    }
+
+    let target = &input.ident;
+    let target_name = target.to_string();
+    let struct_doc = doc_comment(&input.attrs);
+    let vis = &input.vis;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let (debug_impl_generics, _, debug_where_clause) = debug_generics.split_for_impl();
+
+    Ok(quote! {
+        #[allow(dead_code)]
+        impl #impl_generics #target #ty_generics #where_clause {
+            #vis fn describe() -> #schema::StructSchema {
+                #schema::StructSchema {
+                    name: #target_name,
+                    kind: #kind,
+                    doc: #struct_doc,
+                    fields: ::std::vec![#(#schemas),*],
+                }
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #debug_impl_generics #target #ty_generics #debug_where_clause {
+            #vis fn field_values(&self) -> ::std::vec::Vec<(&'static str, ::std::string::String)> {
+                ::std::vec![#((#names, ::std::format!("{:?}", self.#members))),*]
+            }
+        }
+    })
+}
+
+// The module holding the schema types: `crate` unless
+// #[describe(schema = some::path)] says otherwise
+fn schema_path(input: &DeriveInput) -> syn::Result<Path> {
+    let mut schema: Path = syn::parse_quote!(crate);
+    for attr in &input.attrs {
+        if !attr.path().is_ident("describe") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("schema") {
+                schema = meta.value()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("expected `schema = path`"))
+            }
+        })?;
+    }
+    Ok(schema)
+}
+
+// `/// text` becomes #[doc = " text"]; join the lines of a doc comment
+fn doc_comment(attrs: &[Attribute]) -> String {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(expr) => match &expr.lit {
+                    Lit::Str(s) => Some(s.value().trim().to_string()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    lines.join("\n").trim().to_string()
+}
+
+// Render a type the way it is usually written: `Vec<String>`, `&'a str`,
+// `[u8; 4]`, `fn(u8) -> bool`. Walks the tokens and only puts a space
+// between two words or after `,` `;` `->` `+`.
+fn type_name(tokens: TokenStream) -> String {
+    let mut out = String::new();
+    write_tokens(tokens, &mut out);
+    out.trim_end().to_string()
+}
+
+fn write_tokens(tokens: TokenStream, out: &mut String) {
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::None => ("", ""),
+                };
+                // `mut [u8]`, but `Fn(u8)` and `Vec<[u8; 4]>`
+                let keyword = out.rsplit(|c: char| !c.is_alphanumeric() && c != '_').next();
+                if matches!(keyword, Some("mut" | "const" | "dyn" | "impl" | "as")) {
+                    out.push(' ');
+                }
+                out.push_str(open);
+                write_tokens(group.stream(), out);
+                if out.ends_with(' ') {
+                    out.pop(); // `(A, B, )` -> `(A, B,)`
+                }
+                out.push_str(close);
+            }
+            TokenTree::Ident(ident) => push_word(out, &ident.to_string()),
+            TokenTree::Literal(literal) => push_word(out, &literal.to_string()),
+            TokenTree::Punct(punct) => {
+                let c = punct.as_char();
+                match c {
+                    ',' | ';' => {
+                        out.push(c);
+                        out.push(' ');
+                    }
+                    '+' | '=' => {
+                        push_space(out);
+                        out.push(c);
+                        out.push(' ');
+                    }
+                    '-' if punct.spacing() == Spacing::Joint => {
+                        push_space(out);
+                        out.push(c);
+                    }
+                    '>' if out.ends_with('-') => out.push_str("> "),
+                    _ => out.push(c),
+                }
+            }
+        }
+    }
+}
+
+// Words (identifiers, keywords, literals) need a space between them
+fn push_word(out: &mut String, word: &str) {
+    if out.ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == ')') {
+        out.push(' ');
+    }
+    out.push_str(word);
+}
+
+fn push_space(out: &mut String) {
+    if !out.is_empty() && !out.ends_with(' ') {
+        out.push(' ');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::type_name;
+    use quote::quote;
+
+    #[test]
+    fn types_print_as_written() {
+        let cases = [
+            (quote!(i32), "i32"),
+            (quote!(Vec<String>), "Vec<String>"),
+            (quote!(HashMap<String, Vec<u8>>), "HashMap<String, Vec<u8>>"),
+            (quote!(&'a str), "&'a str"),
+            (quote!(&'static mut [u8]), "&'static mut [u8]"),
+            (quote!([u8; 4]), "[u8; 4]"),
+            (quote!((i32, String)), "(i32, String)"),
+            (quote!((i32,)), "(i32,)"),
+            (quote!(()), "()"),
+            (quote!(::std::vec::Vec<T>), "::std::vec::Vec<T>"),
+            (quote!(*const T), "*const T"),
+            (quote!(fn(u8) -> bool), "fn(u8) -> bool"),
+            (quote!(Box<dyn Fn(&str) -> Option<u8> + Send>), "Box<dyn Fn(&str) -> Option<u8> + Send>"),
+            (quote!(impl Iterator<Item = u8>), "impl Iterator<Item = u8>"),
+            (quote!(<T as Trait>::Output), "<T as Trait>::Output"),
+        ];
+        for (tokens, expected) in cases {
+            assert_eq!(type_name(tokens), expected);
+        }
+    }
+}
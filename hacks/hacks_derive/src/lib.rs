@@ -0,0 +1,30 @@
+//! Derive macros used by the `hacks` examples.
+//!
+//! Procedural macros must live in their own crate (`proc-macro = true`),
+//! so the examples depend on this crate through the workspace.
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod builder;
+mod describe;
+
+/// Generates a `<Name>Builder` with chainable setters and a checked `build()`.
+/// See `examples/m5_builder.rs` for the attributes it understands.
+#[proc_macro_derive(Builder, attributes(builder))]
+pub fn derive_builder(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    builder::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates `describe()` (field names, types and doc comments) and
+/// `field_values()`. See `examples/m5_derive_describe.rs`.
+#[proc_macro_derive(Describe, attributes(describe))]
+pub fn derive_describe(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    describe::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,10 @@
+// Compile tests for #[derive(Builder)]: tests/ui/pass_*.rs must build,
+// tests/ui/fail_*.rs must fail with the error in the matching .stderr.
+// After changing a message, regenerate with TRYBUILD=overwrite cargo test.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass_*.rs");
+    t.compile_fail("tests/ui/fail_*.rs");
+}
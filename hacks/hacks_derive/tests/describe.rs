@@ -0,0 +1,10 @@
+// Compile tests for #[derive(Describe)]: tests/ui/describe_pass_*.rs must
+// build, tests/ui/describe_fail_*.rs must fail with the error in the
+// matching .stderr. Regenerate with TRYBUILD=overwrite cargo test.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/describe_pass_*.rs");
+    t.compile_fail("tests/ui/describe_fail_*.rs");
+}
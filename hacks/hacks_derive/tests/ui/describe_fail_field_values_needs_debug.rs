@@ -0,0 +1,31 @@
+use hacks_derive::Describe;
+
+pub enum StructKind {
+    Named,
+    Tuple,
+    Unit,
+}
+
+pub struct FieldSchema {
+    pub name: &'static str,
+    pub ty: &'static str,
+    pub doc: &'static str,
+}
+
+pub struct StructSchema {
+    pub name: &'static str,
+    pub kind: StructKind,
+    pub doc: &'static str,
+    pub fields: Vec<FieldSchema>,
+}
+
+#[derive(Describe)]
+struct Wrapper<T> {
+    inner: T,
+}
+
+struct NotDebug;
+
+fn main() {
+    Wrapper { inner: NotDebug }.field_values();
+}
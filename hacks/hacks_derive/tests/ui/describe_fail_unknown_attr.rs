@@ -0,0 +1,9 @@
+use hacks_derive::Describe;
+
+#[derive(Describe)]
+#[describe(types = crate)]
+struct Point {
+    x: i32,
+}
+
+fn main() {}
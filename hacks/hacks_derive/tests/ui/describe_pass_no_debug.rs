@@ -0,0 +1,38 @@
+// describe() has no Debug bound; only field_values() needs one
+
+#![allow(dead_code)]
+
+use hacks_derive::Describe;
+
+#[derive(Debug, PartialEq)]
+pub enum StructKind {
+    Named,
+    Tuple,
+    Unit,
+}
+
+pub struct FieldSchema {
+    pub name: &'static str,
+    pub ty: &'static str,
+    pub doc: &'static str,
+}
+
+pub struct StructSchema {
+    pub name: &'static str,
+    pub kind: StructKind,
+    pub doc: &'static str,
+    pub fields: Vec<FieldSchema>,
+}
+
+#[derive(Describe)]
+struct Wrapper<T> {
+    inner: T,
+}
+
+struct NotDebug;
+
+fn main() {
+    let schema = Wrapper::<NotDebug>::describe();
+    assert_eq!(schema.fields[0].ty, "T");
+    assert_eq!(Wrapper { inner: 7 }.field_values(), [("inner", String::from("7"))]);
+}
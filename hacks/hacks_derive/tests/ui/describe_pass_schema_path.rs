@@ -0,0 +1,44 @@
+// The schema types may live anywhere; #[describe(schema = ...)] says where,
+// and nothing needs to be imported into the deriving module
+
+#![allow(dead_code)]
+
+mod reflect {
+    #[derive(Debug, PartialEq)]
+    pub enum StructKind {
+        Named,
+        Tuple,
+        Unit,
+    }
+
+    pub struct FieldSchema {
+        pub name: &'static str,
+        pub ty: &'static str,
+        pub doc: &'static str,
+    }
+
+    pub struct StructSchema {
+        pub name: &'static str,
+        pub kind: StructKind,
+        pub doc: &'static str,
+        pub fields: Vec<FieldSchema>,
+    }
+}
+
+mod model {
+    use hacks_derive::Describe;
+
+    /// Pixels.
+    #[derive(Describe)]
+    #[describe(schema = crate::reflect)]
+    pub struct Size(pub u32, pub [u8; 2]);
+}
+
+fn main() {
+    let schema = model::Size::describe();
+    assert_eq!((schema.name, schema.doc), ("Size", "Pixels."));
+    assert_eq!(schema.kind, reflect::StructKind::Tuple);
+    let types: Vec<_> = schema.fields.iter().map(|f| (f.name, f.ty, f.doc)).collect();
+    assert_eq!(types, [("0", "u32", ""), ("1", "[u8; 2]", "")]);
+    assert_eq!(model::Size(3, [1, 2]).field_values()[1], ("1", String::from("[1, 2]")));
+}
@@ -0,0 +1,9 @@
+use hacks_derive::Builder;
+
+#[derive(Builder)]
+enum Mode {
+    Fast,
+    Slow,
+}
+
+fn main() {}
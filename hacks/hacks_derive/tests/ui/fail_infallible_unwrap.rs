@@ -0,0 +1,12 @@
+// Without required fields or a validator there is no Result to unwrap
+use hacks_derive::Builder;
+
+#[derive(Builder)]
+struct Retry {
+    #[builder(default = 3)]
+    attempts: u32,
+}
+
+fn main() {
+    let _ = Retry::builder().build().unwrap();
+}
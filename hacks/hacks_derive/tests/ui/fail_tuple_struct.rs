@@ -0,0 +1,6 @@
+use hacks_derive::Builder;
+
+#[derive(Builder)]
+struct Point(i32, i32);
+
+fn main() {}
@@ -0,0 +1,9 @@
+use hacks_derive::Builder;
+
+#[derive(Builder)]
+struct Server {
+    #[builder(defualt = 8080)]
+    port: u16,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use hacks_derive::Builder;
+
+#[derive(Builder)]
+#[builder(check = Server::check)]
+struct Server {
+    port: u16,
+}
+
+fn main() {}
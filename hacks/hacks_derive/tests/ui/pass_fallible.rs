@@ -0,0 +1,33 @@
+// A required field and a validator: build() returns a Result
+use hacks_derive::Builder;
+
+#[derive(Builder)]
+#[builder(validate = Route::validate)]
+struct Route {
+    path: String,
+    #[builder(default = 80, setter = on_port)]
+    port: u16,
+}
+
+impl Route {
+    fn validate(&self) -> Result<(), String> {
+        if self.path.starts_with('/') {
+            Ok(())
+        } else {
+            Err(String::from("path must start with /"))
+        }
+    }
+}
+
+fn main() {
+    let route = Route::builder().path("/").on_port(8080).build().unwrap();
+    assert_eq!((route.path.as_str(), route.port), ("/", 8080));
+    assert_eq!(
+        Route::builder().build().err(),
+        Some(RouteBuilderError::MissingField("path"))
+    );
+    assert!(matches!(
+        Route::builder().path("x").build(),
+        Err(RouteBuilderError::Invalid(_))
+    ));
+}
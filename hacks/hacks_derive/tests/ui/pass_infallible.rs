@@ -0,0 +1,16 @@
+// All defaults and no validator: build() returns the struct itself
+use hacks_derive::Builder;
+
+#[derive(Builder)]
+struct Retry {
+    #[builder(default = 3)]
+    attempts: u32,
+    #[builder(default)]
+    label: String,
+}
+
+fn main() {
+    let retry: Retry = Retry::builder().attempts(5).label("x").build();
+    assert_eq!(retry.attempts, 5);
+    assert_eq!(retry.label, "x");
+}
@@ -0,0 +1,20 @@
+// A local `write!` must not leak into the generated Display impl
+use hacks_derive::Builder;
+
+#[allow(unused_macros)]
+macro_rules! write {
+    ($($tt:tt)*) => {
+        compile_error!("the derive used the caller's write!")
+    };
+}
+
+#[derive(Builder)]
+struct Server {
+    port: u16,
+}
+
+fn main() {
+    let err = ServerBuilder::new().build().err().unwrap();
+    assert_eq!(err.to_string(), "missing required field `port`");
+    assert_eq!(Server::builder().port(1).build().unwrap().port, 1);
+}
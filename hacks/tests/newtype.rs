@@ -0,0 +1,11 @@
+// Compile tests for m5_newtype: each tests/ui/newtype_fail_*.rs pulls in
+// the example with include! and adds one of the mistakes its comments say
+// will not compile. The error must match the .stderr next to it.
+// Regenerate with TRYBUILD=overwrite cargo test --test newtype.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/newtype_pass_example.rs");
+    t.compile_fail("tests/ui/newtype_fail_*.rs");
+}
@@ -0,0 +1,8 @@
+include!("../../examples/m5_newtype.rs");
+
+#[allow(dead_code)]
+fn bad_example() {
+    let width = Meters::new(5.0);
+    let area = calculate_area_meters(&Meters::new(10.0), &width);
+    calculate_area_meters(&area, &width);
+}
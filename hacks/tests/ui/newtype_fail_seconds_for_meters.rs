@@ -0,0 +1,8 @@
+include!("../../examples/m5_newtype.rs");
+
+#[allow(dead_code)]
+fn bad_example() {
+    let length = Meters::new(10.0);
+    let time = Seconds::new(60.0);
+    calculate_area_meters(&length, &time);
+}
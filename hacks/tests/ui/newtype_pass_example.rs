@@ -0,0 +1,2 @@
+// The example on its own builds, so any error below comes from the mistake
+include!("../../examples/m5_newtype.rs");